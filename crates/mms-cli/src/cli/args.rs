@@ -36,11 +36,21 @@ pub enum Commands {
         #[command(subcommand)]
         action: LectureAction,
     },
+    /// Track attendance of lectures and tutorials
+    Attendance {
+        #[command(subcommand)]
+        action: AttendanceAction,
+    },
     /// Manage exams
     Exam {
         #[command(subcommand)]
         action: ExamAction,
     },
+    /// List and restore archived courses
+    Archive {
+        #[command(subcommand)]
+        action: ArchiveAction,
+    },
     /// Manage holidays
     Holiday {
         #[command(subcommand)]
@@ -51,6 +61,21 @@ pub enum Commands {
         #[command(subcommand)]
         action: StatsAction,
     },
+    /// Show when the active course changed
+    History {
+        /// Course ID or shortname (only switches from or to this course)
+        #[arg(short = 'c', long)]
+        course: Option<String>,
+        /// First day to show (dd.mm.yyyy)
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day to show (dd.mm.yyyy)
+        #[arg(long)]
+        to: Option<String>,
+        /// Maximum number of entries
+        #[arg(short = 'n', long, default_value = "20")]
+        limit: u64,
+    },
     /// Manage background service
     Service {
         #[command(subcommand)]
@@ -58,11 +83,23 @@ pub enum Commands {
     },
     /// Check sync status between filesystem and database
     Status,
-    /// Sync filesystem with database (create missing folders)
+    /// Sync filesystem with database (create missing folders, register slides)
     Sync {
         /// Perform a dry-run without making changes
         #[arg(long)]
         dry_run: bool,
+        /// Rebuild semesters and courses in the database from their TOML files
+        #[arg(long, conflicts_with = "merge")]
+        import: bool,
+        /// Rename semester folders to the [general] semester_folder pattern
+        #[arg(long, conflicts_with_all = ["import", "merge"])]
+        normalize: bool,
+        /// Merge fields differing between TOML files and the database
+        #[arg(long)]
+        merge: bool,
+        /// Which side wins when merging (default: [sync] merge_strategy)
+        #[arg(long, requires = "merge", value_parser = ["toml-wins", "db-wins", "interactive"])]
+        strategy: Option<String>,
     },
     /// Show today's schedule (events and lectures)
     Today,
@@ -81,8 +118,11 @@ pub enum Commands {
 pub enum SemesterAction {
     /// Add a new semester
     Add {
-        /// Semester type: bachelor or master
-        #[arg(value_parser = ["bachelor", "b", "master", "m"])]
+        /// Semester type: bachelor, master, phd, exchange, preparatory or orientation
+        #[arg(value_parser = [
+            "bachelor", "b", "master", "m", "phd", "exchange", "ex",
+            "preparatory", "prep", "orientation", "o",
+        ])]
         type_: String,
         /// Semester number
         number: i32,
@@ -131,13 +171,90 @@ pub enum CourseAction {
     Grade {
         /// Course ID
         id: i64,
-        /// Grade (1.0-5.0 or 'none' to remove)
+        /// Grade in `grading.default_scheme` (e.g. 1,7 or 30L), 'none' to remove
         grade: String,
     },
     /// Set active course manually
+    ///
+    /// The background service keeps the course until --until, the next
+    /// scheduled slot, or `mms course set-active --auto`.
     SetActive {
+        /// Course ID
+        #[arg(required_unless_present = "auto", conflicts_with = "auto")]
+        id: Option<i64>,
+        /// Keep the course until then (HH:MM or dd.mm.yyyy HH:MM)
+        #[arg(long, conflicts_with = "auto")]
+        until: Option<String>,
+        /// Hand the active course back to the schedule
+        #[arg(long)]
+        auto: bool,
+    },
+    /// Mark a course as dropped and move it to the archive
+    Drop {
+        /// Course ID
+        id: i64,
+        /// Skip the confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Import a course folder from another folder structure
+    Import {
+        /// Course folder
+        path: std::path::PathBuf,
+        /// Course code (default: the folder name)
+        #[arg(long)]
+        code: Option<String>,
+        /// Course name (default: the folder name)
+        #[arg(long)]
+        name: Option<String>,
+        /// ECTS credits
+        #[arg(long)]
+        ects: i32,
+        /// Semester ID or code (default: current semester)
+        #[arg(short, long)]
+        semester: Option<String>,
+        /// Keep the folder in place (adopt), or copy or move it into the semester folder
+        #[arg(long, default_value = "adopt", value_parser = ["adopt", "copy", "move"])]
+        mode: String,
+    },
+    /// Turn an external course into a standard one
+    Convert {
+        /// Course ID
+        id: i64,
+        /// Copy the files instead of moving them
+        #[arg(long)]
+        copy: bool,
+        /// Skip the confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Move a completed course to the archive
+    Archive {
+        /// Course ID
+        id: i64,
+        /// Skip the confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Show or set how a course's exercise folders and files are named
+    Config {
         /// Course ID
         id: i64,
+        /// Exercise folder names, e.g. "ex{n:02d}" or "sheet_{n}"
+        #[arg(long, conflicts_with = "detect_naming")]
+        exercise_naming: Option<String>,
+        /// Assignment file names inside an exercise folder, e.g. "sheet{n}.pdf"
+        #[arg(long, conflicts_with = "detect_naming")]
+        exercise_file_naming: Option<String>,
+        /// Store the naming detected from the existing exercise folders
+        #[arg(long)]
+        detect_naming: bool,
+        /// Forget the configured naming and detect it on every scan
+        #[arg(
+            long,
+            conflicts_with_all = ["exercise_naming", "exercise_file_naming", "detect_naming"]
+        )]
+        reset_naming: bool,
     },
 }
 
@@ -280,6 +397,52 @@ pub enum LectureAction {
         #[arg(short = 'c', long)]
         course: Option<String>,
     },
+    /// Create lectures for elapsed scheduled lecture slots
+    Sync {
+        /// Course ID or shortname (optional - syncs all courses of the current semester)
+        #[arg(short = 'c', long)]
+        course: Option<String>,
+    },
+}
+
+// ============================================================================
+// Attendance Commands
+// ============================================================================
+
+#[derive(Subcommand, Debug)]
+pub enum AttendanceAction {
+    /// Mark attendance for a lecture or tutorial
+    Mark {
+        /// Attendance status
+        #[arg(value_parser = ["attended", "missed", "excused"])]
+        status: String,
+        /// Course ID or shortname (optional - infers from directory or uses active course)
+        #[arg(short = 'c', long)]
+        course: Option<String>,
+        /// Date (dd.mm.yyyy, defaults to today)
+        #[arg(short = 'd', long)]
+        date: Option<String>,
+        /// Schedule type (lecture, tutorium, ...) if several are scheduled that day
+        #[arg(short = 't', long = "type")]
+        schedule_type: Option<String>,
+    },
+    /// Show attendance records and statistics for a course
+    List {
+        /// Course ID or shortname (optional - infers from directory or uses active course)
+        #[arg(short = 'c', long)]
+        course: Option<String>,
+    },
+    /// Set the required attendance for a schedule type
+    Require {
+        /// Required attendance in percent (0-100)
+        percentage: f64,
+        /// Schedule type the requirement applies to
+        #[arg(short = 't', long = "type", default_value = "tutorium")]
+        schedule_type: String,
+        /// Course ID or shortname (optional - infers from directory or uses active course)
+        #[arg(short = 'c', long)]
+        course: Option<String>,
+    },
 }
 
 // ============================================================================
@@ -360,6 +523,28 @@ pub enum HolidayAction {
     },
 }
 
+// ============================================================================
+// Archive Commands
+// ============================================================================
+
+#[derive(Subcommand, Debug)]
+pub enum ArchiveAction {
+    /// List archived courses
+    List {
+        /// Only dropped courses
+        #[arg(long)]
+        dropped: bool,
+    },
+    /// Move an archived course back into a semester
+    Restore {
+        /// Course ID
+        id: i64,
+        /// Semester ID or code to restore into (default: the course's semester)
+        #[arg(short, long)]
+        semester: Option<String>,
+    },
+}
+
 // ============================================================================
 // Stats Commands
 // ============================================================================
@@ -372,6 +557,14 @@ pub enum StatsAction {
     Categories,
     /// Show overview of all statistics
     Overview,
+    /// Show attendance statistics of the current semester
+    Attendance,
+    /// Show hours per course per week compared with the ECTS workload
+    Time {
+        /// Number of weeks to show (including the current one)
+        #[arg(short, long, default_value_t = 4)]
+        weeks: u32,
+    },
 }
 
 // ============================================================================
@@ -380,9 +573,9 @@ pub enum StatsAction {
 
 #[derive(Subcommand, Debug)]
 pub enum ServiceAction {
-    /// Install the background service (launchd)
+    /// Install the background service (launchd on macOS, systemd on Linux)
     Install,
-    /// Uninstall the background service (launchd)
+    /// Uninstall the background service (launchd on macOS, systemd on Linux)
     Uninstall,
     /// Start the service
     Start,
@@ -392,6 +585,27 @@ pub enum ServiceAction {
     Status,
     /// Run the service in foreground (for debugging)
     Run,
+    /// Pause automatic course switching
+    Pause {
+        /// Resume after this many minutes (default: until resumed)
+        #[arg(short, long)]
+        minutes: Option<u64>,
+    },
+    /// Resume automatic course switching
+    Resume,
+    /// Re-check the schedule now
+    Recheck,
+    /// Reload the configuration
+    Reload,
+    /// Show the service logs
+    Logs {
+        /// Keep watching for new log lines
+        #[arg(short, long)]
+        follow: bool,
+        /// Only show lines since a time (e.g. 30m, 2h, 1d, 2025-10-20, "2025-10-20 14:00")
+        #[arg(short, long)]
+        since: Option<String>,
+    },
 }
 
 // ============================================================================
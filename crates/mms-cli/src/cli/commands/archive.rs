@@ -0,0 +1,75 @@
+use crate::cli::args::ArchiveAction;
+use anyhow::Result;
+use colored::Colorize;
+use mms_core::archive;
+use mms_core::config::Config;
+use mms_core::db::connection_seaorm;
+use mms_core::semester;
+
+pub async fn handle(action: ArchiveAction) -> Result<()> {
+    match action {
+        ArchiveAction::List { dropped } => handle_list(dropped).await,
+        ArchiveAction::Restore { id, semester } => handle_restore(id, semester).await,
+    }
+}
+
+async fn handle_list(only_dropped: bool) -> Result<()> {
+    let conn = connection_seaorm::get_connection().await?;
+    let courses = archive::list_archived_courses(&conn, only_dropped).await?;
+
+    if courses.is_empty() {
+        println!("{}", "No archived courses.".yellow());
+        return Ok(());
+    }
+
+    println!("{}", "Archived courses:".bold().underline());
+    println!();
+
+    for course in courses {
+        let semester = semester::get_semester_by_id(&conn, course.semester_id).await?;
+        let status = match course.dropped_at {
+            Some(at) => format!(
+                "dropped {}",
+                at.with_timezone(&chrono::Local).format("%d.%m.%Y")
+            ),
+            None if course.is_dropped => "dropped".to_string(),
+            None => "completed".to_string(),
+        };
+
+        println!(
+            "  [{}] {}/{} {} ({})",
+            course.id,
+            semester.code,
+            course.short_name.bold(),
+            course.name,
+            status.dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+async fn handle_restore(id: i64, semester_input: Option<String>) -> Result<()> {
+    let config = Config::load()?;
+    let conn = connection_seaorm::get_connection().await?;
+
+    let semester_id = match semester_input {
+        Some(input) => match input.parse::<i64>() {
+            Ok(id) => Some(id),
+            Err(_) => Some(semester::get_semester_by_code(&conn, &input).await?.id),
+        },
+        None => None,
+    };
+
+    let course = archive::restore_course(&config, &conn, id, semester_id).await?;
+    let semester = semester::get_semester_by_id(&conn, course.semester_id).await?;
+
+    println!("{}", "✓ Course restored!".green());
+    println!("  {} ({})", course.name.bold(), semester.code);
+    println!(
+        "  Moved to: {}",
+        course.directory_path.display().to_string().dimmed()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use crate::cli::args::AttendanceAction;
+use crate::cli::commands::course::print_attendance;
+use crate::cli::course_resolver::CourseResolver;
+use anyhow::Result;
+use colored::Colorize;
+use mms_core::attendance::{self, AttendanceStatus};
+use mms_core::db::connection_seaorm;
+use mms_core::db::queries;
+use mms_core::error::MmsError;
+use mms_core::utils::date_validation::parse_german_date;
+use std::str::FromStr;
+
+pub async fn handle(action: AttendanceAction) -> Result<()> {
+    match action {
+        AttendanceAction::Mark {
+            status,
+            course,
+            date,
+            schedule_type,
+        } => handle_mark(status, course, date, schedule_type).await,
+        AttendanceAction::List { course } => handle_list(course).await,
+        AttendanceAction::Require {
+            percentage,
+            schedule_type,
+            course,
+        } => handle_require(percentage, schedule_type, course).await,
+    }
+}
+
+async fn handle_mark(
+    status: String,
+    course: Option<String>,
+    date: Option<String>,
+    schedule_type: Option<String>,
+) -> Result<()> {
+    let status = AttendanceStatus::from_str(&status)
+        .map_err(|_| MmsError::InvalidAttendanceStatus(status.clone()))?;
+
+    let conn = connection_seaorm::get_connection().await?;
+    let course = CourseResolver::resolve(&conn, course).await?;
+
+    let now = chrono::Local::now().naive_local();
+    let date = match date {
+        Some(date) => parse_german_date(&date)?,
+        None => now.date(),
+    };
+
+    let record = attendance::mark_attendance(
+        &conn,
+        course.id,
+        date,
+        schedule_type.as_deref(),
+        status,
+        now,
+    )
+    .await?;
+
+    println!(
+        "{}",
+        format!(
+            "✓ Marked {} {} on {} {}-{} as {}",
+            course.short_name,
+            record.schedule_type,
+            record.date,
+            record.start_time,
+            record.end_time,
+            record.status
+        )
+        .green()
+    );
+
+    Ok(())
+}
+
+async fn handle_list(course: Option<String>) -> Result<()> {
+    let conn = connection_seaorm::get_connection().await?;
+    let course = CourseResolver::resolve(&conn, course).await?;
+    let records = queries::attendance::list_by_course(&conn, course.id).await?;
+
+    println!("{}", format!("Attendance of {}", course.name).bold());
+    println!();
+
+    if records.is_empty() {
+        println!("{}", "No attendance recorded yet.".yellow());
+    }
+
+    for record in records {
+        let status = match AttendanceStatus::from_str(&record.status) {
+            Ok(AttendanceStatus::Attended) => record.status.green(),
+            Ok(AttendanceStatus::Missed) => record.status.red(),
+            _ => record.status.yellow(),
+        };
+        let auto = if record.is_auto { "(auto)" } else { "" };
+
+        println!(
+            "  {}  {}-{}  {:<10} {} {}",
+            record.date,
+            record.start_time,
+            record.end_time,
+            record.schedule_type,
+            status,
+            auto.dimmed()
+        );
+    }
+
+    print_attendance(&conn, course.id).await?;
+
+    Ok(())
+}
+
+async fn handle_require(
+    percentage: f64,
+    schedule_type: String,
+    course: Option<String>,
+) -> Result<()> {
+    let conn = connection_seaorm::get_connection().await?;
+    let course = CourseResolver::resolve(&conn, course).await?;
+
+    let requirement =
+        attendance::set_attendance_requirement(&conn, course.id, &schedule_type, percentage)
+            .await?;
+
+    println!(
+        "{}",
+        format!(
+            "✓ {} requires {:.0}% attendance of {}",
+            course.short_name, requirement.required_percentage, requirement.schedule_type
+        )
+        .green()
+    );
+
+    Ok(())
+}
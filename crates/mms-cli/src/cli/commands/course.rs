@@ -1,149 +1,243 @@
 use crate::cli::args::CourseAction;
-use crate::cli::semester_resolver::SemesterResolver;
-use crate::cli::prompt_helpers::*;
+use anyhow::Result;
+use chrono::Utc;
+use colored::Colorize;
+use inquire::validator::Validation;
+use inquire::{Confirm, CustomType, Select, Text};
+use mms_core::archive;
+use mms_core::attendance;
 use mms_core::config::Config;
-use mms_core::db::connection;
-use mms_core::db::models::course::Course;
+use mms_core::course::{self, ImportMode, ImportedCourse};
+use mms_core::db::connection_seaorm;
 use mms_core::db::queries;
-use anyhow::Result;
 use mms_core::error::MmsError;
-use colored::Colorize;
-use dialoguer::{Input, Select, Confirm};
-
-pub fn handle(action: CourseAction) -> Result<()> {
+use mms_core::exercise;
+use mms_core::grade::{self, GradeBuilder, SchemeRegistry};
+use mms_core::history::{self, SwitchReason};
+use mms_core::semester;
+use mms_core::service::control::{self, ControlRequest};
+use mms_core::symlink::Symlinks;
+use mms_core::utils::date_validation::parse_until;
+use sea_orm::DatabaseConnection;
+use std::path::Path;
+
+pub async fn handle(action: CourseAction) -> Result<()> {
     match action {
-        CourseAction::Add => handle_add_interactive(),
-        CourseAction::List { semester } => handle_list(semester),
-        CourseAction::Show { id } => handle_show(id),
-        CourseAction::Edit { id } => handle_update(id),
-        CourseAction::Open { id } => handle_open(id),
-        CourseAction::Grade { id, grade } => handle_set_grade(id, grade),
-        CourseAction::SetActive { id } => handle_set_active(id),
+        CourseAction::List { semester } => handle_list(semester).await,
+        CourseAction::Show { id } => handle_show(id).await,
+        CourseAction::Open { id } => handle_open(id).await,
+        CourseAction::SetActive {
+            id: Some(id),
+            until,
+            ..
+        } => handle_set_active(id, until).await,
+        // clap requires the ID unless --auto is given
+        CourseAction::SetActive { id: None, .. } => handle_set_auto().await,
+        CourseAction::Import {
+            path,
+            code,
+            name,
+            ects,
+            semester,
+            mode,
+        } => handle_import(&path, code, name, ects, semester, &mode).await,
+        CourseAction::Convert { id, copy, yes } => handle_convert(id, copy, yes).await,
+        CourseAction::Drop { id, yes } => handle_archive(id, true, yes).await,
+        CourseAction::Archive { id, yes } => handle_archive(id, false, yes).await,
+        CourseAction::Config {
+            id,
+            exercise_naming,
+            exercise_file_naming,
+            detect_naming,
+            reset_naming,
+        } => {
+            handle_config(
+                id,
+                exercise_naming,
+                exercise_file_naming,
+                detect_naming,
+                reset_naming,
+            )
+            .await
+        }
+        CourseAction::Grade { id, grade } => handle_grade(id, &grade).await,
+        CourseAction::Add => handle_add_interactive().await,
+        CourseAction::Edit { id } => handle_update(id).await,
     }
 }
 
-fn handle_add_interactive() -> Result<()> {
-    let conn = connection::get()?;
-    let config = Config::load()?;
+async fn handle_add_interactive() -> Result<()> {
+    let conn = connection_seaorm::get_connection().await?;
 
     println!("{}", "Add New Course".bold().underline());
     println!();
 
-    // Get current semester or let user choose
-    let current_semester = queries::semester::get_current(&conn)?;
-    let all_semesters = queries::semester::list(&conn)?;
-
-    let semester = if all_semesters.is_empty() {
-        return Err(MmsError::Other("No semesters found. Create a semester first with 'mms semester add'".to_string()).into());
-    } else if let Some(current) = current_semester {
-        let use_current = Confirm::new()
-            .with_prompt(format!("Add to current semester ({})?", current.to_string()))
-            .default(true)
-            .interact()?;
-
-        if use_current {
-            current
-        } else {
-            // Let user select semester
-            let semester_names: Vec<String> = all_semesters.iter()
-                .map(|s| s.to_string())
-                .collect();
-            let selection = Select::new()
-                .with_prompt("Select semester")
-                .items(&semester_names)
-                .interact()?;
-            all_semesters[selection].clone()
+    // Use the current semester or let the user choose
+    let mut semesters = semester::list_semesters(&conn, false).await?;
+    if semesters.is_empty() {
+        anyhow::bail!("No semesters found. Create a semester first with 'mms semester add'");
+    }
+    let current = semesters.iter().position(|s| s.is_current);
+    let index = match current {
+        Some(index)
+            if Confirm::new(&format!(
+                "Add to current semester ({})?",
+                semesters[index].code
+            ))
+            .with_default(true)
+            .prompt()? =>
+        {
+            index
+        }
+        _ => {
+            let codes: Vec<String> = semesters.iter().map(|s| s.code.clone()).collect();
+            Select::new("Select semester:", codes).raw_prompt()?.index
         }
-    } else {
-        // No current semester, let user select
-        let semester_names: Vec<String> = all_semesters.iter()
-            .map(|s| s.to_string())
-            .collect();
-        let selection = Select::new()
-            .with_prompt("Select semester")
-            .items(&semester_names)
-            .interact()?;
-        all_semesters[selection].clone()
     };
-
-    let semester_id = semester.id.unwrap();
+    let semester = semesters.swap_remove(index);
 
     // Get course details
-    let name = prompt_text("Course name (e.g., Machine Learning)")?;
-    let short_name = prompt_text("Short name for folder (e.g., ML)")?;
-
-    // Get available categories from config
-    let config_cats: Vec<String> = config.categories.keys().cloned().collect();
-    let category = if !config_cats.is_empty() {
-        let selection = prompt_select("Category", &config_cats)?;
-        config_cats[selection].clone()
-    } else {
-        prompt_text("Category")?
-    };
-
-    let ects: i32 = prompt_text("ECTS credits")?.parse()
-        .map_err(|_| MmsError::Parse("Invalid ECTS value".to_string()))?;
-    let add_lecturer = prompt_confirm("Add lecturer?", false)?;
-
-    let lecturer = prompt_optional_text("Lecturer name", add_lecturer)?;
-
-    let add_platform = prompt_confirm("Add learning platform URL?", false)?;
-    let platform_url = prompt_optional_text("Platform URL (e.g., https://moodle.example.com/course/123)", add_platform)?;
-
-    let mut course = Course::new(semester_id, name.clone(), short_name.clone(), category.clone(), ects);
-
-    if let Some(lect) = lecturer {
-        course = course.with_lecturer(lect);
-    }
-
-    if let Some(url) = platform_url {
-        course = course.with_learning_platform(url);
-    }
+    let name = Text::new("Course name (e.g., Machine Learning):")
+        .with_validator(non_empty_validator)
+        .prompt()?;
+    let short_name = Text::new("Short name for folder (e.g., ml):")
+        .with_validator(non_empty_validator)
+        .prompt()?;
+    let ects = CustomType::<i32>::new("ECTS credits:")
+        .with_error_message("Please enter a whole number")
+        .prompt()?;
+    let lecturer = prompt_optional("Lecturer (optional):")?;
+    let platform_url =
+        prompt_optional("Platform URL (optional, e.g., https://moodle.example.com/course/123):")?;
 
     println!();
     println!("{}", "Summary:".bold());
     println!("  Name:      {}", name);
     println!("  Short:     {}", short_name);
-    println!("  Semester:  {}", semester.to_string());
-    println!("  Category:  {}", category);
+    println!("  Semester:  {}", semester.code);
     println!("  ECTS:      {}", ects);
     println!();
 
-    let confirm = Confirm::new()
-        .with_prompt("Create this course?")
-        .default(true)
-        .interact()?;
-
-    if !confirm {
+    if !Confirm::new("Create this course?")
+        .with_default(true)
+        .prompt()?
+    {
         println!("{}", "Cancelled.".yellow());
         return Ok(());
     }
 
-    let id = queries::course::insert(&conn, &course)?;
-
-    // Create course directory
-    let semester_path = config.general.university_base_path.join(semester.folder_name());
-    let course_path = semester_path.join(course.folder_name());
-    std::fs::create_dir_all(&course_path)?;
+    let created = course::create_course(
+        &conn,
+        semester.id,
+        course::CourseCode::new(short_name)?,
+        name,
+        course::Ects::new(ects)?,
+        lecturer,
+        None,
+        None,
+        None,
+        platform_url,
+        None,
+        None,
+        false,
+        None,
+        false,
+        None,
+    )
+    .await?;
 
     println!();
     println!("{}", "✓ Course created successfully!".green());
-    println!("  ID:   {}", id);
-    println!("  Path: {}", course_path.display().to_string().dimmed());
+    println!("  ID:   {}", created.id);
+    println!(
+        "  Path: {}",
+        created.directory_path.display().to_string().dimmed()
+    );
 
     Ok(())
 }
 
-fn handle_list(semester_input: Option<String>) -> Result<()> {
-    let conn = connection::get()?;
+async fn handle_update(id: i64) -> Result<()> {
+    let conn = connection_seaorm::get_connection().await?;
+    let course = course::get_course_by_id(&conn, id).await?;
+
+    println!("{}", "Update Course".bold().underline());
+    println!();
+    println!("Leave blank to keep current value.");
+    println!();
+
+    let name = prompt_change("Name", Some(&course.name))?;
+    let lecturer = prompt_change("Lecturer", course.lecturer.as_deref())?;
+    let platform_url = prompt_change("Platform URL", course.learning_platform_url.as_deref())?;
+    let ects = prompt_change("ECTS", Some(&course.ects.to_string()))?
+        .map(|input| {
+            input
+                .parse::<i32>()
+                .map_err(|_| MmsError::Parse("Invalid ECTS value".to_string()))
+                .and_then(course::Ects::new)
+        })
+        .transpose()?;
+
+    course::update_course(
+        &conn,
+        id,
+        None,
+        name,
+        ects,
+        lecturer,
+        None,
+        None,
+        None,
+        platform_url,
+        None,
+        None,
+        false,
+    )
+    .await?;
+
+    println!();
+    println!("{}", "✓ Course updated successfully!".green());
+    println!(
+        "{}",
+        format!("Use 'mms course grade {}' to set the grade.", id).dimmed()
+    );
 
-    let courses = if semester_input.is_some() {
-        let (semester_id, _) = SemesterResolver::resolve(semester_input)?;
-        queries::course::list_by_semester(&conn, semester_id)?
+    Ok(())
+}
+
+fn non_empty_validator(input: &str) -> Result<Validation, inquire::CustomUserError> {
+    if input.trim().is_empty() {
+        Ok(Validation::Invalid("This field cannot be empty".into()))
     } else {
-        queries::course::list(&conn)?
+        Ok(Validation::Valid)
+    }
+}
+
+/// Prompt for an optional value, `None` if left blank
+fn prompt_optional(label: &str) -> Result<Option<String>> {
+    let input = Text::new(label).prompt()?;
+    let input = input.trim();
+    Ok((!input.is_empty()).then(|| input.to_string()))
+}
+
+/// Prompt for a new value showing the current one, `None` if left blank
+fn prompt_change(label: &str, current: Option<&str>) -> Result<Option<String>> {
+    prompt_optional(&format!("{} [{}]:", label, current.unwrap_or("none")))
+}
+
+async fn handle_list(semester_input: Option<String>) -> Result<()> {
+    let conn = connection_seaorm::get_connection().await?;
+
+    let semester_id = match semester_input {
+        Some(input) => match input.parse::<i64>() {
+            Ok(id) => Some(id),
+            Err(_) => Some(semester::get_semester_by_code(&conn, &input).await?.id),
+        },
+        None => queries::semester::get_current(&conn).await?.map(|s| s.id),
     };
 
+    let courses = course::list_courses(&conn, semester_id, false, false).await?;
+
     if courses.is_empty() {
         println!("{}", "No courses found.".yellow());
         println!("Use 'mms course add' to create one.");
@@ -151,8 +245,7 @@ fn handle_list(semester_input: Option<String>) -> Result<()> {
     }
 
     // Get active course ID
-    let active = queries::active::get(&conn)?;
-    let active_course_id = active.course_id;
+    let active_course_id = queries::active::get(&conn).await?.course_id;
 
     println!("{}", "Courses:".bold().underline());
     println!();
@@ -165,49 +258,41 @@ fn handle_list(semester_input: Option<String>) -> Result<()> {
             if last_semester_id.is_some() {
                 println!();
             }
-            let semester = queries::semester::get_by_id(&conn, course.semester_id)?;
-            println!("{}", format!("{}:", semester.to_string()).bold());
+            let semester = semester::get_semester_by_id(&conn, course.semester_id).await?;
+            println!("{}", format!("{}:", semester.code).bold());
             last_semester_id = Some(course.semester_id);
         }
 
-        let grade_str = if let Some(grade) = course.grade {
-            format!(" - Grade: {:.1}", grade).green().to_string()
-        } else {
-            "".to_string()
-        };
-
-        let active_marker = if Some(course.id.unwrap()) == active_course_id {
+        let active_marker = if Some(course.id) == active_course_id {
             " ★".green()
         } else {
             "  ".normal()
         };
 
         println!(
-            "{} [{}] {} ({}) - {} ECTS{}",
+            "{} [{}] {} ({}) - {} ECTS",
             active_marker,
-            course.id.unwrap(),
+            course.id,
             course.name.bold(),
             course.short_name.dimmed(),
-            course.ects,
-            grade_str
+            course.ects
         );
     }
 
     Ok(())
 }
 
-fn handle_show(id: i64) -> Result<()> {
-    let conn = connection::get()?;
-    let course = queries::course::get_by_id(&conn, id)?;
-    let semester = queries::semester::get_by_id(&conn, course.semester_id)?;
+async fn handle_show(id: i64) -> Result<()> {
+    let conn = connection_seaorm::get_connection().await?;
+    let course = course::get_course_by_id(&conn, id).await?;
+    let semester = semester::get_semester_by_id(&conn, course.semester_id).await?;
 
     println!("{}", "Course Details:".bold().underline());
     println!();
-    println!("  ID:         {}", course.id.unwrap());
+    println!("  ID:         {}", course.id);
     println!("  Name:       {}", course.name.bold());
     println!("  Short Name: {}", course.short_name);
-    println!("  Semester:   {}", semester.to_string());
-    println!("  Category:   {}", course.category);
+    println!("  Semester:   {}", semester.code);
     println!("  ECTS:       {}", course.ects);
 
     if let Some(lecturer) = &course.lecturer {
@@ -222,220 +307,444 @@ fn handle_show(id: i64) -> Result<()> {
         println!("  Platform:   {}", url.dimmed());
     }
 
-    if let Some(grade) = course.grade {
-        println!("  Grade:      {}", format!("{:.1}", grade).green().bold());
-    }
+    println!(
+        "  Directory:  {}",
+        course.directory_path.display().to_string().dimmed()
+    );
 
-    println!("  Counts towards average: {}", if course.counts_towards_average { "Yes" } else { "No" });
+    print_attendance(&conn, course.id).await?;
 
     Ok(())
 }
 
-fn handle_update(id: i64) -> Result<()> {
-    let conn = connection::get()?;
-    let mut course = queries::course::get_by_id(&conn, id)?;
-
-    println!("{}", "Update Course".bold().underline());
-    println!();
-    println!("Leave blank to keep current value.");
-    println!();
-
-    // Update name
-    let name_input: String = Input::new()
-        .with_prompt(format!("Name [{}]", course.name))
-        .allow_empty(true)
-        .interact_text()?;
-    if !name_input.is_empty() {
-        course.name = name_input;
-    }
-
-    // Update lecturer
-    let lecturer_prompt = format!(
-        "Lecturer [{}] (type 'none' to remove)",
-        course.lecturer.as_deref().unwrap_or("none")
-    );
-    let lecturer_input: String = Input::new()
-        .with_prompt(lecturer_prompt)
-        .allow_empty(true)
-        .interact_text()?;
-    if !lecturer_input.is_empty() {
-        if lecturer_input.to_lowercase() == "none" {
-            course.lecturer = None;
-        } else {
-            course.lecturer = Some(lecturer_input);
-        }
-    }
-
-    // Update platform URL
-    let platform_prompt = format!(
-        "Platform URL [{}] (type 'none' to remove)",
-        course.learning_platform_url.as_deref().unwrap_or("none")
-    );
-    let platform_input: String = Input::new()
-        .with_prompt(platform_prompt)
-        .allow_empty(true)
-        .interact_text()?;
-    if !platform_input.is_empty() {
-        if platform_input.to_lowercase() == "none" {
-            course.learning_platform_url = None;
-        } else {
-            course.learning_platform_url = Some(platform_input);
+/// Record the final grade of a course in the configured default scheme
+///
+/// `none` removes the final grade.
+async fn handle_grade(id: i64, input: &str) -> Result<()> {
+    let conn = connection_seaorm::get_connection().await?;
+    let course = course::get_course_by_id(&conn, id).await?;
+    let previous = grade::get_final_grade(&conn, course.id).await?;
+
+    if input.trim().eq_ignore_ascii_case("none") {
+        match previous {
+            Some(previous) => {
+                grade::delete_grade(&conn, previous.id).await?;
+                println!("{}", "✓ Grade removed!".green());
+            }
+            None => println!("{}", "No grade recorded.".yellow()),
         }
+        return Ok(());
     }
 
-    // Update category
     let config = Config::load()?;
-    let config_cats: Vec<String> = config.categories.keys().cloned().collect();
-
-    let category_input: String = if !config_cats.is_empty() {
-        let mut items = config_cats.clone();
-        items.push(format!("Keep current ({})", course.category));
-        items.push("Enter custom...".to_string());
-
-        let selection = Select::new()
-            .with_prompt("Category")
-            .items(&items)
-            .default(items.len() - 2)
-            .interact()?;
-
-        if selection == items.len() - 2 {
-            // Keep current
-            String::new()
-        } else if selection == items.len() - 1 {
-            // Custom input
-            Input::new()
-                .with_prompt(format!("Category [{}]", course.category))
-                .allow_empty(true)
-                .interact_text()?
-        } else {
-            config_cats[selection].clone()
-        }
-    } else {
-        Input::new()
-            .with_prompt(format!("Category [{}]", course.category))
-            .allow_empty(true)
-            .interact_text()?
-    };
-
-    if !category_input.is_empty() {
-        course.category = category_input;
+    let scheme_name = config
+        .grading
+        .as_ref()
+        .map_or("german", |grading| grading.default_scheme.as_str());
+    let scheme = SchemeRegistry::load()?.resolve(scheme_name)?;
+    let builder = GradeBuilder::parse(course.id, input, scheme)?.as_final(true);
+
+    // The new grade replaces the previous final one
+    if let Some(previous) = previous {
+        grade::update_grade(&conn, previous.id, None, None, Some(false)).await?;
     }
-
-    // Update ECTS
-    let ects_input: String = Input::new()
-        .with_prompt(format!("ECTS [{}]", course.ects))
-        .allow_empty(true)
-        .interact_text()?;
-    if !ects_input.is_empty() {
-        course.ects = ects_input.parse::<i32>()
-            .map_err(|_| MmsError::Parse("Invalid ECTS value".to_string()))?;
-    }
-
-    // Update grade
-    let grade_prompt = format!(
-        "Grade [{}] (type 'none' to remove)",
-        course.grade.map(|g| format!("{:.1}", g)).unwrap_or_else(|| "none".to_string())
+    let recorded = builder.record(&conn).await?;
+
+    println!(
+        "{} {}: {} ({}, {})",
+        "✓ Grade set!".green(),
+        course.name.bold(),
+        recorded.grading_scheme.format_grade(recorded.grade),
+        recorded.grading_scheme,
+        if recorded.passed { "passed" } else { "failed" }
     );
-    let grade_input: String = Input::new()
-        .with_prompt(grade_prompt)
-        .allow_empty(true)
-        .interact_text()?;
-    if !grade_input.is_empty() {
-        if grade_input.to_lowercase() == "none" {
-            course.grade = None;
-        } else {
-            course.grade = Some(grade_input.parse::<f32>()
-                .map_err(|_| MmsError::Parse("Invalid grade".to_string()))?);
-        }
-    }
-
-    queries::course::update(&conn, &course)?;
-
-    println!();
-    println!("{}", "✓ Course updated successfully!".green());
 
     Ok(())
 }
 
-fn handle_set_grade(id: i64, grade: String) -> Result<()> {
-    let conn = connection::get()?;
-    let mut course = queries::course::get_by_id(&conn, id)?;
+/// Print the attendance section of a course (nothing if there is nothing to show)
+pub async fn print_attendance(conn: &DatabaseConnection, course_id: i64) -> Result<()> {
+    let stats =
+        attendance::attendance_stats(conn, course_id, chrono::Local::now().naive_local()).await?;
 
-    if grade.to_lowercase() == "none" {
-        course.grade = None;
-        queries::course::update(&conn, &course)?;
-        println!("{}", "✓ Grade removed!".green());
-        println!("  {}", course.name.bold());
-    } else {
-        let grade_value = grade.parse::<f32>()
-            .map_err(|_| MmsError::Parse(format!("Invalid grade: '{}'. Use a number or 'none'", grade)))?;
-        course.grade = Some(grade_value);
-        queries::course::update(&conn, &course)?;
-        println!("{}", "✓ Grade updated!".green());
-        println!("  {}: {}", course.name.bold(), format!("{:.1}", grade_value).green());
+    if stats.is_empty() {
+        return Ok(());
+    }
+
+    println!();
+    println!("  {}", "Attendance:".bold());
+
+    for stat in stats {
+        let rate = stat
+            .percentage()
+            .map(|p| format!("{:.0}%", p))
+            .unwrap_or_else(|| "-".to_string());
+
+        let requirement = match (stat.required_percentage, stat.meets_requirement()) {
+            (Some(required), Some(true)) => format!("(required {:.0}%)", required).green(),
+            (Some(required), _) => format!("(required {:.0}%)", required).red(),
+            (None, _) => "".normal(),
+        };
+
+        println!(
+            "    {:<10} {:>4}  {} attended, {} missed, {} excused, {} unmarked {}",
+            stat.schedule_type,
+            rate,
+            stat.attended,
+            stat.missed,
+            stat.excused,
+            stat.unmarked,
+            requirement
+        );
     }
 
     Ok(())
 }
 
-fn handle_open(id: i64) -> Result<()> {
-    let conn = connection::get()?;
-    let course = queries::course::get_by_id(&conn, id)?;
+async fn handle_open(id: i64) -> Result<()> {
+    let conn = connection_seaorm::get_connection().await?;
+    let course = course::get_course_by_id(&conn, id).await?;
 
     if let Some(url) = &course.learning_platform_url {
         println!("Opening {}...", url);
 
         #[cfg(target_os = "macos")]
         {
-            std::process::Command::new("open")
-                .arg(url)
-                .spawn()?;
+            std::process::Command::new("open").arg(url).spawn()?;
         }
 
         #[cfg(target_os = "linux")]
         {
-            std::process::Command::new("xdg-open")
-                .arg(url)
-                .spawn()?;
+            std::process::Command::new("xdg-open").arg(url).spawn()?;
         }
 
         #[cfg(target_os = "windows")]
         {
             std::process::Command::new("cmd")
-                .args(&["/C", "start", url])
+                .args(["/C", "start", url])
                 .spawn()?;
         }
 
         println!("{}", "✓ Opened in browser".green());
     } else {
-        println!("{}", "No learning platform URL set for this course.".yellow());
+        println!(
+            "{}",
+            "No learning platform URL set for this course.".yellow()
+        );
         println!("Use 'mms course edit {}' to add one.", id);
     }
 
     Ok(())
 }
 
-fn handle_set_active(id: i64) -> Result<()> {
-    let conn = connection::get()?;
+async fn handle_set_active(id: i64, until: Option<String>) -> Result<()> {
+    let conn = connection_seaorm::get_connection().await?;
+    let until = until
+        .map(|until| parse_until(&until, chrono::Local::now().naive_local()))
+        .transpose()?;
 
     // Verify course exists
-    let course = queries::course::get_by_id(&conn, id)?;
-    let semester = queries::semester::get_by_id(&conn, course.semester_id)?;
+    let course = queries::course::get_by_id(&conn, id).await?;
+    if course.is_archived {
+        anyhow::bail!(
+            "{} is archived. Use 'mms archive restore {}' first.",
+            course.name,
+            id
+        );
+    }
+    let semester = queries::semester::get_by_id(&conn, course.semester_id).await?;
 
-    // Set as active (includes setting active semester)
-    queries::active::set_active_course(&conn, id, course.semester_id)?;
+    let previous = queries::active::get(&conn).await?.course_id;
 
-    // Update both symlinks
-    mms_core::symlink::update_semester_symlink(&semester.folder_name())?;
-    mms_core::symlink::update_course_symlink(&semester.folder_name(), course.folder_name())?;
+    // Set as active (includes setting active semester) and keep it
+    queries::active::set_manual_course(&conn, id, course.semester_id, until).await?;
+    history::record_switch(&conn, previous, Some(id), SwitchReason::Manual, Utc::now()).await?;
+
+    // Update the semester, course and context symlinks
+    let symlinks = Symlinks::from_config(&Config::load()?)?;
+    symlinks.set_course(
+        Path::new(&semester.directory_path),
+        Path::new(&course.directory_path),
+    )?;
+    symlinks.set_course_context(&conn, &course).await?;
 
     println!("{}", "✓ Active course set!".green());
     println!("  {}", course.name.bold());
-    println!("  {}", semester.to_string().dimmed());
     println!();
     println!("Symlinks updated:");
-    println!("  ~/cs -> {}", semester.folder_name().dimmed());
-    println!("  ~/cc -> {}/{}", semester.folder_name().dimmed(), course.folder_name().dimmed());
+    for status in symlinks.check() {
+        if let Some(target) = status.target {
+            println!(
+                "  {} -> {}",
+                status.path.display(),
+                target.display().to_string().dimmed()
+            );
+        }
+    }
+    println!();
+    match until {
+        Some(until) => println!(
+            "Kept until {} or the next scheduled slot.",
+            until.with_timezone(&chrono::Local).format("%d.%m.%Y %H:%M")
+        ),
+        None => println!("Kept until the next scheduled slot."),
+    }
+    println!(
+        "{}",
+        "Use 'mms course set-active --auto' to follow the schedule again.".dimmed()
+    );
+
+    Ok(())
+}
+
+async fn handle_import(
+    path: &Path,
+    code: Option<String>,
+    name: Option<String>,
+    ects: i32,
+    semester_input: Option<String>,
+    mode: &str,
+) -> Result<()> {
+    let config = Config::load()?;
+    let conn = connection_seaorm::get_connection().await?;
+
+    let semester_id = match semester_input {
+        Some(input) => match input.parse::<i64>() {
+            Ok(id) => id,
+            Err(_) => semester::get_semester_by_code(&conn, &input).await?.id,
+        },
+        None => match queries::semester::get_current(&conn).await? {
+            Some(semester) => semester.id,
+            None => anyhow::bail!("No current semester. Use --semester to choose one."),
+        },
+    };
+
+    let folder_name = path
+        .canonicalize()?
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let code = code.unwrap_or_else(|| folder_name.to_lowercase().replace(' ', "-"));
+    let mode = mode
+        .parse::<ImportMode>()
+        .map_err(|_| anyhow::anyhow!("Invalid import mode: {}", mode))?;
+
+    let imported = course::import_course(
+        &config,
+        &conn,
+        path,
+        semester_id,
+        course::CourseCode::new(code)?,
+        name.unwrap_or(folder_name),
+        course::Ects::new(ects)?,
+        mode,
+    )
+    .await?;
+
+    println!("{}", "✓ Course imported!".green());
+    print_imported(&imported);
+    if imported.course.is_external {
+        println!();
+        println!(
+            "{}",
+            format!(
+                "Use 'mms course convert {}' to move it into the semester folder.",
+                imported.course.id
+            )
+            .dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+async fn handle_convert(id: i64, copy: bool, yes: bool) -> Result<()> {
+    let config = Config::load()?;
+    let conn = connection_seaorm::get_connection().await?;
+    let course = queries::course::get_by_id(&conn, id).await?;
+
+    let action = if copy { "Copy" } else { "Move" };
+    let confirmed = yes
+        || Confirm::new(&format!(
+            "{} {} into the standard structure?",
+            action, course.name
+        ))
+        .with_default(true)
+        .prompt()?;
+    if !confirmed {
+        println!("{}", "Cancelled.".yellow());
+        return Ok(());
+    }
+
+    let converted = course::convert_course(&config, &conn, id, !copy).await?;
+
+    println!("{}", "✓ Course is now a standard course!".green());
+    print_imported(&converted);
+
+    Ok(())
+}
+
+/// Print where an imported course lives and what was found in it
+fn print_imported(imported: &ImportedCourse) {
+    let course = &imported.course;
+    let layout = &imported.layout;
+
+    println!("  {} ({})", course.name.bold(), course.short_name.dimmed());
+    println!(
+        "  Directory: {}",
+        course.directory_path.display().to_string().dimmed()
+    );
+    if let Some(dir) = &layout.slides_dir {
+        println!("  Slides:    {}", dir.display());
+    }
+    if let Some(dir) = &layout.exercises_dir {
+        println!("  Exercises: {}", dir.display());
+    }
+    if let Some(url) = &layout.git_remote_url {
+        println!("  Git:       {}", url);
+    } else if layout.has_git_repo {
+        println!("  Git:       local repository");
+    }
+}
+
+async fn handle_config(
+    id: i64,
+    exercise_naming: Option<String>,
+    exercise_file_naming: Option<String>,
+    detect_naming: bool,
+    reset_naming: bool,
+) -> Result<()> {
+    let conn = connection_seaorm::get_connection().await?;
+    let course = queries::course::get_by_id(&conn, id).await?;
+
+    let naming = if reset_naming {
+        Some((None, None))
+    } else if detect_naming {
+        let Some(detected) = exercise::detect_naming(Path::new(&course.directory_path))? else {
+            println!("{}", "No numbered exercise folders found.".yellow());
+            return Ok(());
+        };
+        let files = detected.files.as_ref().map(|files| files.to_string());
+        let confirmed = Confirm::new(&format!(
+            "Detected naming scheme: {}{}. Use this?",
+            detected.folders,
+            files
+                .as_deref()
+                .map(|f| format!(" / {}", f))
+                .unwrap_or_default()
+        ))
+        .with_default(true)
+        .prompt()?;
+        if !confirmed {
+            println!("{}", "Cancelled.".yellow());
+            return Ok(());
+        }
+        Some((Some(detected.folders.to_string()), files))
+    } else if exercise_naming.is_some() || exercise_file_naming.is_some() {
+        // Keep the pattern that was not given
+        Some((
+            exercise_naming.or(course.exercise_naming.clone()),
+            exercise_file_naming.or(course.exercise_file_naming.clone()),
+        ))
+    } else {
+        None
+    };
+
+    let course = match naming {
+        Some((folders, files)) => {
+            let course =
+                exercise::set_exercise_naming(&conn, id, folders.as_deref(), files.as_deref())
+                    .await?;
+            println!("{}", "✓ Exercise naming updated!".green());
+            course
+        }
+        None => course,
+    };
+
+    let naming = exercise::course_naming(&course)?;
+    let source = |configured: &Option<String>| {
+        if configured.is_some() {
+            "configured"
+        } else {
+            "detected"
+        }
+    };
+    println!("  {}", course.name.bold());
+    println!(
+        "  Folders: {} ({})",
+        naming.folders,
+        source(&course.exercise_naming).dimmed()
+    );
+    match &naming.files {
+        Some(files) => println!(
+            "  Files:   {} ({})",
+            files,
+            source(&course.exercise_file_naming).dimmed()
+        ),
+        None => println!("  Files:   {}", "not set".dimmed()),
+    }
+
+    let scan = exercise::scan_course_exercises(&conn, &course, false).await?;
+    for name in &scan.added {
+        println!("  ✓ Add exercise {}", name);
+    }
+    for name in &scan.updated {
+        println!("  ✓ Update exercise {}", name);
+    }
+
+    Ok(())
+}
+
+async fn handle_archive(id: i64, dropped: bool, yes: bool) -> Result<()> {
+    let config = Config::load()?;
+    let conn = connection_seaorm::get_connection().await?;
+    let course = queries::course::get_by_id(&conn, id).await?;
+
+    let confirmed = yes
+        || Confirm::new(&format!("Move {} to archive?", course.name))
+            .with_default(true)
+            .prompt()?;
+    if !confirmed {
+        println!("{}", "Cancelled.".yellow());
+        return Ok(());
+    }
+
+    let archived = archive::archive_course(&config, &conn, id, dropped).await?;
+
+    if dropped {
+        println!("{}", "✓ Course dropped and archived!".green());
+    } else {
+        println!("{}", "✓ Course archived!".green());
+    }
+    println!("  {}", archived.name.bold());
+    println!(
+        "  Moved to: {}",
+        archived.directory_path.display().to_string().dimmed()
+    );
+    println!();
+    println!(
+        "{}",
+        format!("Use 'mms archive restore {}' to bring it back.", id).dimmed()
+    );
+
+    Ok(())
+}
+
+/// Hand the active course back to the schedule
+async fn handle_set_auto() -> Result<()> {
+    let conn = connection_seaorm::get_connection().await?;
+    queries::active::clear_manual(&conn).await?;
+    println!("{}", "✓ Active course follows the schedule again".green());
+
+    // Let a running daemon switch right away
+    match control::send(&ControlRequest::Recheck) {
+        Ok(_) => println!("  Background service is re-checking the schedule."),
+        Err(MmsError::DaemonUnreachable(_)) => println!(
+            "  {}",
+            "Background service is not running - the course switches once it starts.".dimmed()
+        ),
+        Err(e) => return Err(e.into()),
+    }
 
     Ok(())
 }
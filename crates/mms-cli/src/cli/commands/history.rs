@@ -0,0 +1,75 @@
+use crate::cli::course_resolver::CourseResolver;
+use anyhow::Result;
+use chrono::{Local, Utc};
+use colored::Colorize;
+use mms_core::db::connection_seaorm;
+use mms_core::history::{self, HistoryFilter, SwitchReason};
+use mms_core::utils::date_validation::parse_german_date;
+
+pub async fn handle(
+    course: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    limit: u64,
+) -> Result<()> {
+    let conn = connection_seaorm::get_connection().await?;
+
+    // Only resolve the course when filtering by one
+    let course = match course {
+        Some(course) => Some(CourseResolver::resolve(&conn, Some(course)).await?),
+        None => None,
+    };
+
+    let filter = HistoryFilter {
+        from: from.as_deref().map(parse_german_date).transpose()?,
+        to: to.as_deref().map(parse_german_date).transpose()?,
+        course_id: course.as_ref().map(|c| c.id),
+        limit: Some(limit),
+    };
+    let entries = history::list_history(&conn, &filter).await?;
+
+    match &course {
+        Some(course) => {
+            println!("{}", format!("Course history of {}", course.name).bold());
+            let now = Utc::now();
+            match history::last_active(&conn, course.id, now).await? {
+                Some(at) if at == now => println!("  Last active: {}", "now".green()),
+                Some(at) => println!(
+                    "  Last active: {}",
+                    at.with_timezone(&Local).format("%d.%m.%Y %H:%M")
+                ),
+                None => println!("  Last active: {}", "never".dimmed()),
+            }
+        }
+        None => println!("{}", "Course history".bold()),
+    }
+    println!();
+
+    if entries.is_empty() {
+        println!("{}", "No course switches recorded.".yellow());
+        return Ok(());
+    }
+
+    for entry in entries {
+        let from = entry.from.as_deref().unwrap_or("-");
+        let to = entry.to.as_deref().unwrap_or("-");
+        let reason = match entry.reason {
+            SwitchReason::Schedule => entry.reason.to_string().dimmed(),
+            SwitchReason::Manual => entry.reason.to_string().cyan(),
+            SwitchReason::Override => entry.reason.to_string().yellow(),
+            SwitchReason::Archive => entry.reason.to_string().red(),
+        };
+        println!(
+            "  {}  {:>8} → {:<8}  {}",
+            entry
+                .switched_at
+                .with_timezone(&Local)
+                .format("%d.%m.%Y %H:%M"),
+            from,
+            to.bold(),
+            reason
+        );
+    }
+
+    Ok(())
+}
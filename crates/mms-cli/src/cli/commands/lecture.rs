@@ -0,0 +1,87 @@
+use crate::cli::args::LectureAction;
+use crate::cli::course_resolver::CourseResolver;
+use anyhow::Result;
+use colored::Colorize;
+use mms_core::db::connection_seaorm;
+use mms_core::db::queries;
+use mms_core::lecture;
+
+pub async fn handle(action: LectureAction) -> Result<()> {
+    match action {
+        LectureAction::Record {
+            lecture_num,
+            course,
+        } => {
+            println!("{}", "TODO: Lecture recording not yet implemented".yellow());
+            println!("Lecture: {}, course: {:?}", lecture_num, course);
+            Ok(())
+        }
+        LectureAction::List { course } => handle_list(course).await,
+        LectureAction::Sync { course } => handle_sync(course).await,
+    }
+}
+
+async fn handle_list(course: Option<String>) -> Result<()> {
+    let conn = connection_seaorm::get_connection().await?;
+    let course = CourseResolver::resolve(&conn, course).await?;
+    let lectures = queries::lectures::list_by_course(&conn, course.id).await?;
+
+    println!("{}", format!("Lectures of {}", course.name).bold());
+    println!();
+
+    if lectures.is_empty() {
+        println!("{}", "No lectures recorded yet.".yellow());
+        return Ok(());
+    }
+
+    for lecture in lectures {
+        let room = lecture.room.unwrap_or_default();
+        println!(
+            "  {:>3}  {}  {}-{}  {}",
+            lecture.lecture_number,
+            lecture.date,
+            lecture.start_time,
+            lecture.end_time,
+            room.dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+async fn handle_sync(course: Option<String>) -> Result<()> {
+    let conn = connection_seaorm::get_connection().await?;
+    let now = chrono::Local::now().naive_local();
+
+    let created = match course {
+        Some(input) => {
+            let course = CourseResolver::resolve(&conn, Some(input)).await?;
+            lecture::sync_lectures(&conn, course.id, now).await?
+        }
+        None => lecture::sync_semester_lectures(&conn, now).await?,
+    };
+
+    if created.is_empty() {
+        println!("{}", "✓ Lectures are up to date".green());
+        return Ok(());
+    }
+
+    for lecture in &created {
+        let course = queries::course::get_by_id(&conn, lecture.course_id).await?;
+        println!(
+            "  {} #{} on {} {}-{}",
+            course.short_name.cyan(),
+            lecture.lecture_number,
+            lecture.date,
+            lecture.start_time,
+            lecture.end_time
+        );
+    }
+    println!();
+    println!(
+        "{}",
+        format!("✓ Created {} lecture(s)", created.len()).green()
+    );
+
+    Ok(())
+}
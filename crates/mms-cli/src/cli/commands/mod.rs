@@ -1,15 +1,19 @@
+pub mod archive;
+pub mod attendance;
 pub mod config;
+pub mod course;
+pub mod history;
+pub mod lecture;
 pub mod service;
+pub mod stats;
+pub mod status;
+pub mod sync;
 
 // pub mod semester;
-// pub mod status;
-// pub mod course;
 // pub mod schedule;
 // pub mod today;
 
 // TODO: Implement other command modules
 // pub mod todo;
-// pub mod lecture;
 // pub mod exam;
 // pub mod holiday;
-// pub mod stats;
\ No newline at end of file
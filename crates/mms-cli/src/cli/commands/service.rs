@@ -1,9 +1,17 @@
 use crate::cli::args::ServiceAction;
 use anyhow::Result;
+use colored::Colorize;
 use mms_core::error::MmsError;
+use mms_core::paths;
+use mms_core::service::control::{self, ControlRequest, DaemonState};
+use mms_core::service::logging::{self, LogEntry};
 use mms_core::service::{Daemon, DaemonStatus};
-use colored::Colorize;
 use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+use std::time::Duration;
 
 pub async fn handle(action: ServiceAction) -> Result<()> {
     match action {
@@ -13,119 +21,299 @@ pub async fn handle(action: ServiceAction) -> Result<()> {
         ServiceAction::Stop => handle_stop(),
         ServiceAction::Status => handle_status(),
         ServiceAction::Run => handle_run().await,
+        ServiceAction::Pause { minutes } => handle_control(ControlRequest::Pause { minutes }),
+        ServiceAction::Resume => handle_control(ControlRequest::Resume),
+        ServiceAction::Recheck => handle_control(ControlRequest::Recheck),
+        ServiceAction::Reload => handle_control(ControlRequest::Reload),
+        ServiceAction::Logs { follow, since } => handle_logs(follow, since),
     }
 }
 
 fn handle_install() -> Result<()> {
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "macos")]
+    {
+        install_launchd()
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        install_systemd()
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
-        return Err(MmsError::Other(
-            "Auto-start installation is only supported on macOS".to_string()
-        ).into());
+        Err(MmsError::Other(
+            "Auto-start installation is only supported on macOS and Linux".to_string(),
+        )
+        .into())
     }
+}
 
+fn handle_uninstall() -> Result<()> {
     #[cfg(target_os = "macos")]
     {
-        println!("{}", "Installing MMS background service...".bold());
-        println!();
+        uninstall_launchd()
+    }
 
-        // Get paths
-        let plist_template = include_str!("../../../../../resources/com.mms.daemon.plist");
-        let home_dir = dirs::home_dir()
-            .ok_or_else(|| MmsError::Other("Could not determine home directory".to_string()))?;
-        let launch_agents_dir = home_dir.join("Library/LaunchAgents");
-        let plist_path = launch_agents_dir.join("com.mms.daemon.plist");
-        let binary_path = std::env::current_exe()?;
-        let log_dir = dirs::data_local_dir()
-            .ok_or_else(|| MmsError::Other("Could not determine data directory".to_string()))?
-            .join("mms");
-
-        // Create log directory
-        fs::create_dir_all(&log_dir)?;
-
-        // Create LaunchAgents directory if it doesn't exist
-        fs::create_dir_all(&launch_agents_dir)?;
-
-        // Replace placeholders in template
-        let plist_content = plist_template
-            .replace("{{BINARY_PATH}}", &binary_path.to_string_lossy())
-            .replace("{{LOG_PATH}}", &log_dir.to_string_lossy())
-            .replace("{{HOME}}", &home_dir.to_string_lossy());
-
-        // Write plist file
-        fs::write(&plist_path, plist_content)?;
-
-        println!("{}", "✓ Plist file created".green());
-        println!("  Location: {}", plist_path.display().to_string().dimmed());
+    #[cfg(target_os = "linux")]
+    {
+        uninstall_systemd()
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        Err(MmsError::Other(
+            "Auto-start uninstallation is only supported on macOS and Linux".to_string(),
+        )
+        .into())
+    }
+}
+
+// ============================================================================
+// launchd (macOS)
+// ============================================================================
+
+#[cfg(target_os = "macos")]
+fn install_launchd() -> Result<()> {
+    println!("{}", "Installing MMS background service...".bold());
+    println!();
+
+    // Get paths
+    let plist_template = include_str!("../../../../../resources/com.mms.daemon.plist");
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| MmsError::Other("Could not determine home directory".to_string()))?;
+    let launch_agents_dir = home_dir.join("Library/LaunchAgents");
+    let plist_path = launch_agents_dir.join("com.mms.daemon.plist");
+    let binary_path = std::env::current_exe()?;
+    let log_dir = dirs::data_local_dir()
+        .ok_or_else(|| MmsError::Other("Could not determine data directory".to_string()))?
+        .join("mms");
+
+    // Create log directory
+    fs::create_dir_all(&log_dir)?;
+
+    // Create LaunchAgents directory if it doesn't exist
+    fs::create_dir_all(&launch_agents_dir)?;
+
+    // Replace placeholders in template
+    let plist_content = plist_template
+        .replace("{{BINARY_PATH}}", &binary_path.to_string_lossy())
+        .replace("{{LOG_PATH}}", &log_dir.to_string_lossy())
+        .replace("{{HOME}}", &home_dir.to_string_lossy());
+
+    // Write plist file
+    fs::write(&plist_path, plist_content)?;
+
+    println!("{}", "✓ Plist file created".green());
+    println!("  Location: {}", plist_path.display().to_string().dimmed());
+    println!();
+
+    // Load the service
+    let output = std::process::Command::new("launchctl")
+        .arg("load")
+        .arg(&plist_path)
+        .output()?;
+
+    if output.status.success() {
+        println!("{}", "✓ Service installed and loaded!".green());
         println!();
+        println!("The MMS daemon will now start automatically on login.");
+        println!("Logs: {}", "mms service logs --follow".dimmed());
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        println!("{}", "✗ Failed to load service".red());
+        println!("Error: {}", stderr);
+    }
 
-        // Load the service
-        let output = std::process::Command::new("launchctl")
-            .arg("load")
-            .arg(&plist_path)
-            .output()?;
+    Ok(())
+}
 
-        if output.status.success() {
-            println!("{}", "✓ Service installed and loaded!".green());
-            println!();
-            println!("The MMS daemon will now start automatically on login.");
-            println!("Logs: {}", log_dir.join("mms-daemon.log").display().to_string().dimmed());
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            println!("{}", "✗ Failed to load service".red());
-            println!("Error: {}", stderr);
-        }
+#[cfg(target_os = "macos")]
+fn uninstall_launchd() -> Result<()> {
+    println!("{}", "Uninstalling MMS background service...".bold());
+    println!();
+
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| MmsError::Other("Could not determine home directory".to_string()))?;
+    let plist_path = home_dir.join("Library/LaunchAgents/com.mms.daemon.plist");
+
+    if !plist_path.exists() {
+        println!("{}", "Service is not installed.".yellow());
+        return Ok(());
+    }
 
-        Ok(())
+    // Unload the service
+    let output = std::process::Command::new("launchctl")
+        .arg("unload")
+        .arg(&plist_path)
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        println!("{}", "Warning: Failed to unload service".yellow());
+        println!("Error: {}", stderr);
+        println!();
     }
+
+    // Remove plist file
+    fs::remove_file(&plist_path)?;
+
+    println!("{}", "✓ Service uninstalled!".green());
+    println!();
+    println!("The MMS daemon will no longer start automatically on login.");
+
+    Ok(())
 }
 
-fn handle_uninstall() -> Result<()> {
-    #[cfg(not(target_os = "macos"))]
-    {
-        return Err(MmsError::Other(
-            "Auto-start uninstallation is only supported on macOS".to_string()
-        ).into());
+// ============================================================================
+// systemd (Linux)
+// ============================================================================
+
+#[cfg(target_os = "linux")]
+const SYSTEMD_UNIT: &str = "mms-daemon.service";
+
+/// Path of the systemd user unit (~/.config/systemd/user/mms-daemon.service)
+#[cfg(target_os = "linux")]
+fn systemd_unit_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| MmsError::Other("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("systemd/user").join(SYSTEMD_UNIT))
+}
+
+/// Whether the systemd user unit is installed
+#[cfg(target_os = "linux")]
+fn systemd_installed() -> bool {
+    systemd_unit_path().is_ok_and(|path| path.exists())
+}
+
+/// Run `systemctl --user <args>`
+#[cfg(target_os = "linux")]
+fn systemctl(args: &[&str]) -> Result<std::process::Output> {
+    let output = std::process::Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .output()
+        .map_err(|e| MmsError::Other(format!("Failed to run systemctl: {}", e)))?;
+    Ok(output)
+}
+
+#[cfg(target_os = "linux")]
+fn install_systemd() -> Result<()> {
+    println!("{}", "Installing MMS background service...".bold());
+    println!();
+
+    // Get paths
+    let unit_template = include_str!("../../../../../resources/mms-daemon.service");
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| MmsError::Other("Could not determine home directory".to_string()))?;
+    let unit_path = systemd_unit_path()?;
+    let binary_path = std::env::current_exe()?;
+    let log_dir = dirs::data_local_dir()
+        .ok_or_else(|| MmsError::Other("Could not determine data directory".to_string()))?
+        .join("mms");
+
+    // Create log directory
+    fs::create_dir_all(&log_dir)?;
+
+    // Create systemd user directory if it doesn't exist
+    if let Some(parent) = unit_path.parent() {
+        fs::create_dir_all(parent)?;
     }
 
-    #[cfg(target_os = "macos")]
-    {
-        println!("{}", "Uninstalling MMS background service...".bold());
+    // Replace placeholders in template
+    let unit_content = unit_template
+        .replace("{{BINARY_PATH}}", &binary_path.to_string_lossy())
+        .replace("{{LOG_PATH}}", &log_dir.to_string_lossy())
+        .replace("{{HOME}}", &home_dir.to_string_lossy());
+
+    // Write unit file
+    fs::write(&unit_path, unit_content)?;
+
+    println!("{}", "✓ Unit file created".green());
+    println!("  Location: {}", unit_path.display().to_string().dimmed());
+    println!();
+
+    // Pick up the new unit, then enable and start it
+    systemctl(&["daemon-reload"])?;
+    let output = systemctl(&["enable", "--now", SYSTEMD_UNIT])?;
+
+    if output.status.success() {
+        println!("{}", "✓ Service installed and started!".green());
         println!();
+        println!("The MMS daemon will now start automatically on login.");
+        println!("Logs: {}", "mms service logs --follow".dimmed());
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        println!("{}", "✗ Failed to enable service".red());
+        println!("Error: {}", stderr);
+    }
 
-        let home_dir = dirs::home_dir()
-            .ok_or_else(|| MmsError::Other("Could not determine home directory".to_string()))?;
-        let plist_path = home_dir.join("Library/LaunchAgents/com.mms.daemon.plist");
+    Ok(())
+}
 
-        if !plist_path.exists() {
-            println!("{}", "Service is not installed.".yellow());
-            return Ok(());
-        }
+#[cfg(target_os = "linux")]
+fn uninstall_systemd() -> Result<()> {
+    println!("{}", "Uninstalling MMS background service...".bold());
+    println!();
 
-        // Unload the service
-        let output = std::process::Command::new("launchctl")
-            .arg("unload")
-            .arg(&plist_path)
-            .output()?;
+    let unit_path = systemd_unit_path()?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            println!("{}", "Warning: Failed to unload service".yellow());
-            println!("Error: {}", stderr);
-            println!();
-        }
+    if !unit_path.exists() {
+        println!("{}", "Service is not installed.".yellow());
+        return Ok(());
+    }
 
-        // Remove plist file
-        fs::remove_file(&plist_path)?;
+    // Stop and disable the service
+    let output = systemctl(&["disable", "--now", SYSTEMD_UNIT])?;
 
-        println!("{}", "✓ Service uninstalled!".green());
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        println!("{}", "Warning: Failed to disable service".yellow());
+        println!("Error: {}", stderr);
         println!();
-        println!("The MMS daemon will no longer start automatically on login.");
+    }
+
+    // Remove unit file
+    fs::remove_file(&unit_path)?;
+    systemctl(&["daemon-reload"])?;
 
-        Ok(())
+    println!("{}", "✓ Service uninstalled!".green());
+    println!();
+    println!("The MMS daemon will no longer start automatically on login.");
+
+    Ok(())
+}
+
+/// Start, stop or restart the installed systemd unit
+#[cfg(target_os = "linux")]
+fn systemd_control(action: &str) -> Result<()> {
+    let output = systemctl(&[action, SYSTEMD_UNIT])?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(MmsError::Other(format!(
+            "systemctl --user {} {} failed: {}",
+            action,
+            SYSTEMD_UNIT,
+            stderr.trim()
+        ))
+        .into());
     }
+
+    Ok(())
 }
 
+/// Query a `systemctl --user is-*` property of the unit (e.g. "active", "enabled")
+#[cfg(target_os = "linux")]
+fn systemd_query(query: &str) -> Result<String> {
+    let output = systemctl(&[query, SYSTEMD_UNIT])?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// ============================================================================
+// Control
+// ============================================================================
+
 fn handle_start() -> Result<()> {
     let daemon = Daemon::new()?;
 
@@ -139,6 +327,28 @@ fn handle_start() -> Result<()> {
         DaemonStatus::Stopped => {}
     }
 
+    // Let systemd manage the process if the unit is installed
+    #[cfg(target_os = "linux")]
+    if systemd_installed() {
+        systemd_control("start")?;
+        std::thread::sleep(std::time::Duration::from_millis(500));
+
+        match daemon.status()? {
+            DaemonStatus::Running { pid } => {
+                println!("{}", "✓ Service started successfully!".green());
+                println!("  PID: {}", pid);
+            }
+            DaemonStatus::Stopped => {
+                println!("{}", "✗ Failed to start service".red());
+                println!(
+                    "Check 'systemctl --user status {}' for details.",
+                    SYSTEMD_UNIT
+                );
+            }
+        }
+        return Ok(());
+    }
+
     // Fork and run in background
     #[cfg(unix)]
     {
@@ -172,7 +382,10 @@ fn handle_start() -> Result<()> {
 
     #[cfg(not(unix))]
     {
-        println!("{}", "Starting service in background is not supported on this platform.".yellow());
+        println!(
+            "{}",
+            "Starting service in background is not supported on this platform.".yellow()
+        );
         println!("Use 'mms service run' to run in foreground.");
     }
 
@@ -189,6 +402,15 @@ fn handle_stop() -> Result<()> {
         }
         DaemonStatus::Running { pid } => {
             println!("Stopping service (PID: {})...", pid);
+
+            // Stop through systemd so it does not consider the exit a failure
+            #[cfg(target_os = "linux")]
+            if systemd_installed() {
+                systemd_control("stop")?;
+                println!("{}", "✓ Service stopped successfully!".green());
+                return Ok(());
+            }
+
             daemon.stop()?;
             println!("{}", "✓ Service stopped successfully!".green());
         }
@@ -206,6 +428,15 @@ fn handle_status() -> Result<()> {
             println!();
             println!("  Status: {}", "Running".green().bold());
             println!("  PID:    {}", pid);
+
+            match control::send(&ControlRequest::Status) {
+                Ok(response) => {
+                    if let Some(state) = response.state {
+                        print_state(&state);
+                    }
+                }
+                Err(e) => println!("  {}", e.to_string().yellow()),
+            }
         }
         DaemonStatus::Stopped => {
             println!("{}", "Service Status:".bold().underline());
@@ -216,15 +447,160 @@ fn handle_status() -> Result<()> {
         }
     }
 
+    #[cfg(target_os = "linux")]
+    if systemd_installed() {
+        println!(
+            "  Unit:   {} ({}, {})",
+            SYSTEMD_UNIT,
+            systemd_query("is-enabled")?,
+            systemd_query("is-active")?
+        );
+    }
+
+    Ok(())
+}
+
+/// Print the state reported by the daemon
+fn print_state(state: &DaemonState) {
+    println!(
+        "  Active: {}",
+        state.active_course.as_deref().unwrap_or("none")
+    );
+    if state.manual {
+        match state.manual_until {
+            Some(until) => println!(
+                "  Manual: {} until {}",
+                "yes".cyan(),
+                until.format("%Y-%m-%d %H:%M")
+            ),
+            None => println!("  Manual: {}", "yes".cyan()),
+        }
+    }
+    if state.paused {
+        match state.paused_until {
+            Some(until) => println!(
+                "  Paused: {} until {}",
+                "yes".yellow(),
+                until.format("%Y-%m-%d %H:%M")
+            ),
+            None => println!("  Paused: {}", "indefinitely".yellow()),
+        }
+    }
+    if let Some(switch) = &state.next_switch {
+        println!(
+            "  Next:   {} at {}",
+            switch.course.as_deref().unwrap_or("none"),
+            switch.at.format("%Y-%m-%d %H:%M")
+        );
+    }
+    if let Some(next_check) = state.next_check {
+        println!("  Check:  {}", next_check.format("%Y-%m-%d %H:%M"));
+    }
+}
+
+/// Send a request to the running daemon and show the result
+fn handle_control(request: ControlRequest) -> Result<()> {
+    let response = control::send(&request)?;
+    if !response.ok {
+        return Err(MmsError::Other(response.error.unwrap_or_default()).into());
+    }
+
+    let message = match request {
+        ControlRequest::Pause {
+            minutes: Some(minutes),
+        } => {
+            format!("Automatic switching paused for {} minutes", minutes)
+        }
+        ControlRequest::Pause { minutes: None } => "Automatic switching paused".to_string(),
+        ControlRequest::Resume => "Automatic switching resumed".to_string(),
+        ControlRequest::Recheck => "Schedule re-check requested".to_string(),
+        ControlRequest::Reload => "Configuration reloaded".to_string(),
+        ControlRequest::Status => String::new(),
+    };
+    println!("{}", format!("✓ {}", message).green());
+
+    if let Some(state) = response.state {
+        print_state(&state);
+    }
     Ok(())
 }
 
+fn handle_logs(follow: bool, since: Option<String>) -> Result<()> {
+    let log_dir = paths::log_dir_path()?;
+    let since = since
+        .map(|since| logging::parse_since(&since, chrono::Local::now()))
+        .transpose()?;
+
+    for entry in logging::read_logs(&log_dir, since)? {
+        print_log_entry(&entry);
+    }
+
+    if follow {
+        follow_logs(&log_dir)?;
+    }
+    Ok(())
+}
+
+/// Print new log lines as they are written, switching files on rotation
+fn follow_logs(log_dir: &Path) -> Result<()> {
+    let mut current = logging::log_files(log_dir)?.pop();
+    let mut offset = match &current {
+        Some(file) => fs::metadata(file)?.len(),
+        None => 0,
+    };
+
+    loop {
+        if let Some(file) = &current {
+            offset = print_appended(file, offset)?;
+        }
+
+        // A newer file means the log was rotated
+        let newest = logging::log_files(log_dir)?.pop();
+        if newest != current {
+            current = newest;
+            offset = 0;
+            continue;
+        }
+
+        std::thread::sleep(Duration::from_millis(500));
+    }
+}
+
+/// Print complete lines appended to `file` after `offset`, returning the new offset
+fn print_appended(file: &Path, offset: u64) -> Result<u64> {
+    let mut handle = fs::File::open(file)?;
+    handle.seek(SeekFrom::Start(offset))?;
+    let mut appended = String::new();
+    handle.read_to_string(&mut appended)?;
+
+    // Leave a partially written line for the next round
+    let Some(end) = appended.rfind('\n') else {
+        return Ok(offset);
+    };
+    for line in appended[..end].lines() {
+        if let Some(entry) = LogEntry::parse(line) {
+            print_log_entry(&entry);
+        }
+    }
+    Ok(offset + end as u64 + 1)
+}
+
+fn print_log_entry(entry: &LogEntry) {
+    let line = entry.display_line();
+    match entry.level.as_str() {
+        "ERROR" => println!("{}", line.red()),
+        "WARN" => println!("{}", line.yellow()),
+        "DEBUG" | "TRACE" => println!("{}", line.dimmed()),
+        _ => println!("{}", line),
+    }
+}
+
 async fn handle_run() -> Result<()> {
     println!("{}", "Starting MMS service...".bold());
     println!();
 
-    let daemon = Daemon::new()?;
+    let mut daemon = Daemon::new()?;
     daemon.run().await?; // Await the async run
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,88 @@
+use crate::cli::args::StatsAction;
+use crate::cli::commands::course::print_attendance;
+use anyhow::Result;
+use colored::Colorize;
+use mms_core::config::Config;
+use mms_core::db::connection_seaorm;
+use mms_core::db::queries;
+use mms_core::study_time::{self, DEFAULT_HOURS_PER_ECTS};
+
+pub async fn handle(action: StatsAction) -> Result<()> {
+    match action {
+        StatsAction::Attendance => handle_attendance().await,
+        StatsAction::Time { weeks } => handle_time(weeks).await,
+        // TODO: Implement grade and ECTS statistics
+        action => {
+            println!("{}", "TODO: Stats commands not yet implemented".yellow());
+            println!("Action: {:?}", action);
+            Ok(())
+        }
+    }
+}
+
+async fn handle_attendance() -> Result<()> {
+    let conn = connection_seaorm::get_connection().await?;
+
+    let Some(semester) = queries::semester::get_current(&conn).await? else {
+        println!("{}", "No current semester set.".yellow());
+        return Ok(());
+    };
+
+    println!("{}", "Attendance:".bold().underline());
+
+    for course in queries::course::list_by_semester(&conn, semester.id).await? {
+        println!();
+        println!("{} ({})", course.name.bold(), course.short_name.dimmed());
+        print_attendance(&conn, course.id).await?;
+    }
+
+    Ok(())
+}
+
+async fn handle_time(weeks: u32) -> Result<()> {
+    let config = Config::load()?;
+    let hours_per_ects = config
+        .study_time
+        .as_ref()
+        .map(|s| s.hours_per_ects)
+        .unwrap_or(DEFAULT_HOURS_PER_ECTS);
+
+    let conn = connection_seaorm::get_connection().await?;
+
+    let Some(semester) = queries::semester::get_current(&conn).await? else {
+        println!("{}", "No current semester set.".yellow());
+        return Ok(());
+    };
+
+    let now = chrono::Local::now().naive_local();
+    let from = now.date() - chrono::Days::new(7 * weeks.saturating_sub(1) as u64);
+    let report =
+        study_time::study_time_report(&conn, semester.id, from, now.date(), hours_per_ects, now)
+            .await?;
+
+    println!("{}", "Study time:".bold().underline());
+
+    let mut current_week = None;
+    for entry in report {
+        if current_week != Some(entry.week_start) {
+            println!();
+            println!(
+                "{}",
+                format!("Week of {}", entry.week_start.format("%d.%m.%Y")).bold()
+            );
+            current_week = Some(entry.week_start);
+        }
+
+        let hours = format!("{:>5.1}h / {:.1}h", entry.hours, entry.expected_hours);
+        let hours = match entry.ratio() {
+            Some(ratio) if ratio >= 1.0 => hours.green(),
+            Some(ratio) if ratio >= 0.5 => hours.yellow(),
+            Some(_) => hours.red(),
+            None => hours.normal(),
+        };
+
+        println!("  {:<12} {}", entry.short_name, hours);
+    }
+
+    Ok(())
+}
@@ -1,54 +1,75 @@
 use anyhow::Result;
-use mms_core::sync;
 use colored::Colorize;
+use mms_core::config::Config;
+use mms_core::db::connection_seaorm;
+use mms_core::db::queries;
+use mms_core::semester::SemesterInfo;
+use mms_core::symlink::Symlinks;
+use mms_core::sync;
+use std::path::Path;
 
-pub fn handle_status() -> Result<()> {
-    let conn = mms_core::db::connection::get()?;
+pub async fn handle() -> Result<()> {
+    let config = Config::load()?;
+    let conn = connection_seaorm::get_connection().await?;
 
     println!("{}", "System Status".bold().underline());
     println!();
 
     // Get active state
-    let active = mms_core::db::queries::active::get(&conn)?;
+    let active = queries::active::get(&conn).await?;
 
     // Show active semester
     if let Some(semester_id) = active.semester_id {
-        let semester = mms_core::db::queries::semester::get_by_id(&conn, semester_id)?;
-        println!("{} {}", "Active Semester:".bold(), semester.to_string().green());
+        let semester =
+            SemesterInfo::try_from(queries::semester::get_by_id(&conn, semester_id).await?)?;
+        println!("{} {}", "Active Semester:".bold(), semester.code.green());
     } else {
         println!("{} {}", "Active Semester:".bold(), "None".yellow());
     }
 
     // Show active course
     if let Some(course_id) = active.course_id {
-        let course = mms_core::db::queries::course::get_by_id(&conn, course_id)?;
+        let course = queries::course::get_by_id(&conn, course_id).await?;
         println!("{} {}", "Active Course:  ".bold(), course.name.green());
     } else {
         println!("{} {}", "Active Course:  ".bold(), "None".yellow());
     }
 
     // Show symlink status
-    let (cs_target, cc_target) = mms_core::symlink::check_symlinks()?;
     println!();
     println!("{}", "Symlinks:".bold());
-    if let Some(target) = cs_target {
-        println!("  ~/cs -> {}", target.display().to_string().green());
-    } else {
-        println!("  ~/cs -> {}", "Not set".yellow());
-    }
-    if let Some(target) = cc_target {
-        println!("  ~/cc -> {}", target.display().to_string().green());
-    } else {
-        println!("  ~/cc -> {}", "Not set".yellow());
+    for link in Symlinks::from_config(&config)?.check() {
+        let path = link.path.display();
+        match link.target {
+            Some(target) if link.broken => println!(
+                "  {} -> {} {}",
+                path,
+                target.display().to_string().red(),
+                "(broken)".red()
+            ),
+            Some(target) => println!("  {} -> {}", path, target.display().to_string().green()),
+            None => println!("  {} -> {}", path, "Not set".yellow()),
+        }
     }
 
     println!();
     println!("{}", "Sync Status".bold().underline());
     println!();
 
-    let status = sync::check_status()?;
+    let mut status = sync::check_status().await?;
+    let drifts = sync::detect_drift(&conn).await?;
+    let moves = sync::detect_moves(&config, &conn).await?;
+    let renames = sync::normalize_semester_folders(&config, &conn, true).await?;
 
-    if status.is_synced() {
+    // A moved semester is neither missing nor unknown
+    status
+        .semesters_in_db_only
+        .retain(|s| !moves.iter().any(|m| m.from == Path::new(&s.directory_path)));
+    status
+        .semesters_on_disk_only
+        .retain(|s| !moves.iter().any(|m| m.to == s.path));
+
+    if status.is_synced() && drifts.is_empty() && moves.is_empty() && renames.is_empty() {
         println!("{}", "✓ Everything is in sync!".green().bold());
         println!();
         println!("Semesters synced: {}", status.synced_semesters.len());
@@ -59,25 +80,19 @@ pub fn handle_status() -> Result<()> {
     if !status.synced_semesters.is_empty() {
         println!("{}", "✓ Synced Semesters:".green().bold());
         for semester in &status.synced_semesters {
-            println!("  {} [{}]", semester.to_string(), semester.folder_name().dimmed());
+            println!("  {}", semester.directory_path.dimmed());
         }
         println!();
     }
 
     // Show semesters in DB but missing folders
     if !status.semesters_in_db_only.is_empty() {
-        println!("{}", "⚠ Semesters in database without folders:".yellow().bold());
+        println!(
+            "{}",
+            "⚠ Semesters in database without folders:".yellow().bold()
+        );
         for semester in &status.semesters_in_db_only {
-            let expected_path = mms_core::config::Config::load()?
-                .general
-                .university_base_path
-                .join(semester.folder_name());
-            println!(
-                "  {} [{}] - Missing: {}",
-                semester.to_string(),
-                semester.folder_name().dimmed(),
-                expected_path.display().to_string().red()
-            );
+            println!("  Missing: {}", semester.directory_path.red());
         }
         println!();
     }
@@ -86,18 +101,66 @@ pub fn handle_status() -> Result<()> {
     if !status.semesters_on_disk_only.is_empty() {
         println!("{}", "⚠ Folders on disk not in database:".yellow().bold());
         for disk_sem in &status.semesters_on_disk_only {
-            if disk_sem.parsed_type.is_some() && disk_sem.parsed_number.is_some() {
-                println!(
-                    "  {} - {}",
-                    disk_sem.folder_name.bold(),
-                    disk_sem.path.display().to_string().dimmed()
-                );
-            } else {
+            println!(
+                "  {} - {}",
+                disk_sem.folder_name.bold(),
+                disk_sem.path.display().to_string().dimmed()
+            );
+        }
+        println!();
+    }
+
+    // Show folders renamed or moved by hand
+    if !moves.is_empty() {
+        println!("{}", "⚠ Folders moved or renamed:".yellow().bold());
+        for folder_move in &moves {
+            println!(
+                "  {} - {} → {}",
+                folder_move.label.bold(),
+                folder_move.from.display().to_string().dimmed(),
+                folder_move.to.display().to_string().green()
+            );
+        }
+        println!();
+    }
+
+    // Show folders not following [general] semester_folder
+    if !renames.is_empty() {
+        println!(
+            "{}",
+            "⚠ Folders not following the naming pattern:"
+                .yellow()
+                .bold()
+        );
+        for rename in &renames {
+            println!(
+                "  {} - {} → {}",
+                rename.label.bold(),
+                rename.from.display().to_string().dimmed(),
+                rename.to.display().to_string().green()
+            );
+        }
+        println!();
+    }
+
+    // Show fields edited in TOML files or the database only
+    if !drifts.is_empty() {
+        println!(
+            "{}",
+            "⚠ TOML files differing from the database:".yellow().bold()
+        );
+        for drift in &drifts {
+            println!(
+                "  {} - {}",
+                drift.label.bold(),
+                drift.toml_path.display().to_string().dimmed()
+            );
+            for diff in &drift.diffs {
                 println!(
-                    "  {} - {} {}",
-                    disk_sem.folder_name.bold(),
-                    disk_sem.path.display().to_string().dimmed(),
-                    "(invalid format)".red()
+                    "    {}: toml {} ≠ db {}",
+                    diff.field,
+                    diff.toml.as_deref().unwrap_or("(unset)").cyan(),
+                    diff.db.as_deref().unwrap_or("(unset)").cyan()
                 );
             }
         }
@@ -106,45 +169,46 @@ pub fn handle_status() -> Result<()> {
 
     // Show summary
     println!("{}", "Summary:".bold().underline());
-    println!("  Synced:                    {}", status.synced_semesters.len());
-    println!("  In DB only (need folders): {}", status.semesters_in_db_only.len());
-    println!("  On disk only (not in DB):  {}", status.semesters_on_disk_only.len());
+    println!(
+        "  Synced:                    {}",
+        status.synced_semesters.len()
+    );
+    println!(
+        "  In DB only (need folders): {}",
+        status.semesters_in_db_only.len()
+    );
+    println!(
+        "  On disk only (not in DB):  {}",
+        status.semesters_on_disk_only.len()
+    );
+    println!("  Moved folders:             {}", moves.len());
+    println!("  Folders to rename:         {}", renames.len());
+    println!("  TOML drift:                {}", drifts.len());
     println!();
 
     if !status.semesters_in_db_only.is_empty() {
         println!("{}", "Run 'mms sync' to create missing folders.".cyan());
     }
-
-    Ok(())
-}
-
-pub fn handle_sync(dry_run: bool) -> Result<()> {
-    if dry_run {
-        println!("{}", "Dry-run mode: No changes will be made".yellow().bold());
-        println!();
+    if !moves.is_empty() {
+        println!(
+            "{}",
+            "Run 'mms sync' to update the paths of moved folders.".cyan()
+        );
     }
-
-    let actions = sync::sync_to_filesystem(dry_run)?;
-
-    if actions.is_empty() {
-        println!("{}", "✓ Nothing to sync!".green().bold());
-        return Ok(());
+    if !status.semesters_on_disk_only.is_empty() {
+        println!(
+            "{}",
+            "Run 'mms sync --import' to add folders with a .semester.toml.".cyan()
+        );
     }
-
-    println!("{}", "Sync actions:".bold());
-    for action in &actions {
-        if dry_run {
-            println!("  [DRY-RUN] {}", action.dimmed());
-        } else {
-            println!("  ✓ {}", action);
-        }
+    if !renames.is_empty() {
+        println!("{}", "Run 'mms sync --normalize' to rename them.".cyan());
     }
-    println!();
-
-    if dry_run {
-        println!("{}", "Run 'mms sync' without --dry-run to apply changes.".cyan());
-    } else {
-        println!("{}", "✓ Sync completed!".green().bold());
+    if !drifts.is_empty() {
+        println!(
+            "{}",
+            "Run 'mms sync --merge' to resolve the differences.".cyan()
+        );
     }
 
     Ok(())
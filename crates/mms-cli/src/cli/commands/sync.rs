@@ -0,0 +1,365 @@
+use anyhow::Result;
+use colored::Colorize;
+use inquire::{Confirm, Select};
+use mms_core::config::Config;
+use mms_core::config::settings::MergeStrategy;
+use mms_core::db::connection_seaorm;
+use mms_core::exercise::{self, ExerciseScan};
+use mms_core::slides::{self, SlideScan};
+use mms_core::sync::{self, FieldDiff, Side};
+
+pub async fn handle(dry_run: bool) -> Result<()> {
+    if dry_run {
+        println!(
+            "{}",
+            "Dry-run mode: No changes will be made".yellow().bold()
+        );
+        println!();
+    }
+
+    let config = Config::load()?;
+    let conn = connection_seaorm::get_connection().await?;
+    let mut actions = Vec::new();
+
+    // Renamed or moved folders, before missing folders are recreated
+    for folder_move in sync::detect_moves(&config, &conn).await? {
+        let action = format!(
+            "Moved {}: {} -> {}",
+            folder_move.label,
+            folder_move.from.display(),
+            folder_move.to.display()
+        );
+        if dry_run {
+            actions.push(action);
+            continue;
+        }
+
+        let confirmed = Confirm::new(&format!(
+            "{} was moved to {}. Update the database?",
+            folder_move.label,
+            folder_move.to.display()
+        ))
+        .with_default(true)
+        .prompt()?;
+        if confirmed {
+            sync::apply_move(&config, &conn, &folder_move).await?;
+            actions.push(action);
+        }
+    }
+
+    // Semester folders
+    actions.extend(sync::sync_to_filesystem(dry_run).await?);
+
+    // Slides of the current semester
+    for (course, scan) in slides::scan_semester_slides(&conn, dry_run).await? {
+        actions.extend(
+            slide_actions(&scan)
+                .into_iter()
+                .map(|action| format!("{}: {}", course.short_name, action)),
+        );
+    }
+
+    // Exercise folders of the current semester
+    for (course, scan) in exercise::scan_semester_exercises(&conn, dry_run).await? {
+        actions.extend(
+            exercise_actions(&scan)
+                .into_iter()
+                .map(|action| format!("{}: {}", course.short_name, action)),
+        );
+    }
+
+    if actions.is_empty() {
+        println!("{}", "✓ Nothing to sync!".green().bold());
+        return Ok(());
+    }
+
+    println!("{}", "Sync actions:".bold());
+    for action in &actions {
+        if dry_run {
+            println!("  [DRY-RUN] {}", action.dimmed());
+        } else {
+            println!("  ✓ {}", action);
+        }
+    }
+    println!();
+
+    if dry_run {
+        println!(
+            "{}",
+            "Run 'mms sync' without --dry-run to apply changes.".cyan()
+        );
+    } else {
+        println!("{}", "✓ Sync completed!".green().bold());
+    }
+
+    Ok(())
+}
+
+pub async fn handle_import(dry_run: bool) -> Result<()> {
+    if dry_run {
+        println!(
+            "{}",
+            "Dry-run mode: No changes will be made".yellow().bold()
+        );
+        println!();
+    }
+
+    let config = Config::load()?;
+    let conn = connection_seaorm::get_connection().await?;
+    let report = sync::import_from_filesystem(&config, &conn, dry_run).await?;
+
+    if report.is_empty() {
+        println!("{}", "✓ Database matches the TOML files!".green().bold());
+        return Ok(());
+    }
+
+    let actions: Vec<String> = report
+        .semesters_created
+        .iter()
+        .map(|code| format!("Create semester {}", code))
+        .chain(
+            report
+                .semesters_updated
+                .iter()
+                .map(|code| format!("Update semester {}", code)),
+        )
+        .chain(
+            report
+                .courses_created
+                .iter()
+                .map(|name| format!("Create course {}", name)),
+        )
+        .chain(
+            report
+                .courses_updated
+                .iter()
+                .map(|name| format!("Update course {}", name)),
+        )
+        .collect();
+
+    if !actions.is_empty() {
+        println!("{}", "Import actions:".bold());
+        for action in &actions {
+            if dry_run {
+                println!("  [DRY-RUN] {}", action.dimmed());
+            } else {
+                println!("  ✓ {}", action);
+            }
+        }
+        println!();
+    }
+
+    if !report.skipped.is_empty() {
+        println!("{}", "Skipped:".yellow().bold());
+        for (path, reason) in &report.skipped {
+            println!("  ✗ {}: {}", path.display(), reason);
+        }
+        println!();
+    }
+
+    if dry_run {
+        println!(
+            "{}",
+            "Run 'mms sync --import' without --dry-run to apply changes.".cyan()
+        );
+    } else {
+        println!("{}", "✓ Import completed!".green().bold());
+    }
+
+    Ok(())
+}
+
+pub async fn handle_normalize(dry_run: bool) -> Result<()> {
+    if dry_run {
+        println!(
+            "{}",
+            "Dry-run mode: No changes will be made".yellow().bold()
+        );
+        println!();
+    }
+
+    let config = Config::load()?;
+    let conn = connection_seaorm::get_connection().await?;
+    let moves = sync::normalize_semester_folders(&config, &conn, dry_run).await?;
+
+    if moves.is_empty() {
+        println!(
+            "{}",
+            "✓ Semester folders follow the naming pattern!"
+                .green()
+                .bold()
+        );
+        return Ok(());
+    }
+
+    println!("{}", "Renamed folders:".bold());
+    for folder_move in &moves {
+        let action = format!(
+            "{}: {} -> {}",
+            folder_move.label,
+            folder_move.from.display(),
+            folder_move.to.display()
+        );
+        if dry_run {
+            println!("  [DRY-RUN] {}", action.dimmed());
+        } else {
+            println!("  ✓ {}", action);
+        }
+    }
+    println!();
+
+    if dry_run {
+        println!(
+            "{}",
+            "Run 'mms sync --normalize' without --dry-run to apply changes.".cyan()
+        );
+    } else {
+        println!("{}", "✓ Semester folders normalized!".green().bold());
+    }
+
+    Ok(())
+}
+
+pub async fn handle_merge(dry_run: bool, strategy: Option<String>) -> Result<()> {
+    if dry_run {
+        println!(
+            "{}",
+            "Dry-run mode: No changes will be made".yellow().bold()
+        );
+        println!();
+    }
+
+    // --strategy overrides [sync] merge_strategy
+    let strategy = match strategy {
+        Some(strategy) => strategy
+            .parse::<MergeStrategy>()
+            .map_err(|_| anyhow::anyhow!("Invalid merge strategy: {}", strategy))?,
+        None => Config::load()?
+            .sync
+            .map(|s| s.merge_strategy)
+            .unwrap_or_default(),
+    };
+
+    let conn = connection_seaorm::get_connection().await?;
+    let drifts = sync::detect_drift(&conn).await?;
+
+    if drifts.is_empty() {
+        println!("{}", "✓ TOML files match the database!".green().bold());
+        return Ok(());
+    }
+
+    for drift in &drifts {
+        println!(
+            "{} {}",
+            drift.label.bold(),
+            drift.toml_path.display().to_string().dimmed()
+        );
+
+        if dry_run {
+            for diff in &drift.diffs {
+                let side = match strategy {
+                    MergeStrategy::TomlWins => " → db",
+                    MergeStrategy::DbWins => " → toml",
+                    MergeStrategy::Interactive => "",
+                };
+                println!(
+                    "  [DRY-RUN] {}: toml {} ≠ db {}{}",
+                    diff.field,
+                    value(&diff.toml),
+                    value(&diff.db),
+                    side
+                );
+            }
+            continue;
+        }
+
+        // Ask for all fields first, so a cancelled prompt changes nothing
+        let mut sides = Vec::new();
+        for diff in &drift.diffs {
+            let side = match strategy {
+                MergeStrategy::TomlWins => Side::Toml,
+                MergeStrategy::DbWins => Side::Db,
+                MergeStrategy::Interactive => prompt_side(diff)?,
+            };
+            sides.push(side);
+        }
+
+        let mut sides = sides.into_iter();
+        sync::resolve_drift(&conn, drift, |_| sides.next().unwrap_or(Side::Toml)).await?;
+
+        for diff in &drift.diffs {
+            println!("  ✓ {}", diff.field);
+        }
+    }
+    println!();
+
+    if dry_run {
+        println!(
+            "{}",
+            "Run 'mms sync --merge' without --dry-run to apply changes.".cyan()
+        );
+    } else {
+        println!("{}", "✓ Merge completed!".green().bold());
+    }
+
+    Ok(())
+}
+
+/// Ask which value of a field to keep
+fn prompt_side(diff: &FieldDiff) -> Result<Side> {
+    let toml = format!("TOML: {}", value(&diff.toml));
+    let db = format!("DB:   {}", value(&diff.db));
+    let selection = Select::new(
+        &format!("Keep which {}?", diff.field),
+        vec![toml.clone(), db],
+    )
+    .prompt()?;
+
+    Ok(if selection == toml {
+        Side::Toml
+    } else {
+        Side::Db
+    })
+}
+
+fn value(value: &Option<String>) -> &str {
+    value.as_deref().unwrap_or("(unset)")
+}
+
+/// Describe the changes of a slide scan
+fn slide_actions(scan: &SlideScan) -> Vec<String> {
+    let mut actions = Vec::new();
+    actions.extend(scan.added.iter().map(|name| format!("Add slides {}", name)));
+    actions.extend(
+        scan.updated
+            .iter()
+            .map(|name| format!("Update slides {}", name)),
+    );
+    actions.extend(
+        scan.renamed
+            .iter()
+            .map(|(from, to)| format!("Rename slides {} -> {}", from, to)),
+    );
+    actions.extend(
+        scan.removed
+            .iter()
+            .map(|name| format!("Remove slides {}", name)),
+    );
+    actions
+}
+
+/// Describe the changes of an exercise scan
+fn exercise_actions(scan: &ExerciseScan) -> Vec<String> {
+    let mut actions = Vec::new();
+    actions.extend(
+        scan.added
+            .iter()
+            .map(|name| format!("Add exercise {}", name)),
+    );
+    actions.extend(
+        scan.updated
+            .iter()
+            .map(|name| format!("Update exercise {}", name)),
+    );
+    actions
+}
@@ -1,6 +1,6 @@
 pub mod args;
 pub mod commands;
-// pub mod course_resolver;
+pub mod course_resolver;
 // pub mod semester_resolver;
 // pub mod prompt_helpers;
 pub mod setup;
@@ -29,10 +29,8 @@ async fn main() -> Result<()> {
             println!("{}", "Command 'semester' is currently being refactored.".yellow());
             Ok(())
         }
-        Commands::Course { action: _ } => {
-            println!("{}", "Command 'course' is currently being refactored.".yellow());
-            Ok(())
-        }
+        Commands::Course { action } => commands::course::handle(action).await,
+        Commands::Archive { action } => commands::archive::handle(action).await,
         Commands::Schedule { action: _ } => {
             println!("{}", "Command 'schedule' is currently being refactored.".yellow());
             Ok(())
@@ -42,11 +40,8 @@ async fn main() -> Result<()> {
             println!("Action: {:?}", action);
             Ok(())
         }
-        Commands::Lecture { action } => {
-            println!("{}", "TODO: Lecture commands not yet implemented".yellow());
-            println!("Action: {:?}", action);
-            Ok(())
-        }
+        Commands::Lecture { action } => commands::lecture::handle(action).await,
+        Commands::Attendance { action } => commands::attendance::handle(action).await,
         Commands::Exam { action } => {
             println!("{}", "TODO: Exam commands not yet implemented".yellow());
             println!("Action: {:?}", action);
@@ -57,22 +52,32 @@ async fn main() -> Result<()> {
             println!("Action: {:?}", action);
             Ok(())
         }
-        Commands::Stats { action } => {
-            println!("{}", "TODO: Stats commands not yet implemented".yellow());
-            println!("Action: {:?}", action);
-            Ok(())
+        Commands::Stats { action } => commands::stats::handle(action).await,
+        Commands::History {
+            course,
+            from,
+            to,
+            limit,
+        } => commands::history::handle(course, from, to, limit).await,
+
+        Commands::Status => commands::status::handle().await,
+        Commands::Sync {
+            dry_run,
+            import,
+            normalize,
+            merge,
+            strategy,
+        } => {
+            if normalize {
+                commands::sync::handle_normalize(dry_run).await
+            } else if import {
+                commands::sync::handle_import(dry_run).await
+            } else if merge {
+                commands::sync::handle_merge(dry_run, strategy).await
+            } else {
+                commands::sync::handle(dry_run).await
+            }
         }
-        
-        Commands::Status => {
-             // commands::status::handle_status()
-             println!("{}", "Command 'status' is currently being refactored.".yellow());
-             Ok(())
-        },
-        Commands::Sync { dry_run: _ } => {
-             // commands::status::handle_sync(dry_run)
-             println!("{}", "Command 'sync' is currently being refactored.".yellow());
-             Ok(())
-        },
         Commands::Today => {
              // commands::today::handle()
              println!("{}", "Command 'today' is currently being refactored.".yellow());
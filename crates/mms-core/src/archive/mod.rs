@@ -0,0 +1,5 @@
+pub mod operations;
+
+pub use operations::{
+    ARCHIVE_FOLDER, archive_course, archive_directory, list_archived_courses, restore_course,
+};
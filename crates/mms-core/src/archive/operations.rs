@@ -0,0 +1,307 @@
+//! Archiving dropped and completed courses
+//!
+//! Archived course folders live in `.archive/<semester>/<course>` below
+//! `university_base_path`. Their rows stay in the database for transcripts,
+//! but schedules, the daemon and default listings leave them out.
+
+use crate::config::Config;
+use crate::course::CourseInfo;
+use crate::db::entities::{courses, semesters};
+use crate::db::queries;
+use crate::error::{MmsError, Result};
+use crate::history::{self, SwitchReason};
+use crate::paths;
+use crate::semester::SemesterType;
+use crate::symlink;
+use crate::sync::semester_path;
+use crate::toml::CourseToml;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel,
+    QueryFilter, QueryOrder,
+};
+use std::path::{Path, PathBuf};
+
+/// Name of the archive folder in `university_base_path`
+pub const ARCHIVE_FOLDER: &str = ".archive";
+
+/// The archive folder
+pub fn archive_directory(config: &Config) -> PathBuf {
+    config.university_base_path.join(ARCHIVE_FOLDER)
+}
+
+/// Archive folder of a course, named after its semester folder
+fn archived_course_directory(
+    config: &Config,
+    semester: &semesters::Model,
+    short_name: &str,
+) -> Result<PathBuf> {
+    let semester_type = SemesterType::from_str(&semester.r#type)
+        .ok_or_else(|| MmsError::InvalidSemesterType(semester.r#type.clone()))?;
+    let folder_name =
+        paths::semester_folder_name(config.semester_folder(), semester_type, semester.number);
+    Ok(archive_directory(config).join(folder_name).join(short_name))
+}
+
+/// Move a course to the archive
+///
+/// This function performs the following steps:
+/// 1. Moves the course folder to `.archive/<semester>/<course>` (external courses stay in place)
+/// 2. Sets `is_archived` (and `is_dropped` with `dropped_at` if `dropped`) in the row and TOML
+/// 3. Clears the active course and the `cc` symlink if they pointed at the course,
+///    recording the switch in the history
+pub async fn archive_course(
+    config: &Config,
+    db: &DatabaseConnection,
+    course_id: i64,
+    dropped: bool,
+) -> Result<CourseInfo> {
+    let course = queries::course::get_by_id(db, course_id).await?;
+    if course.is_archived {
+        return Err(MmsError::CourseAlreadyArchived(course.short_name));
+    }
+
+    let semester = queries::semester::get_by_id(db, course.semester_id).await?;
+    let from = PathBuf::from(&course.directory_path);
+    let to = archived_course_directory(config, &semester, &course.short_name)?;
+    let archived = move_course(db, course, &to, semester.id, true, dropped).await?;
+
+    // Archived courses are never active
+    if queries::active::get(db).await?.course_id == Some(course_id) {
+        queries::active::clear_active_course(db).await?;
+        history::record_switch(db, Some(course_id), None, SwitchReason::Archive, Utc::now())
+            .await?;
+    }
+    symlink::unlink_course(config, &from)?;
+
+    Ok(archived.into())
+}
+
+/// Move an archived course back into a semester
+///
+/// Restores into the course's own semester unless `semester_id` is given,
+/// e.g. to retake the course. Clears `is_archived`, `is_dropped` and `dropped_at`.
+pub async fn restore_course(
+    config: &Config,
+    db: &DatabaseConnection,
+    course_id: i64,
+    semester_id: Option<i64>,
+) -> Result<CourseInfo> {
+    let course = queries::course::get_by_id(db, course_id).await?;
+    if !course.is_archived {
+        return Err(MmsError::CourseNotArchived(course.short_name));
+    }
+
+    let semester =
+        queries::semester::get_by_id(db, semester_id.unwrap_or(course.semester_id)).await?;
+    let to = semester_path(config, &semester).join(&course.short_name);
+    let restored = move_course(db, course, &to, semester.id, false, false).await?;
+
+    Ok(restored.into())
+}
+
+/// Archived courses, optionally only the dropped ones
+pub async fn list_archived_courses(
+    db: &DatabaseConnection,
+    only_dropped: bool,
+) -> Result<Vec<courses::Model>> {
+    let mut query = courses::Entity::find().filter(courses::Column::IsArchived.eq(true));
+
+    if only_dropped {
+        query = query.filter(courses::Column::IsDropped.eq(true));
+    }
+
+    Ok(query
+        .order_by_asc(courses::Column::SemesterId)
+        .order_by_asc(courses::Column::ShortName)
+        .all(db)
+        .await?)
+}
+
+/// Move a course folder to `to` and update its row and TOML
+///
+/// The folder is moved back if the database update fails.
+async fn move_course(
+    db: &DatabaseConnection,
+    course: courses::Model,
+    to: &Path,
+    semester_id: i64,
+    is_archived: bool,
+    is_dropped: bool,
+) -> Result<courses::Model> {
+    let from = PathBuf::from(&course.directory_path);
+    let dir = if course.is_external {
+        from.clone()
+    } else {
+        to.to_path_buf()
+    };
+
+    let moved = from != dir && from.exists();
+    if moved {
+        if dir.exists() {
+            return Err(MmsError::FolderExists { from, to: dir });
+        }
+        if let Some(parent) = dir.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::rename(&from, &dir)?;
+    }
+
+    let dropped_at = is_dropped.then(|| course.dropped_at.unwrap_or_else(Utc::now));
+    let is_external = course.is_external;
+
+    let mut active = course.into_active_model();
+    active.semester_id = ActiveValue::Set(semester_id);
+    active.directory_path = ActiveValue::Set(dir.to_string_lossy().to_string());
+    if !is_external {
+        active.toml_path =
+            ActiveValue::Set(Some(dir.join(".course.toml").to_string_lossy().to_string()));
+    }
+    active.is_archived = ActiveValue::Set(is_archived);
+    active.is_dropped = ActiveValue::Set(is_dropped);
+    active.dropped_at = ActiveValue::Set(dropped_at);
+    active.updated_at = ActiveValue::Set(Utc::now());
+
+    let updated = match active.update(db).await {
+        Ok(model) => model,
+        Err(e) => {
+            if moved {
+                let _ = std::fs::rename(&dir, &from);
+            }
+            return Err(e.into());
+        }
+    };
+
+    // A missing TOML is recreated by the next sync
+    if let Ok(toml) = CourseToml::read_from_directory(&dir) {
+        toml.with_archived(is_archived)
+            .with_dropped(is_dropped)
+            .write_to_directory(&dir)?;
+    }
+
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semester::create_semester;
+    use crate::test_support::{self, test_db};
+    use tempfile::TempDir;
+
+    async fn setup() -> (DatabaseConnection, TempDir, Config) {
+        let db = test_db().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            university_base_path: temp_dir.path().to_path_buf(),
+            general: None,
+            grading: None,
+            notes: None,
+            schedule: None,
+            sync: None,
+            attendance: None,
+            study_time: None,
+            notifications: None,
+            hooks: None,
+            logging: None,
+            symlinks: None,
+        };
+        (db, temp_dir, config)
+    }
+
+    async fn semester(db: &DatabaseConnection, config: &Config, number: i64) -> i64 {
+        create_semester(
+            config,
+            db,
+            SemesterType::Bachelor,
+            number,
+            None,
+            None,
+            None,
+            Some("Tübingen".to_string()),
+            true,
+            false,
+        )
+        .await
+        .unwrap()
+        .id
+    }
+
+    async fn course(db: &DatabaseConnection, semester_id: i64, short_name: &str) -> CourseInfo {
+        test_support::course(db, semester_id, short_name, short_name, 6).await
+    }
+
+    #[tokio::test]
+    async fn test_drop_and_restore_course() {
+        let (db, temp_dir, config) = setup().await;
+        let base = temp_dir.path();
+        let b3 = semester(&db, &config, 3).await;
+        let ana = course(&db, b3, "ana").await;
+        queries::active::set_active_course(&db, ana.id, b3)
+            .await
+            .unwrap();
+
+        let dropped = archive_course(&config, &db, ana.id, true).await.unwrap();
+        assert_eq!(dropped.directory_path, base.join(".archive/b3/ana"));
+        assert!(!base.join("b3/ana").exists());
+
+        let toml = CourseToml::read_from_directory(&dropped.directory_path).unwrap();
+        assert!(toml.is_archived && toml.is_dropped);
+
+        let row = queries::course::get_by_id(&db, ana.id).await.unwrap();
+        assert!(row.is_archived && row.is_dropped && row.dropped_at.is_some());
+        assert_eq!(queries::active::get(&db).await.unwrap().course_id, None);
+        let switches = history::list_history(&db, &history::HistoryFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(switches.len(), 1);
+        assert_eq!(switches[0].from_course_id, Some(ana.id));
+        assert_eq!(switches[0].to_course_id, None);
+        assert_eq!(switches[0].reason, SwitchReason::Archive);
+
+        // Left out of schedules, kept for transcripts
+        assert!(
+            queries::course::list_unarchived_by_semester(&db, b3)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(list_archived_courses(&db, true).await.unwrap().len(), 1);
+        assert!(matches!(
+            archive_course(&config, &db, ana.id, false).await,
+            Err(MmsError::CourseAlreadyArchived(_))
+        ));
+
+        // Retake in the next semester
+        let b4 = semester(&db, &config, 4).await;
+        let restored = restore_course(&config, &db, ana.id, Some(b4))
+            .await
+            .unwrap();
+        assert_eq!(restored.directory_path, base.join("b4/ana"));
+        assert_eq!(restored.semester_id, b4);
+
+        let row = queries::course::get_by_id(&db, ana.id).await.unwrap();
+        assert!(!row.is_archived && !row.is_dropped && row.dropped_at.is_none());
+        let toml = CourseToml::read_from_directory(&restored.directory_path).unwrap();
+        assert!(!toml.is_archived && !toml.is_dropped);
+        assert!(list_archived_courses(&db, false).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_archive_refuses_taken_folder() {
+        let (db, temp_dir, config) = setup().await;
+        let b3 = semester(&db, &config, 3).await;
+        let ana = course(&db, b3, "ana").await;
+        std::fs::create_dir_all(temp_dir.path().join(".archive/b3/ana")).unwrap();
+
+        let result = archive_course(&config, &db, ana.id, false).await;
+        assert!(matches!(result, Err(MmsError::FolderExists { .. })));
+        assert!(temp_dir.path().join("b3/ana").exists());
+        assert!(
+            !queries::course::get_by_id(&db, ana.id)
+                .await
+                .unwrap()
+                .is_archived
+        );
+    }
+}
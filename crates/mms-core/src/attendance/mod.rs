@@ -0,0 +1,8 @@
+pub mod operations;
+pub mod types;
+
+pub use operations::{
+    AttendanceStats, attendance_stats, mark_attendance, mark_ended_slot, record_attendance,
+    set_attendance_requirement,
+};
+pub use types::AttendanceStatus;
@@ -0,0 +1,532 @@
+use crate::attendance::types::AttendanceStatus;
+use crate::db::entities::{attendance, attendance_requirements};
+use crate::db::queries;
+use crate::error::{MmsError, Result};
+use crate::service::{Occurrence, ScheduleEngine};
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, IntoActiveModel, Set};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Attendance statistics of a course for one schedule type
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttendanceStats {
+    /// Schedule type (lowercase, e.g. "lecture" or "tutorium")
+    pub schedule_type: String,
+    /// Number of elapsed occurrences
+    pub total: usize,
+    pub attended: usize,
+    pub missed: usize,
+    pub excused: usize,
+    /// Elapsed occurrences without an attendance record
+    pub unmarked: usize,
+    /// Required attendance in percent, if configured
+    pub required_percentage: Option<f64>,
+}
+
+impl AttendanceStats {
+    /// Attendance rate in percent
+    ///
+    /// Excused occurrences are not counted; unmarked occurrences count as not attended.
+    /// Returns `None` if there is nothing to count yet.
+    pub fn percentage(&self) -> Option<f64> {
+        let counted = self.total - self.excused;
+        if counted == 0 {
+            return None;
+        }
+        Some(self.attended as f64 / counted as f64 * 100.0)
+    }
+
+    /// Whether the configured requirement is currently met
+    ///
+    /// Returns `None` if no requirement is configured.
+    pub fn meets_requirement(&self) -> Option<bool> {
+        let required = self.required_percentage?;
+        Some(self.percentage().unwrap_or(100.0) >= required)
+    }
+}
+
+// ============================================================================
+// Recording
+// ============================================================================
+
+/// Record the attendance status of a concrete occurrence
+///
+/// An existing record for the same occurrence is overwritten.
+pub async fn record_attendance(
+    db: &DatabaseConnection,
+    occurrence: &Occurrence,
+    status: AttendanceStatus,
+    is_auto: bool,
+) -> Result<attendance::Model> {
+    let date = occurrence.date.format("%Y-%m-%d").to_string();
+    let start_time = occurrence.start_time.format("%H:%M").to_string();
+    let now = chrono::Utc::now();
+
+    match queries::attendance::get_by_occurrence(db, occurrence.course_id, &date, &start_time)
+        .await?
+    {
+        Some(existing) => {
+            let mut record = existing.into_active_model();
+            record.status = Set(status.to_string());
+            record.is_auto = Set(is_auto);
+            record.updated_at = Set(now);
+            queries::attendance::update(db, record).await
+        }
+        None => {
+            let record = attendance::ActiveModel {
+                course_id: Set(occurrence.course_id),
+                schedule_id: Set(Some(occurrence.schedule_id)),
+                schedule_type: Set(occurrence.schedule_type.to_lowercase()),
+                date: Set(date),
+                start_time: Set(start_time),
+                end_time: Set(occurrence.end_time.format("%H:%M").to_string()),
+                status: Set(status.to_string()),
+                is_auto: Set(is_auto),
+                notes: Set(None),
+                created_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            };
+            Ok(record.insert(db).await?)
+        }
+    }
+}
+
+/// Mark attendance for a course's occurrence on a given date
+///
+/// If several occurrences match, the latest one that has already started
+/// by `now` is chosen (falling back to the first one of the day).
+pub async fn mark_attendance(
+    db: &DatabaseConnection,
+    course_id: i64,
+    date: NaiveDate,
+    schedule_type: Option<&str>,
+    status: AttendanceStatus,
+    now: NaiveDateTime,
+) -> Result<attendance::Model> {
+    let occurrences: Vec<Occurrence> =
+        ScheduleEngine::occurrences_for_course(db, course_id, date, date)
+            .await?
+            .into_iter()
+            .filter(|o| schedule_type.is_none_or(|t| o.is_type(t)))
+            .collect();
+
+    let occurrence = occurrences
+        .iter()
+        .rev()
+        .find(|o| NaiveDateTime::new(o.date, o.start_time) <= now)
+        .or_else(|| occurrences.first())
+        .ok_or_else(|| {
+            MmsError::NotFound(format!(
+                "No scheduled occurrence of course {} on {}",
+                course_id, date
+            ))
+        })?;
+
+    record_attendance(db, occurrence, status, false).await
+}
+
+/// Mark an ended slot as attended if its course was active during it
+///
+/// Whether the course was active is read from the activation intervals, so a
+/// slot counts even if the daemon switched to the next course before it ended.
+/// Existing records are never overwritten.
+///
+/// Returns the new record, if one was created.
+pub async fn mark_ended_slot(
+    db: &DatabaseConnection,
+    occurrence: &Occurrence,
+) -> Result<Option<attendance::Model>> {
+    let date = occurrence.date.format("%Y-%m-%d").to_string();
+    let start_time = occurrence.start_time.format("%H:%M").to_string();
+    if queries::attendance::get_by_occurrence(db, occurrence.course_id, &date, &start_time)
+        .await?
+        .is_some()
+    {
+        return Ok(None);
+    }
+
+    let to_utc = |local: NaiveDateTime| {
+        Local
+            .from_local_datetime(&local)
+            .earliest()
+            .map_or_else(|| Utc.from_utc_datetime(&local), |t| t.with_timezone(&Utc))
+    };
+    let start = to_utc(NaiveDateTime::new(occurrence.date, occurrence.start_time));
+    let end = to_utc(NaiveDateTime::new(occurrence.date, occurrence.end_time));
+
+    let was_active = queries::activation_intervals::list_overlapping(db, start, end)
+        .await?
+        .iter()
+        .any(|interval| interval.course_id == occurrence.course_id);
+    if !was_active {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        record_attendance(db, occurrence, AttendanceStatus::Attended, true).await?,
+    ))
+}
+
+// ============================================================================
+// Requirements & Statistics
+// ============================================================================
+
+/// Set the required attendance (in percent) for a schedule type of a course
+pub async fn set_attendance_requirement(
+    db: &DatabaseConnection,
+    course_id: i64,
+    schedule_type: &str,
+    percentage: f64,
+) -> Result<attendance_requirements::Model> {
+    if !(0.0..=100.0).contains(&percentage) {
+        return Err(MmsError::InvalidAttendanceRequirement(percentage));
+    }
+
+    // Verify course exists
+    queries::course::get_by_id(db, course_id).await?;
+
+    let schedule_type = schedule_type.to_lowercase();
+    let now = chrono::Utc::now();
+
+    let existing = queries::attendance::list_requirements_by_course(db, course_id)
+        .await?
+        .into_iter()
+        .find(|r| r.schedule_type == schedule_type);
+
+    match existing {
+        Some(existing) => {
+            let mut requirement = existing.into_active_model();
+            requirement.required_percentage = Set(percentage);
+            requirement.updated_at = Set(now);
+            queries::attendance::update_requirement(db, requirement).await
+        }
+        None => {
+            let requirement = attendance_requirements::ActiveModel {
+                course_id: Set(course_id),
+                schedule_type: Set(schedule_type),
+                required_percentage: Set(percentage),
+                created_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            };
+            Ok(requirement.insert(db).await?)
+        }
+    }
+}
+
+/// Compute attendance statistics of a course per schedule type
+///
+/// This function performs the following steps:
+/// 1. Enumerates all occurrences that have ended by `now`
+/// 2. Matches them with the recorded attendance
+/// 3. Attaches the configured requirement of each schedule type
+///
+/// Schedule types that have a requirement but no occurrences yet are included.
+pub async fn attendance_stats(
+    db: &DatabaseConnection,
+    course_id: i64,
+    now: NaiveDateTime,
+) -> Result<Vec<AttendanceStats>> {
+    let schedules = queries::schedule::list_by_course(db, course_id).await?;
+    let mut from: Option<NaiveDate> = None;
+    for schedule in &schedules {
+        let start = NaiveDate::parse_from_str(&schedule.start_date, "%Y-%m-%d")?;
+        from = Some(from.map_or(start, |f| f.min(start)));
+    }
+
+    let records: HashMap<(String, String), AttendanceStatus> =
+        queries::attendance::list_by_course(db, course_id)
+            .await?
+            .into_iter()
+            .filter_map(|r| {
+                let status = AttendanceStatus::from_str(&r.status).ok()?;
+                Some(((r.date, r.start_time), status))
+            })
+            .collect();
+
+    let mut stats: BTreeMap<String, AttendanceStats> = BTreeMap::new();
+    let empty = |schedule_type: &str| AttendanceStats {
+        schedule_type: schedule_type.to_string(),
+        total: 0,
+        attended: 0,
+        missed: 0,
+        excused: 0,
+        unmarked: 0,
+        required_percentage: None,
+    };
+
+    if let Some(from) = from {
+        let occurrences = ScheduleEngine::occurrences_for_course(db, course_id, from, now.date())
+            .await?
+            .into_iter()
+            .filter(|o| NaiveDateTime::new(o.date, o.end_time) <= now);
+
+        for occurrence in occurrences {
+            let schedule_type = occurrence.schedule_type.to_lowercase();
+            let entry = stats
+                .entry(schedule_type.clone())
+                .or_insert_with(|| empty(&schedule_type));
+            entry.total += 1;
+
+            let key = (
+                occurrence.date.format("%Y-%m-%d").to_string(),
+                occurrence.start_time.format("%H:%M").to_string(),
+            );
+            match records.get(&key) {
+                Some(AttendanceStatus::Attended) => entry.attended += 1,
+                Some(AttendanceStatus::Missed) => entry.missed += 1,
+                Some(AttendanceStatus::Excused) => entry.excused += 1,
+                None => entry.unmarked += 1,
+            }
+        }
+    }
+
+    for requirement in queries::attendance::list_requirements_by_course(db, course_id).await? {
+        stats
+            .entry(requirement.schedule_type.clone())
+            .or_insert_with(|| empty(&requirement.schedule_type))
+            .required_percentage = Some(requirement.required_percentage);
+    }
+
+    Ok(stats.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::entities::course_schedules;
+    use crate::test_support::semester_with_course;
+    use tempfile::TempDir;
+
+    /// Set up a course with a weekly tutorial (Tuesday 14:00-16:00, October 2024)
+    async fn setup_course() -> (DatabaseConnection, TempDir, i64) {
+        let temp_dir = TempDir::new().unwrap();
+        let (db, course) = semester_with_course(temp_dir.path(), "algo", "Algorithms").await;
+
+        course_schedules::ActiveModel {
+            course_id: Set(course.id),
+            schedule_type: Set("Tutorium".to_string()),
+            day_of_week: Set(1),
+            start_time: Set("14:00".to_string()),
+            end_time: Set("16:00".to_string()),
+            start_date: Set("2024-10-01".to_string()),
+            end_date: Set("2024-10-31".to_string()),
+            priority: Set(0),
+            created_at: Set(chrono::Utc::now()),
+            updated_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        (db, temp_dir, course.id)
+    }
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn day(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_mark_and_stats() {
+        let (db, _temp_dir, course_id) = setup_course().await;
+        let now = at("2024-10-30", "00:00");
+
+        // Tuesdays: 1, 8, 15, 22, 29
+        mark_attendance(
+            &db,
+            course_id,
+            day("2024-10-01"),
+            None,
+            AttendanceStatus::Attended,
+            now,
+        )
+        .await
+        .unwrap();
+        mark_attendance(
+            &db,
+            course_id,
+            day("2024-10-08"),
+            None,
+            AttendanceStatus::Missed,
+            now,
+        )
+        .await
+        .unwrap();
+        mark_attendance(
+            &db,
+            course_id,
+            day("2024-10-15"),
+            None,
+            AttendanceStatus::Excused,
+            now,
+        )
+        .await
+        .unwrap();
+        // Re-marking overwrites the previous status
+        mark_attendance(
+            &db,
+            course_id,
+            day("2024-10-22"),
+            None,
+            AttendanceStatus::Missed,
+            now,
+        )
+        .await
+        .unwrap();
+        mark_attendance(
+            &db,
+            course_id,
+            day("2024-10-22"),
+            None,
+            AttendanceStatus::Attended,
+            now,
+        )
+        .await
+        .unwrap();
+
+        set_attendance_requirement(&db, course_id, "Tutorium", 80.0)
+            .await
+            .unwrap();
+
+        let stats = attendance_stats(&db, course_id, now).await.unwrap();
+        assert_eq!(stats.len(), 1);
+        let tutorial = &stats[0];
+        assert_eq!(tutorial.schedule_type, "tutorium");
+        assert_eq!(tutorial.total, 5);
+        assert_eq!(tutorial.attended, 2);
+        assert_eq!(tutorial.missed, 1);
+        assert_eq!(tutorial.excused, 1);
+        assert_eq!(tutorial.unmarked, 1);
+        assert_eq!(tutorial.percentage(), Some(50.0));
+        assert_eq!(tutorial.meets_requirement(), Some(false));
+    }
+
+    #[tokio::test]
+    async fn test_mark_without_occurrence_fails() {
+        let (db, _temp_dir, course_id) = setup_course().await;
+
+        // Wednesday - no tutorial scheduled
+        let result = mark_attendance(
+            &db,
+            course_id,
+            day("2024-10-02"),
+            None,
+            AttendanceStatus::Attended,
+            at("2024-10-02", "18:00"),
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mark_ended_slot_after_early_switch() {
+        let (db, _temp_dir, course_id) = setup_course().await;
+        let occurrence = ScheduleEngine::occurrences_for_course(
+            &db,
+            course_id,
+            day("2024-10-01"),
+            day("2024-10-08"),
+        )
+        .await
+        .unwrap();
+        let to_utc = |local: NaiveDateTime| {
+            Local
+                .from_local_datetime(&local)
+                .earliest()
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+
+        // Active 13:55-15:50, then switched to the next course before the slot ended
+        queries::activation_intervals::open(&db, course_id, to_utc(at("2024-10-01", "13:55")))
+            .await
+            .unwrap();
+        queries::activation_intervals::close_open(&db, to_utc(at("2024-10-01", "15:50")))
+            .await
+            .unwrap();
+
+        let created = mark_ended_slot(&db, &occurrence[0]).await.unwrap();
+        assert!(created.is_some_and(|record| record.is_auto));
+        assert!(
+            mark_ended_slot(&db, &occurrence[0])
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // Not active during the slot on the 8th
+        assert!(
+            mark_ended_slot(&db, &occurrence[1])
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mark_ended_slot_keeps_manual_records() {
+        let (db, _temp_dir, course_id) = setup_course().await;
+        let occurrence = ScheduleEngine::occurrences_for_course(
+            &db,
+            course_id,
+            day("2024-10-08"),
+            day("2024-10-08"),
+        )
+        .await
+        .unwrap();
+
+        mark_attendance(
+            &db,
+            course_id,
+            day("2024-10-08"),
+            None,
+            AttendanceStatus::Excused,
+            at("2024-10-08", "18:00"),
+        )
+        .await
+        .unwrap();
+
+        // Active during the whole slot
+        let activated = Local
+            .from_local_datetime(&at("2024-10-08", "13:55"))
+            .earliest()
+            .unwrap()
+            .with_timezone(&Utc);
+        queries::activation_intervals::open(&db, course_id, activated)
+            .await
+            .unwrap();
+
+        assert!(
+            mark_ended_slot(&db, &occurrence[0])
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let records = queries::attendance::list_by_course(&db, course_id)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].status, "excused");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_requirement() {
+        let (db, _temp_dir, course_id) = setup_course().await;
+        let result = set_attendance_requirement(&db, course_id, "lecture", 120.0).await;
+        assert!(matches!(
+            result,
+            Err(MmsError::InvalidAttendanceRequirement(_))
+        ));
+    }
+}
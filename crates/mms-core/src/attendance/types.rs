@@ -0,0 +1,60 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Attendance status of a single lecture or tutorial occurrence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttendanceStatus {
+    /// Attended the occurrence
+    Attended,
+    /// Missed the occurrence without excuse
+    Missed,
+    /// Missed the occurrence with a valid excuse (not counted)
+    Excused,
+}
+
+impl FromStr for AttendanceStatus {
+    type Err = ();
+
+    /// Parse from a string (case-insensitive)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "attended" | "present" | "a" => Ok(AttendanceStatus::Attended),
+            "missed" | "absent" | "m" => Ok(AttendanceStatus::Missed),
+            "excused" | "e" => Ok(AttendanceStatus::Excused),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for AttendanceStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttendanceStatus::Attended => write!(f, "attended"),
+            AttendanceStatus::Missed => write!(f, "missed"),
+            AttendanceStatus::Excused => write!(f, "excused"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            AttendanceStatus::Attended,
+            AttendanceStatus::Missed,
+            AttendanceStatus::Excused,
+        ] {
+            assert_eq!(AttendanceStatus::from_str(&status.to_string()), Ok(status));
+        }
+        assert_eq!(
+            AttendanceStatus::from_str("Present"),
+            Ok(AttendanceStatus::Attended)
+        );
+        assert!(AttendanceStatus::from_str("late").is_err());
+    }
+}
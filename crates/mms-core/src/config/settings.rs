@@ -22,6 +22,12 @@ pub struct Config {
     pub notes: Option<NotesConfig>,
     pub schedule: Option<ScheduleConfig>,
     pub sync: Option<SyncConfig>,
+    pub attendance: Option<AttendanceConfig>,
+    pub study_time: Option<StudyTimeConfig>,
+    pub notifications: Option<NotificationsConfig>,
+    pub hooks: Option<HooksConfig>,
+    pub logging: Option<LoggingConfig>,
+    pub symlinks: Option<SymlinksConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +38,13 @@ pub struct GeneralConfig {
     pub default_pdf_viewer: Option<String>,
     pub default_location: Option<String>,
     pub symlink_path: PathBuf,
+    /// Semester folder names; `{type}` is the prefix (b, m), `{number:02}` pads the number
+    #[serde(default = "default_semester_folder")]
+    pub semester_folder: String,
+}
+
+fn default_semester_folder() -> String {
+    paths::DEFAULT_SEMESTER_FOLDER.to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,9 +54,22 @@ pub struct GradingConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotesConfig {
+    /// Run `watch_command` on the lecture note while a lecture is running
     pub auto_watch: bool,
+    /// Open the compiled PDF in `general.default_pdf_viewer` (required) while a lecture is running
     pub auto_open_pdf: bool,
+    /// Template for new lecture notes (`<config dir>/templates/<name>.typ`)
     pub template: String,
+    /// Open the lecture note in `general.default_editor` when a lecture starts
+    #[serde(default)]
+    pub auto_open: bool,
+    /// Command compiling the note on changes; `{input}` and `{output}` are replaced by the paths
+    #[serde(default = "default_watch_command")]
+    pub watch_command: String,
+}
+
+fn default_watch_command() -> String {
+    "typst watch {input} {output}".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +84,163 @@ pub struct ScheduleConfig {
 pub struct SyncConfig {
     pub auto_fetch: bool,
     pub platforms: Vec<String>,
+    /// How fields differing between `.course.toml`/`.semester.toml` and the database are merged
+    #[serde(default)]
+    pub merge_strategy: MergeStrategy,
+}
+
+/// Which side wins when a TOML file and its database row disagree
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergeStrategy {
+    /// Hand edits of the TOML files are copied into the database
+    TomlWins,
+    /// The TOML files are rewritten from the database
+    DbWins,
+    /// Ask for every differing field
+    #[default]
+    Interactive,
+}
+
+impl std::str::FromStr for MergeStrategy {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "toml-wins" | "toml" => Ok(MergeStrategy::TomlWins),
+            "db-wins" | "db" => Ok(MergeStrategy::DbWins),
+            "interactive" => Ok(MergeStrategy::Interactive),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttendanceConfig {
+    /// Automatically mark occurrences as attended when the course was active
+    pub auto_mark: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StudyTimeConfig {
+    /// Expected workload per ECTS credit in hours
+    pub hours_per_ects: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationsConfig {
+    /// Which backend delivers notifications
+    #[serde(default)]
+    pub backend: NotifierBackend,
+    /// Shell command for the `command` backend
+    pub command: Option<String>,
+}
+
+/// Notification backend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifierBackend {
+    /// D-Bus on Linux, `osascript` on macOS
+    #[default]
+    Auto,
+    Dbus,
+    Osascript,
+    Command,
+}
+
+/// Shell commands run by the daemon (`sh -c`, with `MMS_*` environment variables)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// Hooks running longer than this are killed
+    #[serde(default = "default_hook_timeout")]
+    pub timeout_seconds: u64,
+    /// Run when the active course changes
+    #[serde(default)]
+    pub on_switch: Vec<String>,
+    /// Run when a lecture, tutorial, ... begins
+    #[serde(default)]
+    pub on_slot_start: Vec<String>,
+    /// Run when a lecture, tutorial, ... ends
+    #[serde(default)]
+    pub on_slot_end: Vec<String>,
+    /// Run once when an open exercise or todo is due within `deadline_lead_minutes`
+    #[serde(default)]
+    pub on_deadline: Vec<String>,
+    /// How long before a deadline `on_deadline` runs
+    #[serde(default = "default_deadline_lead")]
+    pub deadline_lead_minutes: u64,
+}
+
+fn default_hook_timeout() -> u64 {
+    30
+}
+
+fn default_deadline_lead() -> u64 {
+    24 * 60
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// Minimum level (`error`, `warn`, `info`, `debug`, `trace`) or an `EnvFilter` directive
+    #[serde(default = "default_log_level")]
+    pub level: String,
+    /// Number of daily log files to keep
+    #[serde(default = "default_log_files")]
+    pub max_files: usize,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: default_log_level(),
+            max_files: default_log_files(),
+        }
+    }
+}
+
+/// Names of the links in `general.symlink_path`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymlinksConfig {
+    /// Link to the current semester folder
+    #[serde(default = "default_semester_link")]
+    pub semester: String,
+    /// Link to the current course folder
+    #[serde(default = "default_course_link")]
+    pub course: String,
+    /// Link to the notes file of the current lecture (off if unset)
+    pub lecture_notes: Option<String>,
+    /// Link to the folder of the next open exercise (off if unset)
+    pub next_exercise: Option<String>,
+    /// Link to the newest slide deck (off if unset)
+    pub slides: Option<String>,
+}
+
+impl Default for SymlinksConfig {
+    fn default() -> Self {
+        Self {
+            semester: default_semester_link(),
+            course: default_course_link(),
+            lecture_notes: None,
+            next_exercise: None,
+            slides: None,
+        }
+    }
+}
+
+fn default_semester_link() -> String {
+    "cs".to_string()
+}
+
+fn default_course_link() -> String {
+    "cc".to_string()
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+fn default_log_files() -> usize {
+    14
 }
 
 // ==================================================================================
@@ -125,9 +308,22 @@ impl Config {
             });
         }
 
+        if let Some(general) = &self.general {
+            paths::validate_semester_folder(&general.semester_folder)?;
+        }
+
         Ok(())
     }
 
+    /// Pattern of semester folder names
+    pub fn semester_folder(&self) -> &str {
+        self.general
+            .as_ref()
+            .map_or(paths::DEFAULT_SEMESTER_FOLDER, |g| {
+                g.semester_folder.as_str()
+            })
+    }
+
     /// Check if a valid config exists at the default location.
     pub fn exists() -> bool {
         Self::load().is_ok()
@@ -171,6 +367,12 @@ mod tests {
             notes: None,
             schedule: None,
             sync: None,
+            attendance: None,
+            study_time: None,
+            notifications: None,
+            hooks: None,
+            logging: None,
+            symlinks: None,
         };
 
         let result = config.validate();
@@ -192,6 +394,12 @@ mod tests {
             notes: None,
             schedule: None,
             sync: None,
+            attendance: None,
+            study_time: None,
+            notifications: None,
+            hooks: None,
+            logging: None,
+            symlinks: None,
         };
 
         let result = config.validate();
@@ -213,6 +421,12 @@ mod tests {
             notes: None,
             schedule: None,
             sync: None,
+            attendance: None,
+            study_time: None,
+            notifications: None,
+            hooks: None,
+            logging: None,
+            symlinks: None,
         };
 
         let result = config.validate();
@@ -230,6 +444,7 @@ mod tests {
                 default_pdf_viewer: Some("skim".to_string()),
                 default_location: Some("University".to_string()),
                 symlink_path: PathBuf::from_str("~").unwrap(),
+                semester_folder: "{type}{number:02}".to_string(),
             }),
             grading: Some(GradingConfig {
                 default_scheme: "german".to_string(),
@@ -238,6 +453,8 @@ mod tests {
                 auto_watch: true,
                 auto_open_pdf: true,
                 template: "default".to_string(),
+                auto_open: true,
+                watch_command: default_watch_command(),
             }),
             schedule: Some(ScheduleConfig {
                 auto_switch: true,
@@ -248,6 +465,31 @@ mod tests {
             sync: Some(SyncConfig {
                 auto_fetch: false,
                 platforms: vec![],
+                merge_strategy: MergeStrategy::TomlWins,
+            }),
+            attendance: Some(AttendanceConfig { auto_mark: true }),
+            study_time: Some(StudyTimeConfig {
+                hours_per_ects: 30.0,
+            }),
+            notifications: Some(NotificationsConfig {
+                backend: NotifierBackend::Command,
+                command: Some("notify-send \"$MMS_NOTIFY_SUMMARY\"".to_string()),
+            }),
+            hooks: Some(HooksConfig {
+                timeout_seconds: 30,
+                on_switch: vec![],
+                on_slot_start: vec!["code \"$MMS_COURSE_DIR\"".to_string()],
+                on_slot_end: vec![],
+                on_deadline: vec![],
+                deadline_lead_minutes: 24 * 60,
+            }),
+            logging: Some(LoggingConfig::default()),
+            symlinks: Some(SymlinksConfig {
+                semester: "cs".to_string(),
+                course: "cc".to_string(),
+                lecture_notes: Some("cl".to_string()),
+                next_exercise: Some("ce".to_string()),
+                slides: None,
             }),
         };
 
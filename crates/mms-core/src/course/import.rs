@@ -0,0 +1,686 @@
+//! Importing course folders from other folder structures
+//!
+//! A folder is either adopted in place as an external course or copied or
+//! moved into `<semester>/<course>`. Slides and exercise folders are
+//! recognised by common names and renamed to `slides/` and `exercises/`
+//! when the folder ends up in the standard layout.
+
+use crate::config::Config;
+use crate::course::operations::{CourseInfo, create_course};
+use crate::course::types::{CourseCode, Ects};
+use crate::db::entities::courses;
+use crate::db::queries;
+use crate::error::{MmsError, Result};
+use crate::symlink;
+use crate::sync::import::subdirectories;
+use crate::sync::semester_path;
+use crate::toml::CourseToml;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ActiveValue, DatabaseConnection, IntoActiveModel};
+use std::fs;
+use std::os::unix::fs as unix_fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tracing::warn;
+
+/// Folder names recognised as slides, compared without case and punctuation
+const SLIDES_NAMES: &[&str] = &[
+    "slides",
+    "folien",
+    "lectureslides",
+    "lectures",
+    "vorlesung",
+    "vorlesungen",
+    "skript",
+];
+
+/// Folder names recognised as exercises, compared without case and punctuation
+const EXERCISES_NAMES: &[&str] = &[
+    "exercises",
+    "exercise",
+    "uebungen",
+    "übungen",
+    "uebung",
+    "übung",
+    "assignments",
+    "homework",
+    "sheets",
+    "blaetter",
+    "blätter",
+];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// What `import_course` does with the folder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Keep the folder where it is (external course)
+    Adopt,
+    /// Copy the folder into the semester folder
+    Copy,
+    /// Move the folder into the semester folder
+    Move,
+}
+
+impl FromStr for ImportMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "adopt" | "in-place" => Ok(ImportMode::Adopt),
+            "copy" => Ok(ImportMode::Copy),
+            "move" => Ok(ImportMode::Move),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Folders and repository found in a course folder
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CourseLayout {
+    pub slides_dir: Option<PathBuf>,
+    pub exercises_dir: Option<PathBuf>,
+    pub has_git_repo: bool,
+    pub git_remote_url: Option<String>,
+}
+
+/// An imported or converted course and what was found in its folder
+#[derive(Debug, Clone)]
+pub struct ImportedCourse {
+    pub course: CourseInfo,
+    pub layout: CourseLayout,
+}
+
+// ============================================================================
+// Detection
+// ============================================================================
+
+/// Look for slides and exercise folders and a git repository in `dir`
+pub fn detect_layout(dir: &Path) -> Result<CourseLayout> {
+    let subdirs = subdirectories(dir)?;
+
+    Ok(CourseLayout {
+        slides_dir: find_folder(&subdirs, SLIDES_NAMES),
+        exercises_dir: find_folder(&subdirs, EXERCISES_NAMES),
+        has_git_repo: dir.join(".git").exists(),
+        git_remote_url: git_remote_url(dir),
+    })
+}
+
+/// First folder with one of `names`, preferring the first name
+fn find_folder(subdirs: &[PathBuf], names: &[&str]) -> Option<PathBuf> {
+    let normalized = |path: &PathBuf| {
+        path.file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+    };
+
+    names
+        .iter()
+        .find_map(|name| subdirs.iter().find(|dir| normalized(dir) == *name))
+        .cloned()
+}
+
+/// URL of the `origin` remote (or the first remote) in `.git/config`
+fn git_remote_url(dir: &Path) -> Option<String> {
+    let config = fs::read_to_string(dir.join(".git").join("config")).ok()?;
+
+    let mut remote = None;
+    let mut urls = Vec::new();
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            remote = line
+                .strip_prefix("[remote \"")
+                .and_then(|r| r.strip_suffix("\"]"))
+                .map(str::to_string);
+        } else if let Some(name) = &remote
+            && let Some((key, value)) = line.split_once('=')
+            && key.trim() == "url"
+        {
+            urls.push((name.clone(), value.trim().to_string()));
+        }
+    }
+
+    urls.iter()
+        .find(|(name, _)| name == "origin")
+        .or(urls.first())
+        .map(|(_, url)| url.clone())
+}
+
+/// Folders renamed by [`standardize_layout`] as (original, standard) paths
+type Renames = Vec<(PathBuf, PathBuf)>;
+
+/// Rename the detected slides and exercise folders to `slides/` and `exercises/`
+///
+/// A folder is left alone if the standard name is taken. On error the
+/// folders renamed so far get their names back.
+fn standardize_layout(dir: &Path) -> Result<(CourseLayout, Renames)> {
+    let mut layout = detect_layout(dir)?;
+    let mut renames = Renames::new();
+
+    for (found, standard) in [
+        (&mut layout.slides_dir, "slides"),
+        (&mut layout.exercises_dir, "exercises"),
+    ] {
+        let target = dir.join(standard);
+        if let Some(path) = found
+            && !target.exists()
+        {
+            if let Err(e) = fs::rename(&*path, &target) {
+                revert_renames(&renames);
+                return Err(e.into());
+            }
+            renames.push((path.clone(), target.clone()));
+            *path = target;
+        }
+    }
+
+    Ok((layout, renames))
+}
+
+/// Give the folders renamed by [`standardize_layout`] their names back
+fn revert_renames(renames: &Renames) {
+    for (original, standard) in renames.iter().rev() {
+        let _ = fs::rename(standard, original);
+    }
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+/// Import a course folder from another folder structure
+///
+/// This function performs the following steps:
+/// 1. Detects slides and exercise folders and the git remote
+/// 2. For `Copy`/`Move`, transfers the folder to `<semester>/<short_name>` and
+///    renames the detected folders to `slides/` and `exercises/`
+/// 3. Creates the course (external for `Adopt`)
+/// 4. Writes `.course.toml`, keeping custom keys of an existing one
+///
+/// A transferred folder gets its folder names back and is moved back (or the
+/// copy removed) if renaming its folders or creating the course fails.
+#[allow(clippy::too_many_arguments)]
+pub async fn import_course(
+    config: &Config,
+    db: &DatabaseConnection,
+    source: &Path,
+    semester_id: i64,
+    short_name: CourseCode,
+    name: String,
+    ects: Ects,
+    mode: ImportMode,
+) -> Result<ImportedCourse> {
+    let source = source.canonicalize()?;
+    if !source.is_dir() {
+        return Err(MmsError::NotFound(format!(
+            "Course folder {}",
+            source.display()
+        )));
+    }
+    let metadata = CourseToml::read_from_directory(&source)
+        .map(|toml| toml.metadata)
+        .unwrap_or_default();
+
+    let move_files = mode == ImportMode::Move;
+    let (dir, layout, renames) = match mode {
+        ImportMode::Adopt => (source.clone(), detect_layout(&source)?, Renames::new()),
+        ImportMode::Copy | ImportMode::Move => {
+            let semester = queries::semester::get_by_id(db, semester_id).await?;
+            let target = semester_path(config, &semester).join(short_name.as_str());
+            check_target(&source, &target)?;
+            transfer(&source, &target, move_files)?;
+            match standardize_layout(&target) {
+                Ok((layout, renames)) => (target, layout, renames),
+                Err(e) => {
+                    undo_transfer(&source, &target, move_files);
+                    return Err(e);
+                }
+            }
+        }
+    };
+
+    let is_external = mode == ImportMode::Adopt;
+    let created = create_course(
+        db,
+        semester_id,
+        short_name,
+        name,
+        ects,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        is_external,
+        is_external.then(|| source.to_string_lossy().to_string()),
+        layout.has_git_repo,
+        layout.git_remote_url.clone(),
+    )
+    .await;
+
+    let created = match created {
+        Ok(created) => created,
+        Err(e) => {
+            if mode != ImportMode::Adopt {
+                revert_renames(&renames);
+                undo_transfer(&source, &dir, move_files);
+            }
+            return Err(e);
+        }
+    };
+
+    let model = queries::course::get_by_id(db, created.id).await?;
+    let model = write_toml(db, model, &dir, metadata).await?;
+
+    Ok(ImportedCourse {
+        course: model.into(),
+        layout,
+    })
+}
+
+/// Turn an external course into a standard one
+///
+/// This function performs the following steps:
+/// 1. Copies or moves the folder to `<semester>/<short_name>`
+/// 2. Renames the detected slides and exercise folders to `slides/` and `exercises/`
+/// 3. Clears `is_external` and `original_path` in the row and TOML
+/// 4. Relinks `cc` if it pointed into the old folder
+///
+/// The folder is restored like in [`import_course`] if steps 2 or 3 fail.
+pub async fn convert_course(
+    config: &Config,
+    db: &DatabaseConnection,
+    course_id: i64,
+    move_files: bool,
+) -> Result<ImportedCourse> {
+    let course = queries::course::get_by_id(db, course_id).await?;
+    if !course.is_external {
+        return Err(MmsError::CourseNotExternal(course.short_name));
+    }
+
+    let semester = queries::semester::get_by_id(db, course.semester_id).await?;
+    let source = PathBuf::from(&course.directory_path);
+    let target = semester_path(config, &semester).join(&course.short_name);
+    let metadata = CourseToml::read_from_directory(&source)
+        .map(|toml| toml.metadata)
+        .unwrap_or_default();
+
+    // Without a source folder there is nothing to move back, only to remove
+    let moved = source.exists() && move_files;
+    if source.exists() {
+        check_target(&source, &target)?;
+        transfer(&source, &target, move_files)?;
+    } else {
+        fs::create_dir_all(&target)?;
+    }
+    let (layout, renames) = match standardize_layout(&target) {
+        Ok(standardized) => standardized,
+        Err(e) => {
+            undo_transfer(&source, &target, moved);
+            return Err(e);
+        }
+    };
+
+    let mut active = course.into_active_model();
+    active.directory_path = ActiveValue::Set(target.to_string_lossy().to_string());
+    active.is_external = ActiveValue::Set(false);
+    active.original_path = ActiveValue::Set(None);
+    active.has_git_repo = ActiveValue::Set(layout.has_git_repo);
+    active.git_remote_url = ActiveValue::Set(layout.git_remote_url.clone());
+    let model = match active.update(db).await {
+        Ok(model) => model,
+        Err(e) => {
+            revert_renames(&renames);
+            undo_transfer(&source, &target, moved);
+            return Err(e.into());
+        }
+    };
+    let model = write_toml(db, model, &target, metadata).await?;
+
+    if move_files {
+        symlink::relink(config, &source, &target)?;
+    }
+
+    Ok(ImportedCourse {
+        course: model.into(),
+        layout,
+    })
+}
+
+/// `.course.toml` mirroring a course row
+pub(crate) fn course_toml(model: &courses::Model) -> CourseToml {
+    CourseToml {
+        short_name: model.short_name.clone(),
+        name: model.name.clone(),
+        uid: model.uid.clone(),
+        ects: model.ects as i32,
+        lecturer: model.lecturer.clone(),
+        lecturer_email: model.lecturer_email.clone(),
+        tutor: model.tutor.clone(),
+        tutor_email: model.tutor_email.clone(),
+        learning_platform_url: model.learning_platform_url.clone(),
+        university: model.university.clone(),
+        location: model.location.clone(),
+        is_external: model.is_external,
+        original_path: model.original_path.clone(),
+        is_dropped: model.is_dropped,
+        is_archived: model.is_archived,
+        has_git_repo: model.has_git_repo,
+        git_remote_url: model.git_remote_url.clone(),
+        exercise_naming: model.exercise_naming.clone(),
+        exercise_file_naming: model.exercise_file_naming.clone(),
+        metadata: toml::Table::new(),
+    }
+}
+
+/// Write the row's `.course.toml` into `dir` and record it in the row
+async fn write_toml(
+    db: &DatabaseConnection,
+    model: courses::Model,
+    dir: &Path,
+    metadata: toml::Table,
+) -> Result<courses::Model> {
+    course_toml(&model)
+        .with_metadata(metadata)
+        .write_to_directory(dir)?;
+
+    let mut active = model.into_active_model();
+    active.toml_path =
+        ActiveValue::Set(Some(dir.join(".course.toml").to_string_lossy().to_string()));
+    active.toml_exists = ActiveValue::Set(true);
+    active.exists_on_disk = ActiveValue::Set(true);
+    active.last_scanned_at = ActiveValue::Set(Some(Utc::now()));
+    active.updated_at = ActiveValue::Set(Utc::now());
+    Ok(active.update(db).await?)
+}
+
+// ============================================================================
+// Filesystem
+// ============================================================================
+
+/// Fail if `target` is taken or inside `source`
+fn check_target(source: &Path, target: &Path) -> Result<()> {
+    if target.exists() {
+        return Err(MmsError::FolderExists {
+            from: source.to_path_buf(),
+            to: target.to_path_buf(),
+        });
+    }
+    if target.starts_with(source) {
+        return Err(MmsError::Other(format!(
+            "Cannot import {} into itself",
+            source.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Copy or move a folder, creating the parent of `to`
+///
+/// A partial copy is removed. If the source of a move cannot be removed
+/// after copying, the rest of it is left behind with a warning.
+fn transfer(from: &Path, to: &Path, move_files: bool) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Renaming fails across filesystems, copying does not
+    if move_files && fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    if let Err(e) = copy_dir_all(from, to) {
+        let _ = fs::remove_dir_all(to);
+        return Err(e.into());
+    }
+    if move_files && let Err(e) = fs::remove_dir_all(from) {
+        warn!(from = %from.display(), "Cannot remove the moved folder: {}", e);
+    }
+
+    Ok(())
+}
+
+/// Undo a [`transfer`]: move the folder back or remove the copy
+fn undo_transfer(from: &Path, to: &Path, move_files: bool) {
+    if move_files {
+        let _ = transfer(to, from, true);
+    } else {
+        let _ = fs::remove_dir_all(to);
+    }
+}
+
+/// Copy a folder recursively, keeping symlinks as symlinks
+fn copy_dir_all(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = to.join(entry.file_name());
+
+        if file_type.is_dir() {
+            copy_dir_all(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            unix_fs::symlink(fs::read_link(entry.path())?, &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semester::{SemesterType, create_semester};
+    use crate::test_support::test_db;
+    use sea_orm::ConnectionTrait;
+    use tempfile::TempDir;
+
+    async fn setup() -> (DatabaseConnection, TempDir, Config, i64) {
+        let db = test_db().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            university_base_path: temp_dir.path().join("Studies"),
+            general: None,
+            grading: None,
+            notes: None,
+            schedule: None,
+            sync: None,
+            attendance: None,
+            study_time: None,
+            notifications: None,
+            hooks: None,
+            logging: None,
+            symlinks: None,
+        };
+        let semester = create_semester(
+            &config,
+            &db,
+            SemesterType::Bachelor,
+            3,
+            None,
+            None,
+            None,
+            Some("Tübingen".to_string()),
+            true,
+            false,
+        )
+        .await
+        .unwrap();
+        (db, temp_dir, config, semester.id)
+    }
+
+    /// An old course folder with slides, exercise sheets and a git remote
+    fn old_course(temp_dir: &TempDir) -> PathBuf {
+        let dir = temp_dir.path().join("Old/Algorithmen");
+        fs::create_dir_all(dir.join("Folien")).unwrap();
+        fs::create_dir_all(dir.join("Übungen/Blatt 1")).unwrap();
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        fs::write(dir.join("Folien/01.pdf"), b"%PDF").unwrap();
+        fs::write(
+            dir.join(".git/config"),
+            "[core]\n\tbare = false\n[remote \"upstream\"]\n\turl = git@example.com:a.git\n\
+             [remote \"origin\"]\n\turl = git@example.com:b.git\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_detect_layout() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = old_course(&temp_dir);
+
+        let layout = detect_layout(&dir).unwrap();
+        assert_eq!(layout.slides_dir, Some(dir.join("Folien")));
+        assert_eq!(layout.exercises_dir, Some(dir.join("Übungen")));
+        assert!(layout.has_git_repo);
+        assert_eq!(
+            layout.git_remote_url.as_deref(),
+            Some("git@example.com:b.git")
+        );
+
+        assert_eq!(
+            detect_layout(&dir.join("Folien")).unwrap(),
+            CourseLayout::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_adopt_and_convert() {
+        let (db, temp_dir, config, semester_id) = setup().await;
+        let dir = old_course(&temp_dir).canonicalize().unwrap();
+
+        let imported = import_course(
+            &config,
+            &db,
+            &dir,
+            semester_id,
+            CourseCode::new("algo".to_string()).unwrap(),
+            "Algorithmen".to_string(),
+            Ects::new(8).unwrap(),
+            ImportMode::Adopt,
+        )
+        .await
+        .unwrap();
+
+        // Adopted in place with a TOML next to the old files
+        assert!(imported.course.is_external);
+        assert_eq!(imported.course.directory_path, dir);
+        let toml = CourseToml::read_from_directory(&dir).unwrap();
+        assert!(toml.is_external);
+        assert_eq!(
+            toml.git_remote_url.as_deref(),
+            Some("git@example.com:b.git")
+        );
+        assert!(dir.join("Folien").exists());
+
+        let converted = convert_course(&config, &db, imported.course.id, true)
+            .await
+            .unwrap();
+        let target = config.university_base_path.join("b3/algo");
+        assert_eq!(converted.course.directory_path, target);
+        assert!(!converted.course.is_external);
+        assert!(!dir.exists());
+        assert!(target.join("slides/01.pdf").exists());
+        assert!(target.join("exercises/Blatt 1").exists());
+
+        let toml = CourseToml::read_from_directory(&target).unwrap();
+        assert!(!toml.is_external && toml.original_path.is_none());
+        assert_eq!(
+            toml.uid,
+            queries::course::get_by_id(&db, converted.course.id)
+                .await
+                .unwrap()
+                .uid
+        );
+    }
+
+    #[tokio::test]
+    async fn test_copy_into_semester() {
+        let (db, temp_dir, config, semester_id) = setup().await;
+        let dir = old_course(&temp_dir);
+
+        let imported = import_course(
+            &config,
+            &db,
+            &dir,
+            semester_id,
+            CourseCode::new("algo".to_string()).unwrap(),
+            "Algorithmen".to_string(),
+            Ects::new(8).unwrap(),
+            ImportMode::Copy,
+        )
+        .await
+        .unwrap();
+
+        let target = config.university_base_path.join("b3/algo");
+        assert!(!imported.course.is_external);
+        assert_eq!(imported.layout.slides_dir, Some(target.join("slides")));
+        assert!(target.join(".course.toml").exists());
+        assert!(dir.join("Folien/01.pdf").exists());
+
+        // A second import of the same code fails without touching the copy
+        let again = import_course(
+            &config,
+            &db,
+            &dir,
+            semester_id,
+            CourseCode::new("algo".to_string()).unwrap(),
+            "Algorithmen".to_string(),
+            Ects::new(8).unwrap(),
+            ImportMode::Copy,
+        )
+        .await;
+        assert!(matches!(again, Err(MmsError::FolderExists { .. })));
+        assert!(target.join("slides/01.pdf").exists());
+    }
+
+    #[tokio::test]
+    async fn test_failed_move_restores_folder() {
+        let (db, temp_dir, config, semester_id) = setup().await;
+        let dir = old_course(&temp_dir);
+
+        // Creating the course fails after the folder was moved and standardized
+        db.execute_unprepared("DROP TABLE courses").await.unwrap();
+        let result = import_course(
+            &config,
+            &db,
+            &dir,
+            semester_id,
+            CourseCode::new("algo".to_string()).unwrap(),
+            "Algorithmen".to_string(),
+            Ects::new(8).unwrap(),
+            ImportMode::Move,
+        )
+        .await;
+        assert!(result.is_err());
+        assert!(!config.university_base_path.join("b3/algo").exists());
+        assert!(dir.join("Folien/01.pdf").exists());
+        assert!(dir.join("Übungen/Blatt 1").exists());
+        assert!(!dir.join("slides").exists());
+    }
+
+    #[test]
+    fn test_transfer_removes_partial_copy() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = old_course(&temp_dir);
+        let target = temp_dir.path().join("Studies/b3/algo");
+
+        // Sockets cannot be opened for copying
+        let _socket = std::os::unix::net::UnixListener::bind(dir.join("Folien/socket")).unwrap();
+
+        assert!(transfer(&dir, &target, false).is_err());
+        assert!(!target.exists());
+        assert!(dir.join("Folien/01.pdf").exists());
+    }
+}
@@ -1,8 +1,12 @@
 pub mod builder;
+pub mod import;
 pub mod operations;
 pub mod types;
 
 pub use builder::CourseBuilder;
+pub use import::{
+    CourseLayout, ImportMode, ImportedCourse, convert_course, detect_layout, import_course,
+};
 pub use operations::{
     CourseInfo, create_course, delete_course, get_course_by_id, get_course_by_short_name,
     list_courses, update_course,
@@ -8,6 +8,7 @@ use sea_orm::{
     TransactionTrait,
 };
 use std::path::PathBuf;
+use uuid::Uuid;
 
 // ============================================================================
 // Validation Functions
@@ -173,8 +174,12 @@ pub async fn create_course(
         (_, _) => course_dir.to_string_lossy().to_string(),
     };
 
+    // Stable ID recognising the folder after a rename or move
+    let uid = Uuid::new_v4().to_string();
+
     // Prepare TOML configuration (we'll write it after DB insert in transaction)
-    let mut toml = CourseToml::new(short_name.as_str().to_string(), name.clone(), *ects);
+    let mut toml =
+        CourseToml::new(short_name.as_str().to_string(), name.clone(), *ects).with_uid(&uid);
     if let Some(l) = &lecturer {
         toml = toml.with_lecturer(l);
     }
@@ -235,6 +240,9 @@ pub async fn create_course(
         dropped_at: ActiveValue::Set(None),
         has_git_repo: ActiveValue::Set(has_git_repo),
         git_remote_url: ActiveValue::Set(git_remote_url.clone()),
+        uid: ActiveValue::Set(Some(uid)),
+        exercise_naming: ActiveValue::Set(None), // Detected from the folders until set
+        exercise_file_naming: ActiveValue::Set(None),
         created_at: ActiveValue::Set(Utc::now()),
         updated_at: ActiveValue::Set(Utc::now()),
     };
@@ -647,6 +655,7 @@ mod tests {
             university: ActiveValue::Set(Some("TUM".to_string())),
             is_current: ActiveValue::Set(true),
             is_archived: ActiveValue::Set(false),
+            uid: ActiveValue::Set(None),
             created_at: ActiveValue::Set(chrono::Utc::now()),
             updated_at: ActiveValue::Set(chrono::Utc::now()),
         };
@@ -13,6 +13,9 @@ pub struct Model {
     pub lecture_id: Option<i64>,
     #[sea_orm(column_type = "Timestamp", nullable)]
     pub activated_at: Option<DateTime<Utc>>,
+    pub is_manual: bool,
+    #[sea_orm(column_type = "Timestamp", nullable)]
+    pub manual_until: Option<DateTime<Utc>>,
     #[sea_orm(column_type = "Timestamp")]
     pub updated_at: DateTime<Utc>,
 }
@@ -46,6 +46,12 @@ pub struct Model {
     pub has_git_repo: bool,
     #[sea_orm(column_type = "Text", nullable)]
     pub git_remote_url: Option<String>,
+    #[sea_orm(column_type = "Text", nullable, unique)]
+    pub uid: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub exercise_naming: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub exercise_file_naming: Option<String>,
     #[sea_orm(column_type = "Timestamp")]
     pub created_at: DateTime<Utc>,
     #[sea_orm(column_type = "Timestamp")]
@@ -54,8 +60,14 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::activation_intervals::Entity")]
+    ActivationIntervals,
     #[sea_orm(has_many = "super::active_course::Entity")]
     ActiveCourse,
+    #[sea_orm(has_many = "super::attendance::Entity")]
+    Attendance,
+    #[sea_orm(has_many = "super::attendance_requirements::Entity")]
+    AttendanceRequirements,
     #[sea_orm(has_many = "super::course_degree_mappings::Entity")]
     CourseDegreeMappings,
     #[sea_orm(has_many = "super::course_events::Entity")]
@@ -92,12 +104,30 @@ pub enum Relation {
     Todos,
 }
 
+impl Related<super::activation_intervals::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ActivationIntervals.def()
+    }
+}
+
 impl Related<super::active_course::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::ActiveCourse.def()
     }
 }
 
+impl Related<super::attendance::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Attendance.def()
+    }
+}
+
+impl Related<super::attendance_requirements::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AttendanceRequirements.def()
+    }
+}
+
 impl Related<super::course_degree_mappings::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::CourseDegreeMappings.def()
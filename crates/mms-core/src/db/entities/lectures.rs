@@ -34,6 +34,7 @@ pub struct Model {
     pub git_commit_sha: Option<String>,
     #[sea_orm(column_type = "Text", nullable)]
     pub notes_file_path: Option<String>,
+    pub schedule_id: Option<i64>,
     #[sea_orm(column_type = "Timestamp")]
     pub created_at: DateTime<Utc>,
     #[sea_orm(column_type = "Timestamp")]
@@ -2,7 +2,10 @@
 
 pub mod prelude;
 
+pub mod activation_intervals;
 pub mod active_course;
+pub mod attendance;
+pub mod attendance_requirements;
 pub mod course_degree_mappings;
 pub mod course_events;
 pub mod course_possible_categories;
@@ -21,4 +24,5 @@ pub mod platform_accounts;
 pub mod platform_course_links;
 pub mod semesters;
 pub mod slides;
+pub mod switch_history;
 pub mod todos;
@@ -1,6 +1,9 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 2.0
 
+pub use super::activation_intervals::Entity as ActivationIntervals;
 pub use super::active_course::Entity as ActiveCourse;
+pub use super::attendance::Entity as Attendance;
+pub use super::attendance_requirements::Entity as AttendanceRequirements;
 pub use super::course_degree_mappings::Entity as CourseDegreeMappings;
 pub use super::course_events::Entity as CourseEvents;
 pub use super::course_possible_categories::Entity as CoursePossibleCategories;
@@ -19,4 +22,5 @@ pub use super::platform_accounts::Entity as PlatformAccounts;
 pub use super::platform_course_links::Entity as PlatformCourseLinks;
 pub use super::semesters::Entity as Semesters;
 pub use super::slides::Entity as Slides;
+pub use super::switch_history::Entity as SwitchHistory;
 pub use super::todos::Entity as Todos;
@@ -26,6 +26,8 @@ pub struct Model {
     pub university: Option<String>,
     pub is_current: bool,
     pub is_archived: bool,
+    #[sea_orm(column_type = "Text", nullable, unique)]
+    pub uid: Option<String>,
     #[sea_orm(column_type = "Timestamp")]
     pub created_at: DateTime<Utc>,
     #[sea_orm(column_type = "Timestamp")]
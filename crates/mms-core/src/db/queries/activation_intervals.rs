@@ -0,0 +1,137 @@
+use crate::db::entities::{activation_intervals, prelude::ActivationIntervals};
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, IntoActiveModel,
+    QueryFilter, QueryOrder, Set,
+};
+
+pub async fn insert(
+    db: &DatabaseConnection,
+    interval: activation_intervals::ActiveModel,
+) -> Result<i64> {
+    let res = interval.insert(db).await?;
+    Ok(res.id)
+}
+
+/// Get the currently open interval (the active course's)
+pub async fn get_open(db: &DatabaseConnection) -> Result<Option<activation_intervals::Model>> {
+    let interval = ActivationIntervals::find()
+        .filter(activation_intervals::Column::EndedAt.is_null())
+        .order_by_desc(activation_intervals::Column::StartedAt)
+        .one(db)
+        .await?;
+    Ok(interval)
+}
+
+/// List all intervals overlapping `[from, to)`, including open ones
+pub async fn list_overlapping(
+    db: &DatabaseConnection,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<activation_intervals::Model>> {
+    let intervals = ActivationIntervals::find()
+        .filter(activation_intervals::Column::StartedAt.lt(to))
+        .filter(
+            Condition::any()
+                .add(activation_intervals::Column::EndedAt.is_null())
+                .add(activation_intervals::Column::EndedAt.gt(from)),
+        )
+        .order_by_asc(activation_intervals::Column::StartedAt)
+        .all(db)
+        .await?;
+    Ok(intervals)
+}
+
+/// Open an interval for a course, closing any interval of another course
+///
+/// Does nothing if the course already has an open interval.
+pub async fn open(
+    db: &DatabaseConnection,
+    course_id: i64,
+    started_at: DateTime<Utc>,
+) -> Result<()> {
+    if let Some(open) = get_open(db).await? {
+        if open.course_id == course_id {
+            return Ok(());
+        }
+        close_open(db, started_at).await?;
+    }
+
+    let interval = activation_intervals::ActiveModel {
+        course_id: Set(course_id),
+        started_at: Set(started_at),
+        ended_at: Set(None),
+        last_seen_at: Set(Some(started_at)),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    };
+    insert(db, interval).await?;
+
+    Ok(())
+}
+
+/// Close all open intervals
+pub async fn close_open(db: &DatabaseConnection, ended_at: DateTime<Utc>) -> Result<()> {
+    let open = ActivationIntervals::find()
+        .filter(activation_intervals::Column::EndedAt.is_null())
+        .all(db)
+        .await?;
+
+    for interval in open {
+        let mut interval = interval.into_active_model();
+        interval.ended_at = Set(Some(ended_at));
+        interval.update(db).await?;
+    }
+
+    Ok(())
+}
+
+/// Record that the daemon saw the open interval at `now`
+///
+/// If the last heartbeat is older than `max_gap` (the daemon was down or the
+/// machine suspended), the interval is closed at that heartbeat and a new one
+/// is opened at `now` for the same course.
+pub async fn heartbeat(
+    db: &DatabaseConnection,
+    now: DateTime<Utc>,
+    max_gap: chrono::Duration,
+) -> Result<()> {
+    let Some(open) = get_open(db).await? else {
+        return Ok(());
+    };
+
+    let last_seen = open.last_seen_at.unwrap_or(open.started_at);
+    let course_id = open.course_id;
+    let mut interval = open.into_active_model();
+    if now - last_seen > max_gap {
+        interval.ended_at = Set(Some(last_seen));
+        interval.update(db).await?;
+
+        let resumed = activation_intervals::ActiveModel {
+            course_id: Set(course_id),
+            started_at: Set(now),
+            ended_at: Set(None),
+            last_seen_at: Set(Some(now)),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        };
+        insert(db, resumed).await?;
+    } else {
+        interval.last_seen_at = Set(Some(now));
+        interval.update(db).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn delete(db: &DatabaseConnection, id: i64) -> Result<()> {
+    let res = ActivationIntervals::delete_by_id(id).exec(db).await?;
+    if res.rows_affected == 0 {
+        return Err(crate::error::MmsError::NotFound(format!(
+            "ActivationInterval with ID {} not found",
+            id
+        )));
+    }
+    Ok(())
+}
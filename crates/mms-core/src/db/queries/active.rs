@@ -1,6 +1,7 @@
 use crate::db::entities::{active_course, prelude::ActiveCourse as ActiveCourseEntity};
+use crate::db::queries::activation_intervals;
 use crate::error::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use sea_orm::IntoActiveModel;
 use sea_orm::{ActiveModelTrait, ActiveValue, DatabaseConnection, EntityTrait, Set};
 
@@ -47,6 +48,30 @@ pub async fn set_active_course(
     db: &DatabaseConnection,
     course_id: i64,
     semester_id: i64,
+) -> Result<()> {
+    activate_course(db, course_id, semester_id, false, None).await
+}
+
+/// Activate a manually selected course
+///
+/// Sets the course and the manual flag in one write, so the daemon never
+/// sees the course without the flag. It keeps the course until `until`, the
+/// next scheduled slot or [`clear_manual`], whichever comes first.
+pub async fn set_manual_course(
+    db: &DatabaseConnection,
+    course_id: i64,
+    semester_id: i64,
+    until: Option<DateTime<Utc>>,
+) -> Result<()> {
+    activate_course(db, course_id, semester_id, true, until).await
+}
+
+async fn activate_course(
+    db: &DatabaseConnection,
+    course_id: i64,
+    semester_id: i64,
+    is_manual: bool,
+    manual_until: Option<DateTime<Utc>>,
 ) -> Result<()> {
     let now = Utc::now();
 
@@ -63,6 +88,8 @@ pub async fn set_active_course(
     active.course_id = Set(Some(course_id));
     active.lecture_id = Set(None);
     active.activated_at = Set(Some(now));
+    active.is_manual = Set(is_manual);
+    active.manual_until = Set(manual_until);
     active.updated_at = Set(now);
 
     let exists = ActiveCourseEntity::find_by_id(1).one(db).await?.is_some();
@@ -72,6 +99,9 @@ pub async fn set_active_course(
         active.insert(db).await?;
     }
 
+    // Record the activation for the study-time log
+    activation_intervals::open(db, course_id, now).await?;
+
     Ok(())
 }
 
@@ -89,6 +119,8 @@ pub async fn clear_active_course(db: &DatabaseConnection) -> Result<()> {
 
     active.course_id = Set(None);
     active.lecture_id = Set(None);
+    active.is_manual = Set(false);
+    active.manual_until = Set(None);
     active.updated_at = Set(now);
 
     let exists = ActiveCourseEntity::find_by_id(1).one(db).await?.is_some();
@@ -98,6 +130,8 @@ pub async fn clear_active_course(db: &DatabaseConnection) -> Result<()> {
         active.insert(db).await?;
     }
 
+    activation_intervals::close_open(db, now).await?;
+
     Ok(())
 }
 
@@ -117,6 +151,8 @@ pub async fn clear_all(db: &DatabaseConnection) -> Result<()> {
     active.course_id = Set(None);
     active.lecture_id = Set(None);
     active.activated_at = Set(None);
+    active.is_manual = Set(false);
+    active.manual_until = Set(None);
     active.updated_at = Set(now);
 
     let exists = ActiveCourseEntity::find_by_id(1).one(db).await?.is_some();
@@ -126,6 +162,19 @@ pub async fn clear_all(db: &DatabaseConnection) -> Result<()> {
         active.insert(db).await?;
     }
 
+    activation_intervals::close_open(db, now).await?;
+
+    Ok(())
+}
+
+/// Hand the active course back to the schedule
+pub async fn clear_manual(db: &DatabaseConnection) -> Result<()> {
+    let mut active = get(db).await?.into_active_model();
+    active.is_manual = Set(false);
+    active.manual_until = Set(None);
+    active.updated_at = Set(Utc::now());
+    active.update(db).await?;
+
     Ok(())
 }
 
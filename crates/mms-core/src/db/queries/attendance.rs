@@ -0,0 +1,110 @@
+use crate::db::entities::{
+    attendance, attendance_requirements,
+    prelude::{Attendance, AttendanceRequirements},
+};
+use crate::error::Result;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+
+// Attendance Queries
+
+pub async fn insert(db: &DatabaseConnection, record: attendance::ActiveModel) -> Result<i64> {
+    let res = record.insert(db).await?;
+    Ok(res.id)
+}
+
+pub async fn get_by_id(db: &DatabaseConnection, id: i64) -> Result<attendance::Model> {
+    let record = Attendance::find_by_id(id).one(db).await?.ok_or_else(|| {
+        crate::error::MmsError::NotFound(format!("Attendance with ID {} not found", id))
+    })?;
+    Ok(record)
+}
+
+pub async fn get_by_occurrence(
+    db: &DatabaseConnection,
+    course_id: i64,
+    date: &str,
+    start_time: &str,
+) -> Result<Option<attendance::Model>> {
+    let record = Attendance::find()
+        .filter(attendance::Column::CourseId.eq(course_id))
+        .filter(attendance::Column::Date.eq(date))
+        .filter(attendance::Column::StartTime.eq(start_time))
+        .one(db)
+        .await?;
+    Ok(record)
+}
+
+pub async fn list_by_course(
+    db: &DatabaseConnection,
+    course_id: i64,
+) -> Result<Vec<attendance::Model>> {
+    let records = Attendance::find()
+        .filter(attendance::Column::CourseId.eq(course_id))
+        .order_by_asc(attendance::Column::Date)
+        .order_by_asc(attendance::Column::StartTime)
+        .all(db)
+        .await?;
+    Ok(records)
+}
+
+pub async fn update(
+    db: &DatabaseConnection,
+    record: attendance::ActiveModel,
+) -> Result<attendance::Model> {
+    let record = record.update(db).await?;
+    Ok(record)
+}
+
+pub async fn delete(db: &DatabaseConnection, id: i64) -> Result<()> {
+    let res = Attendance::delete_by_id(id).exec(db).await?;
+    if res.rows_affected == 0 {
+        return Err(crate::error::MmsError::NotFound(format!(
+            "Attendance with ID {} not found",
+            id
+        )));
+    }
+    Ok(())
+}
+
+// AttendanceRequirement Queries
+
+pub async fn insert_requirement(
+    db: &DatabaseConnection,
+    requirement: attendance_requirements::ActiveModel,
+) -> Result<i64> {
+    let res = requirement.insert(db).await?;
+    Ok(res.id)
+}
+
+pub async fn list_requirements_by_course(
+    db: &DatabaseConnection,
+    course_id: i64,
+) -> Result<Vec<attendance_requirements::Model>> {
+    let requirements = AttendanceRequirements::find()
+        .filter(attendance_requirements::Column::CourseId.eq(course_id))
+        .order_by_asc(attendance_requirements::Column::ScheduleType)
+        .all(db)
+        .await?;
+    Ok(requirements)
+}
+
+pub async fn update_requirement(
+    db: &DatabaseConnection,
+    requirement: attendance_requirements::ActiveModel,
+) -> Result<attendance_requirements::Model> {
+    let requirement = requirement.update(db).await?;
+    Ok(requirement)
+}
+
+pub async fn delete_requirement(db: &DatabaseConnection, id: i64) -> Result<()> {
+    let res = AttendanceRequirements::delete_by_id(id).exec(db).await?;
+    if res.rows_affected == 0 {
+        return Err(crate::error::MmsError::NotFound(format!(
+            "AttendanceRequirement with ID {} not found",
+            id
+        )));
+    }
+    Ok(())
+}
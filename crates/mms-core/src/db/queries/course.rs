@@ -37,6 +37,20 @@ pub async fn list_by_semester(
     Ok(courses)
 }
 
+/// Courses of a semester that are not archived (schedules, daemon, scans)
+pub async fn list_unarchived_by_semester(
+    db: &DatabaseConnection,
+    semester_id: i64,
+) -> Result<Vec<courses::Model>> {
+    let courses = Courses::find()
+        .filter(courses::Column::SemesterId.eq(semester_id))
+        .filter(courses::Column::IsArchived.eq(false))
+        .order_by_asc(courses::Column::ShortName)
+        .all(db)
+        .await?;
+    Ok(courses)
+}
+
 pub async fn update(
     db: &DatabaseConnection,
     course: courses::ActiveModel,
@@ -1,4 +1,6 @@
+pub mod activation_intervals;
 pub mod active;
+pub mod attendance;
 pub mod course;
 pub mod course_categories;
 pub mod degree_areas;
@@ -13,6 +15,7 @@ pub mod platform;
 pub mod schedule;
 pub mod semester;
 pub mod slides;
+pub mod switch_history;
 pub mod todos;
 
 // Re-export common types
@@ -0,0 +1,70 @@
+use crate::db::entities::{prelude::SwitchHistory, switch_history};
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+
+pub async fn insert(db: &DatabaseConnection, entry: switch_history::ActiveModel) -> Result<i64> {
+    let res = entry.insert(db).await?;
+    Ok(res.id)
+}
+
+/// Record a switch from one course to another (either may be `None`)
+pub async fn record(
+    db: &DatabaseConnection,
+    from_course_id: Option<i64>,
+    to_course_id: Option<i64>,
+    reason: &str,
+    switched_at: DateTime<Utc>,
+) -> Result<i64> {
+    insert(
+        db,
+        switch_history::ActiveModel {
+            from_course_id: Set(from_course_id),
+            to_course_id: Set(to_course_id),
+            reason: Set(reason.to_string()),
+            switched_at: Set(switched_at),
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+/// List switches within `[from, to)`, optionally only those involving a course, newest first
+pub async fn list(
+    db: &DatabaseConnection,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    course_id: Option<i64>,
+    limit: Option<u64>,
+) -> Result<Vec<switch_history::Model>> {
+    let mut query = SwitchHistory::find();
+    if let Some(from) = from {
+        query = query.filter(switch_history::Column::SwitchedAt.gte(from));
+    }
+    if let Some(to) = to {
+        query = query.filter(switch_history::Column::SwitchedAt.lt(to));
+    }
+    if let Some(course_id) = course_id {
+        query = query.filter(
+            Condition::any()
+                .add(switch_history::Column::FromCourseId.eq(course_id))
+                .add(switch_history::Column::ToCourseId.eq(course_id)),
+        );
+    }
+
+    let entries = query
+        .order_by_desc(switch_history::Column::SwitchedAt)
+        .order_by_desc(switch_history::Column::Id)
+        .limit(limit)
+        .all(db)
+        .await?;
+    Ok(entries)
+}
+
+pub async fn delete(db: &DatabaseConnection, id: i64) -> Result<()> {
+    SwitchHistory::delete_by_id(id).exec(db).await?;
+    Ok(())
+}
@@ -22,6 +22,16 @@ pub async fn list_by_course(db: &DatabaseConnection, course_id: i64) -> Result<V
     Ok(todos)
 }
 
+/// Todos that are not completed and have a due date
+pub async fn list_open_with_due_date(db: &DatabaseConnection) -> Result<Vec<todos::Model>> {
+    let todos = Todos::find()
+        .filter(todos::Column::Completed.eq(false))
+        .filter(todos::Column::DueDate.is_not_null())
+        .all(db)
+        .await?;
+    Ok(todos)
+}
+
 pub async fn update(db: &DatabaseConnection, todo: todos::ActiveModel) -> Result<todos::Model> {
     let todo = todo.update(db).await?;
     Ok(todo)
@@ -95,12 +95,34 @@ pub enum MmsError {
     #[error("Invalid semester type: {0}")]
     InvalidSemesterType(String),
 
+    #[error(
+        "Invalid semester folder pattern: {0} (needs {{type}} and {{number}} or {{number:0N}})"
+    )]
+    InvalidSemesterFolder(String),
+
+    #[error(
+        "Invalid exercise naming pattern: {0} (needs one {{n}} or {{n:0Nd}}, e.g. \"ex{{n:02d}}\")"
+    )]
+    InvalidExercisePattern(String),
+
+    #[error("Invalid grading scheme {name}: {reason}")]
+    InvalidGradingScheme { name: String, reason: String },
+
     #[error("Invalid schedule type: {0}")]
     InvalidScheduleType(String),
 
     #[error("Invalid exam type: {0}")]
     InvalidExamType(String),
 
+    #[error("Invalid attendance status: {0} (expected attended, missed or excused)")]
+    InvalidAttendanceStatus(String),
+
+    #[error("Invalid switch reason: {0} (expected schedule, manual, override or archive)")]
+    InvalidSwitchReason(String),
+
+    #[error("Invalid attendance requirement: {0}% (must be 0-100)")]
+    InvalidAttendanceRequirement(f64),
+
     #[error("Failed to create semester directory at {path}: {source}")]
     SemesterDirectoryCreation {
         path: std::path::PathBuf,
@@ -113,6 +135,21 @@ pub enum MmsError {
         source: std::io::Error,
     },
 
+    #[error("Course {0} is already archived")]
+    CourseAlreadyArchived(String),
+
+    #[error("Course {0} is not archived")]
+    CourseNotArchived(String),
+
+    #[error("Course {0} is not an external course")]
+    CourseNotExternal(String),
+
+    #[error("Cannot rename {from} to {to}: target already exists")]
+    FolderExists {
+        from: std::path::PathBuf,
+        to: std::path::PathBuf,
+    },
+
     #[error("Invalid semester number: {number} (must be positive)")]
     InvalidSemesterNumber { number: i64 },
 
@@ -153,6 +190,15 @@ pub enum MmsError {
     #[error("Invalid area ECTS: {0} (must be positive)")]
     InvalidAreaEcts(i32),
 
+    #[error("Failed to send notification via {backend}: {reason}")]
+    NotificationFailed { backend: String, reason: String },
+
+    #[error("Invalid time: {0} (expected e.g. 30m, 2h, 1d, YYYY-MM-DD or 'YYYY-MM-DD HH:MM')")]
+    InvalidTimeSpec(String),
+
+    #[error("Could not reach the daemon: {0}")]
+    DaemonUnreachable(String),
+
     #[error("{0}")]
     Other(String),
 }
@@ -0,0 +1,8 @@
+pub mod operations;
+pub mod pattern;
+
+pub use operations::{
+    ExerciseNaming, ExerciseScan, course_naming, detect_naming, exercises_dir,
+    scan_course_exercises, scan_semester_exercises, set_exercise_naming,
+};
+pub use pattern::{DEFAULT_EXERCISE_NAMING, ExercisePattern};
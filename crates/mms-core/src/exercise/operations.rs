@@ -0,0 +1,368 @@
+use crate::db::entities::{courses, exercises};
+use crate::db::queries;
+use crate::error::Result;
+use crate::exercise::pattern::{DEFAULT_EXERCISE_NAMING, ExercisePattern};
+use crate::sync::import::subdirectories;
+use crate::toml::CourseToml;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ActiveValue, DatabaseConnection, IntoActiveModel};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// How the exercises of a course are named
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExerciseNaming {
+    /// Folders in `exercises/`
+    pub folders: ExercisePattern,
+    /// Assignment files inside an exercise folder
+    pub files: Option<ExercisePattern>,
+}
+
+/// Changes found by an exercise scan (folder names)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExerciseScan {
+    pub added: Vec<String>,
+    /// Folders whose paths or assignment file changed
+    pub updated: Vec<String>,
+}
+
+impl ExerciseScan {
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty()
+    }
+}
+
+// ============================================================================
+// Naming
+// ============================================================================
+
+/// Folder holding a course's exercises
+pub fn exercises_dir(course_dir: &Path) -> PathBuf {
+    course_dir.join("exercises")
+}
+
+/// Detect the naming of existing exercise folders and their assignment files
+///
+/// Files are only considered if their number matches their folder's number,
+/// e.g. `ex03/sheet3.pdf`. Returns `None` without numbered folders.
+pub fn detect_naming(course_dir: &Path) -> Result<Option<ExerciseNaming>> {
+    let dirs = subdirectories(&exercises_dir(course_dir))?;
+    let Some(folders) = ExercisePattern::detect(dirs.iter().filter_map(|d| file_name(d))) else {
+        return Ok(None);
+    };
+
+    let mut numbered_files = Vec::new();
+    for dir in &dirs {
+        let Some(number) = file_name(dir).and_then(|name| folders.number(name)) else {
+            continue;
+        };
+        numbered_files.extend(
+            list_files(dir)?
+                .into_iter()
+                .filter(|name| first_number(name) == Some(number)),
+        );
+    }
+    let files = ExercisePattern::detect(numbered_files.iter().map(String::as_str));
+
+    Ok(Some(ExerciseNaming { folders, files }))
+}
+
+/// Naming of a course: configured patterns first, then detected ones
+///
+/// Folders fall back to `assignment{n:02d}`.
+pub fn course_naming(course: &courses::Model) -> Result<ExerciseNaming> {
+    let detected = detect_naming(Path::new(&course.directory_path))?;
+
+    let folders = match (&course.exercise_naming, &detected) {
+        (Some(pattern), _) => ExercisePattern::new(pattern)?,
+        (None, Some(detected)) => detected.folders.clone(),
+        (None, None) => ExercisePattern::new(DEFAULT_EXERCISE_NAMING)?,
+    };
+    let files = match &course.exercise_file_naming {
+        Some(pattern) => Some(ExercisePattern::new(pattern)?),
+        None => detected.and_then(|d| d.files),
+    };
+
+    Ok(ExerciseNaming { folders, files })
+}
+
+/// Set the naming patterns of a course, `None` to detect them again
+///
+/// Both patterns are stored in the row and the course's `.course.toml`.
+pub async fn set_exercise_naming(
+    db: &DatabaseConnection,
+    course_id: i64,
+    folders: Option<&str>,
+    files: Option<&str>,
+) -> Result<courses::Model> {
+    // Validate before changing anything
+    for pattern in folders.iter().chain(files.iter()) {
+        ExercisePattern::new(pattern)?;
+    }
+
+    let course = queries::course::get_by_id(db, course_id).await?;
+
+    // The TOML first, so a failed write leaves both unchanged.
+    // A missing TOML is recreated by the next sync.
+    let toml = course
+        .toml_path
+        .as_deref()
+        .map(PathBuf::from)
+        .and_then(|path| Some((CourseToml::read(&path).ok()?, path)));
+    if let Some((toml, path)) = &toml {
+        let mut updated = toml.clone();
+        updated.exercise_naming = folders.map(str::to_string);
+        updated.exercise_file_naming = files.map(str::to_string);
+        updated.write(path)?;
+    }
+
+    let mut active = course.into_active_model();
+    active.exercise_naming = ActiveValue::Set(folders.map(str::to_string));
+    active.exercise_file_naming = ActiveValue::Set(files.map(str::to_string));
+    active.updated_at = ActiveValue::Set(Utc::now());
+    match active.update(db).await {
+        Ok(updated) => Ok(updated),
+        Err(e) => {
+            // Keep the TOML in line with the unchanged row
+            if let Some((toml, path)) = &toml {
+                let _ = toml.write(path);
+            }
+            Err(e.into())
+        }
+    }
+}
+
+// ============================================================================
+// Scanning
+// ============================================================================
+
+/// Bring the `exercises` rows of a course in line with its `exercises/` folder
+///
+/// This function performs the following steps:
+/// 1. Resolves the course's naming (see `course_naming`)
+/// 2. Lists the folders in `<course>/exercises` matching the folder pattern
+/// 3. Finds each folder's assignment file with the file pattern
+/// 4. Inserts rows for new exercise numbers and updates the paths of existing rows
+///
+/// Rows without a folder are kept, as they may hold grades and feedback.
+/// Nothing is written with `dry_run`.
+pub async fn scan_course_exercises(
+    db: &DatabaseConnection,
+    course: &courses::Model,
+    dry_run: bool,
+) -> Result<ExerciseScan> {
+    let dir = exercises_dir(Path::new(&course.directory_path));
+    let mut scan = ExerciseScan::default();
+    if !dir.is_dir() {
+        return Ok(scan);
+    }
+
+    let naming = course_naming(course)?;
+    let mut rows: HashMap<i64, exercises::Model> =
+        queries::exercises::list_by_course(db, course.id)
+            .await?
+            .into_iter()
+            .map(|row| (row.exercise_number, row))
+            .collect();
+    let mut seen = Vec::new();
+    let now = Utc::now();
+
+    for folder in subdirectories(&dir)? {
+        let Some(name) = file_name(&folder).map(str::to_string) else {
+            continue;
+        };
+        // The first folder of a number wins, e.g. `ex03` over `ex3`
+        let Some(number) = naming.folders.number(&name) else {
+            continue;
+        };
+        if seen.contains(&number) {
+            continue;
+        }
+        seen.push(number);
+
+        let solution = Some(folder.to_string_lossy().to_string());
+        let assignment = match &naming.files {
+            Some(files) => list_files(&folder)?
+                .into_iter()
+                .find(|file| files.number(file) == Some(number))
+                .map(|file| folder.join(file).to_string_lossy().to_string()),
+            None => None,
+        };
+
+        match rows.remove(&number) {
+            Some(row) => {
+                // A missing file keeps a path set by hand
+                let assignment = assignment.or_else(|| row.assignment_file_path.clone());
+                if row.solution_directory_path == solution && row.assignment_file_path == assignment
+                {
+                    continue;
+                }
+                scan.updated.push(name);
+                if !dry_run {
+                    let mut active = row.into_active_model();
+                    active.solution_directory_path = ActiveValue::Set(solution);
+                    active.assignment_file_path = ActiveValue::Set(assignment);
+                    active.updated_at = ActiveValue::Set(now);
+                    queries::exercises::update(db, active).await?;
+                }
+            }
+            None => {
+                scan.added.push(name);
+                if !dry_run {
+                    let exercise = exercises::ActiveModel {
+                        course_id: ActiveValue::Set(course.id),
+                        exercise_number: ActiveValue::Set(number),
+                        solution_directory_path: ActiveValue::Set(solution),
+                        assignment_file_path: ActiveValue::Set(assignment),
+                        is_submitted: ActiveValue::Set(false),
+                        is_graded: ActiveValue::Set(false),
+                        created_at: ActiveValue::Set(now),
+                        updated_at: ActiveValue::Set(now),
+                        ..Default::default()
+                    };
+                    queries::exercises::insert(db, exercise).await?;
+                }
+            }
+        }
+    }
+
+    Ok(scan)
+}
+
+/// Scan the exercises of all courses of the current semester
+///
+/// Returns the courses whose exercises changed.
+pub async fn scan_semester_exercises(
+    db: &DatabaseConnection,
+    dry_run: bool,
+) -> Result<Vec<(courses::Model, ExerciseScan)>> {
+    let Some(semester) = queries::semester::get_current(db).await? else {
+        return Ok(Vec::new());
+    };
+
+    let mut changed = Vec::new();
+    for course in queries::course::list_unarchived_by_semester(db, semester.id).await? {
+        let scan = scan_course_exercises(db, &course, dry_run).await?;
+        if !scan.is_empty() {
+            changed.push((course, scan));
+        }
+    }
+
+    Ok(changed)
+}
+
+fn file_name(path: &Path) -> Option<&str> {
+    path.file_name().and_then(|name| name.to_str())
+}
+
+/// First run of digits in a name
+fn first_number(name: &str) -> Option<i64> {
+    let start = name.find(|c: char| c.is_ascii_digit())?;
+    let digits: String = name[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+/// Names of the visible files directly inside `dir`, sorted
+fn list_files(dir: &Path) -> Result<Vec<String>> {
+    let mut files: Vec<String> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .filter_map(|path| file_name(&path).map(str::to_string))
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::semester_with_course;
+    use tempfile::TempDir;
+
+    async fn setup() -> (DatabaseConnection, TempDir, courses::Model) {
+        let temp_dir = TempDir::new().unwrap();
+        let (db, course) = semester_with_course(temp_dir.path(), "algo", "Algorithms").await;
+        let course = queries::course::get_by_id(&db, course.id).await.unwrap();
+
+        (db, temp_dir, course)
+    }
+
+    fn exercise_folder(course: &courses::Model, name: &str, files: &[&str]) {
+        let dir = exercises_dir(Path::new(&course.directory_path)).join(name);
+        fs::create_dir_all(&dir).unwrap();
+        for file in files {
+            fs::write(dir.join(file), "").unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scan_detects_naming() {
+        let (db, _temp_dir, course) = setup().await;
+        exercise_folder(&course, "ex01", &["sheet1.pdf", "solution.typ"]);
+        exercise_folder(&course, "ex02", &["sheet2.pdf"]);
+        exercise_folder(&course, "notes", &[]);
+
+        let naming = course_naming(&course).unwrap();
+        assert_eq!(naming.folders.to_string(), "ex{n:02d}");
+        assert_eq!(naming.files.unwrap().to_string(), "sheet{n}.pdf");
+
+        let scan = scan_course_exercises(&db, &course, false).await.unwrap();
+        assert_eq!(scan.added, vec!["ex01", "ex02"]);
+
+        let rows = queries::exercises::list_by_course(&db, course.id)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].exercise_number, 2);
+        assert!(
+            rows[1]
+                .assignment_file_path
+                .as_ref()
+                .unwrap()
+                .ends_with("ex02/sheet2.pdf")
+        );
+
+        // Nothing changed
+        let scan = scan_course_exercises(&db, &course, false).await.unwrap();
+        assert!(scan.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_configured_naming() {
+        let (db, _temp_dir, course) = setup().await;
+        exercise_folder(&course, "Blatt 1", &["blatt1.pdf"]);
+        exercise_folder(&course, "ex02", &[]);
+        exercise_folder(&course, "ex03", &[]);
+
+        assert!(
+            set_exercise_naming(&db, course.id, Some("Blatt"), None)
+                .await
+                .is_err()
+        );
+        let course = set_exercise_naming(&db, course.id, Some("Blatt {n}"), Some("blatt{n}.pdf"))
+            .await
+            .unwrap();
+
+        let toml = CourseToml::read(Path::new(course.toml_path.as_ref().unwrap())).unwrap();
+        assert_eq!(toml.exercise_naming.as_deref(), Some("Blatt {n}"));
+
+        let scan = scan_course_exercises(&db, &course, true).await.unwrap();
+        assert_eq!(scan.added, vec!["Blatt 1"]);
+        assert!(
+            queries::exercises::list_by_course(&db, course.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use crate::error::{MmsError, Result};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Exercise folder names used when a course has none configured or detected
+pub const DEFAULT_EXERCISE_NAMING: &str = "assignment{n:02d}";
+
+/// A folder or file name with one placeholder for the exercise number
+///
+/// `{n}` is replaced by the plain number and `{n:0Nd}` by the number padded
+/// with zeros to N digits, e.g. `ex{n:02d}` gives `ex01`, `ex02`, ...
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExercisePattern {
+    prefix: String,
+    /// Minimum number of digits, 0 for none
+    width: usize,
+    suffix: String,
+}
+
+impl ExercisePattern {
+    /// Parse a pattern
+    ///
+    /// # Errors
+    /// Returns `InvalidExercisePattern` unless the pattern contains exactly one
+    /// placeholder and no path separators.
+    pub fn new(pattern: &str) -> Result<Self> {
+        let invalid = || MmsError::InvalidExercisePattern(pattern.to_string());
+
+        let (prefix, rest) = pattern.split_once('{').ok_or_else(invalid)?;
+        let (placeholder, suffix) = rest.split_once('}').ok_or_else(invalid)?;
+        let width = match placeholder {
+            "n" => 0,
+            _ => {
+                let format = placeholder.strip_prefix("n:0").ok_or_else(invalid)?;
+                let digits = format.strip_suffix('d').unwrap_or(format);
+                digits.parse::<usize>().map_err(|_| invalid())?
+            }
+        };
+
+        let literal_ok = |s: &str| !s.contains(['{', '}', '/', '\\']);
+        if !literal_ok(prefix) || !literal_ok(suffix) || (prefix.is_empty() && suffix.is_empty()) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            prefix: prefix.to_string(),
+            width,
+            suffix: suffix.to_string(),
+        })
+    }
+
+    /// Name of an exercise, e.g. `ex03`
+    pub fn format(&self, number: i64) -> String {
+        format!(
+            "{}{:0width$}{}",
+            self.prefix,
+            number,
+            self.suffix,
+            width = self.width
+        )
+    }
+
+    /// Exercise number of a name following the pattern
+    ///
+    /// Padding is not enforced, so `ex{n:02d}` also matches `ex3` and `ex100`.
+    pub fn number(&self, name: &str) -> Option<i64> {
+        let digits = name
+            .strip_prefix(self.prefix.as_str())?
+            .strip_suffix(self.suffix.as_str())?;
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse().ok()
+    }
+
+    /// Most common naming among `names`
+    ///
+    /// Names are split at their first run of digits; the prefix and suffix
+    /// shared by the most names win. The number is padded if any of them is.
+    pub fn detect<'a>(names: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        let mut groups: HashMap<(&str, &str), Vec<&str>> = HashMap::new();
+        for name in names {
+            let Some(start) = name.find(|c: char| c.is_ascii_digit()) else {
+                continue;
+            };
+            let end = name[start..]
+                .find(|c: char| !c.is_ascii_digit())
+                .map_or(name.len(), |i| start + i);
+            groups
+                .entry((&name[..start], &name[end..]))
+                .or_default()
+                .push(&name[start..end]);
+        }
+
+        let ((prefix, suffix), numbers) = groups
+            .into_iter()
+            .filter(|((prefix, suffix), _)| !prefix.is_empty() || !suffix.is_empty())
+            // Ties go to the shorter prefix, e.g. `ex` over `ex_old`
+            .max_by(|(a_key, a), (b_key, b)| a.len().cmp(&b.len()).then(b_key.cmp(a_key)))?;
+
+        let padded = numbers.iter().any(|n| n.len() > 1 && n.starts_with('0'));
+        let width = if padded {
+            numbers.iter().map(|n| n.len()).max().unwrap_or(0)
+        } else {
+            0
+        };
+
+        Some(Self {
+            prefix: prefix.to_string(),
+            width,
+            suffix: suffix.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for ExercisePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.width {
+            0 => write!(f, "{}{{n}}{}", self.prefix, self.suffix),
+            width => write!(f, "{}{{n:0{}d}}{}", self.prefix, width, self.suffix),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_format_and_number() {
+        let pattern = ExercisePattern::new("ex{n:02d}").unwrap();
+        assert_eq!(pattern.format(3), "ex03");
+        assert_eq!(pattern.format(12), "ex12");
+        assert_eq!(pattern.number("ex03"), Some(3));
+        assert_eq!(pattern.number("ex3"), Some(3));
+        assert_eq!(pattern.number("ex"), None);
+        assert_eq!(pattern.number("ex03b"), None);
+        assert_eq!(pattern.to_string(), "ex{n:02d}");
+
+        let pattern = ExercisePattern::new("Blatt {n}.pdf").unwrap();
+        assert_eq!(pattern.format(7), "Blatt 7.pdf");
+        assert_eq!(pattern.number("Blatt 7.pdf"), Some(7));
+        assert_eq!(pattern.to_string(), "Blatt {n}.pdf");
+
+        assert!(ExercisePattern::new("sheet_{n:02}").is_ok());
+        assert!(ExercisePattern::new("sheet").is_err());
+        assert!(ExercisePattern::new("{n}").is_err());
+        assert!(ExercisePattern::new("ex{m}").is_err());
+        assert!(ExercisePattern::new("ex{n}/{n}").is_err());
+    }
+
+    #[test]
+    fn test_detect_pattern() {
+        let detected =
+            ExercisePattern::detect(["homework1", "homework2", "homework10", "notes"]).unwrap();
+        assert_eq!(detected.to_string(), "homework{n}");
+
+        let detected = ExercisePattern::detect(["ex01", "ex02", "ex03_old", "ex04"]).unwrap();
+        assert_eq!(detected.to_string(), "ex{n:02d}");
+
+        let detected = ExercisePattern::detect(["sheet01.pdf"]).unwrap();
+        assert_eq!(detected.to_string(), "sheet{n:02d}.pdf");
+
+        assert_eq!(ExercisePattern::detect(["notes", "01"]), None);
+    }
+}
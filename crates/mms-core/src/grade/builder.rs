@@ -1,5 +1,5 @@
 use super::conversion::calculate_weighted_average;
-use super::operations::{GradeInfo, record_grade};
+use super::operations::{GradeInfo, parse_grade_input, record_grade};
 use super::types::GradingScheme;
 use crate::error::Result;
 use sea_orm::DatabaseConnection;
@@ -69,6 +69,15 @@ impl GradeBuilder {
         }
     }
 
+    /// Create a grade builder from a grade as entered, e.g. `1,7` or `30L`
+    ///
+    /// # Errors
+    /// Returns `Parse` if the input is not a valid grade of `scheme`.
+    pub fn parse(course_id: i64, input: &str, scheme: GradingScheme) -> Result<Self> {
+        let grade = parse_grade_input(&scheme, input)?;
+        Ok(Self::new(course_id, grade).with_scheme(scheme))
+    }
+
     /// Set the grading scheme
     pub fn with_scheme(mut self, scheme: GradingScheme) -> Self {
         self.grading_scheme = scheme;
@@ -307,4 +316,18 @@ mod tests {
         assert_eq!(builder.original_grade, Some(3.7));
         assert_eq!(builder.original_scheme, Some(GradingScheme::US));
     }
+
+    #[test]
+    fn test_builder_parse() {
+        let italian: GradingScheme = "italian".parse().unwrap();
+        let builder = GradeBuilder::parse(1, "30L", italian.clone()).unwrap();
+        assert_eq!(builder.grade, 31.0);
+        assert_eq!(builder.grading_scheme, italian);
+
+        let builder = GradeBuilder::parse(1, "1,7", GradingScheme::German).unwrap();
+        assert_eq!(builder.grade, 1.7);
+
+        assert!(GradeBuilder::parse(1, "31", italian).is_err());
+        assert!(GradeBuilder::parse(1, "0.7", GradingScheme::German).is_err());
+    }
 }
@@ -1,8 +1,9 @@
 use super::conversion::calculate_weighted_average;
+use super::scheme::SchemeRegistry;
 use super::types::GradingScheme;
 use crate::error::Result;
 use sea_orm::{DatabaseConnection, FromQueryResult};
-use std::str::FromStr;
+use tracing::warn;
 
 /// GPA calculation result
 #[derive(Debug, Clone)]
@@ -51,7 +52,14 @@ struct GradeWithEcts {
 }
 
 /// Helper to calculate GPA from query results using functional patterns
-fn calculate_gpa_from_results(results: Vec<GradeWithEcts>, scheme: GradingScheme) -> GPAInfo {
+///
+/// Scheme names of the grades are resolved with `schemes`. Grades in an
+/// unknown scheme or that cannot be converted are left out with a warning.
+fn calculate_gpa_from_results(
+    results: Vec<GradeWithEcts>,
+    scheme: GradingScheme,
+    schemes: &SchemeRegistry,
+) -> GPAInfo {
     if results.is_empty() {
         return GPAInfo {
             gpa: 0.0,
@@ -63,16 +71,31 @@ fn calculate_gpa_from_results(results: Vec<GradeWithEcts>, scheme: GradingScheme
 
     let (grade_ects_pairs, total_ects_vec): (Vec<(f64, f64)>, Vec<i32>) = results
         .into_iter()
-        .map(|result| {
+        .filter_map(|result| {
             let grade_value = if result.grading_scheme == scheme.to_string() {
                 result.grade
             } else {
-                let from_scheme = GradingScheme::from_str(&result.grading_scheme)
-                    .unwrap_or(GradingScheme::German);
-                super::conversion::convert_grade(result.grade, from_scheme, scheme)
-                    .unwrap_or(result.grade)
+                let from_scheme = match schemes.resolve(&result.grading_scheme) {
+                    Ok(from_scheme) => from_scheme,
+                    Err(e) => {
+                        warn!(grade = result.grade, "Leaving grade out of the GPA: {}", e);
+                        return None;
+                    }
+                };
+                let Some(grade) =
+                    super::conversion::convert_grade(result.grade, from_scheme, scheme.clone())
+                else {
+                    warn!(
+                        grade = result.grade,
+                        from = %result.grading_scheme,
+                        to = %scheme,
+                        "Leaving grade out of the GPA: conversion failed"
+                    );
+                    return None;
+                };
+                grade
             };
-            ((grade_value, result.ects as f64), result.ects)
+            Some(((grade_value, result.ects as f64), result.ects))
         })
         .unzip();
 
@@ -97,12 +120,14 @@ fn calculate_gpa_from_results(results: Vec<GradeWithEcts>, scheme: GradingScheme
 /// # Arguments
 /// * `db` - Database connection
 /// * `scheme` - Target grading scheme for the GPA
+/// * `schemes` - Schemes to resolve the stored scheme names with
 /// * `include_non_gpa`
 ///   - If `true`, includes grades from degree areas that don't count towards GPA.
 ///   - If `false` (recommended), only includes grades from GPA-counting areas.
 pub async fn calculate_overall_gpa(
     db: &DatabaseConnection,
     scheme: GradingScheme,
+    schemes: &SchemeRegistry,
     include_non_gpa: bool,
 ) -> Result<GPAInfo> {
     let query = if include_non_gpa {
@@ -136,7 +161,7 @@ pub async fn calculate_overall_gpa(
     .all(db)
     .await?;
 
-    Ok(calculate_gpa_from_results(results, scheme))
+    Ok(calculate_gpa_from_results(results, scheme, schemes))
 }
 
 /// Calculate GPA for a specific semester
@@ -145,6 +170,7 @@ pub async fn calculate_overall_gpa(
 /// * `db` - Database connection
 /// * `semester_id` - ID of the semester
 /// * `scheme` - Target grading scheme for the GPA
+/// * `schemes` - Schemes to resolve the stored scheme names with
 /// * `include_non_gpa`
 ///   - If `true`, includes grades from degree areas that don't count towards GPA.
 ///   - If `false` (recommended), only includes grades from GPA-counting areas.
@@ -152,6 +178,7 @@ pub async fn calculate_semester_gpa(
     db: &DatabaseConnection,
     semester_id: i64,
     scheme: GradingScheme,
+    schemes: &SchemeRegistry,
     include_non_gpa: bool,
 ) -> Result<GPAInfo> {
     let query = if include_non_gpa {
@@ -187,7 +214,7 @@ pub async fn calculate_semester_gpa(
     .all(db)
     .await?;
 
-    Ok(calculate_gpa_from_results(results, scheme))
+    Ok(calculate_gpa_from_results(results, scheme, schemes))
 }
 
 /// Calculate GPA for a specific degree
@@ -195,6 +222,7 @@ pub async fn calculate_degree_gpa(
     db: &DatabaseConnection,
     degree_id: i64,
     scheme: GradingScheme,
+    schemes: &SchemeRegistry,
 ) -> Result<GPAInfo> {
     // Get all courses mapped to this degree's areas with final grades
     let query = r#"
@@ -217,7 +245,7 @@ pub async fn calculate_degree_gpa(
     .all(db)
     .await?;
 
-    Ok(calculate_gpa_from_results(results, scheme))
+    Ok(calculate_gpa_from_results(results, scheme, schemes))
 }
 
 /// Calculate GPA for a specific degree area
@@ -225,6 +253,7 @@ pub async fn calculate_degree_area_gpa(
     db: &DatabaseConnection,
     degree_area_id: i64,
     scheme: GradingScheme,
+    schemes: &SchemeRegistry,
 ) -> Result<GPAInfo> {
     let query = r#"
         SELECT g.grade, g.grading_scheme, c.ects
@@ -244,7 +273,7 @@ pub async fn calculate_degree_area_gpa(
     .all(db)
     .await?;
 
-    Ok(calculate_gpa_from_results(results, scheme))
+    Ok(calculate_gpa_from_results(results, scheme, schemes))
 }
 
 /// Get comprehensive GPA statistics
@@ -252,15 +281,17 @@ pub async fn calculate_degree_area_gpa(
 /// # Arguments
 /// * `db` - Database connection
 /// * `scheme` - Target grading scheme for the GPA
+/// * `schemes` - Schemes to resolve the stored scheme names with
 /// * `include_non_gpa`
 ///    - If `true`, includes grades from degree areas that don't count towards GPA.
 ///    - If `false` (recommended), only includes grades from GPA-counting areas.
 pub async fn get_detailed_gpa(
     db: &DatabaseConnection,
     scheme: GradingScheme,
+    schemes: &SchemeRegistry,
     include_non_gpa: bool,
 ) -> Result<DetailedGPAInfo> {
-    let overall = calculate_overall_gpa(db, scheme, include_non_gpa).await?;
+    let overall = calculate_overall_gpa(db, scheme.clone(), schemes, include_non_gpa).await?;
 
     // TODO: Implement per-semester and per-degree-area breakdowns
     // This would require joining with semesters and degree_areas tables
@@ -298,8 +329,10 @@ mod tests {
 
     #[test]
     fn test_calculate_gpa_from_results() {
+        let schemes = SchemeRegistry::default();
+
         // Case 1: Empty results
-        let info = calculate_gpa_from_results(vec![], GradingScheme::German);
+        let info = calculate_gpa_from_results(vec![], GradingScheme::German, &schemes);
         assert_eq!(info.gpa, 0.0);
         assert_eq!(info.total_courses, 0);
         assert_eq!(info.total_ects, 0);
@@ -310,7 +343,7 @@ mod tests {
             grading_scheme: "german".to_string(),
             ects: 5,
         }];
-        let info = calculate_gpa_from_results(results, GradingScheme::German);
+        let info = calculate_gpa_from_results(results, GradingScheme::German, &schemes);
         assert_eq!(info.gpa, 1.0);
         assert_eq!(info.total_courses, 1);
         assert_eq!(info.total_ects, 5);
@@ -330,9 +363,44 @@ mod tests {
         ];
         // Convert US 4.0 to German -> approx 1.0
         // Weighted average of 1.0 (5 ECTS) and ~1.0 (5 ECTS) should be ~1.0
-        let info = calculate_gpa_from_results(results, GradingScheme::German);
+        let info = calculate_gpa_from_results(results, GradingScheme::German, &schemes);
         assert!((info.gpa - 1.0).abs() < 0.1);
         assert_eq!(info.total_courses, 2);
         assert_eq!(info.total_ects, 10);
+
+        // Case 4: Grade in a user-defined scheme
+        let schemes = SchemeRegistry::default()
+            .with_scheme(
+                ::toml::from_str(
+                    "name = \"liechtenstein\"\nmin = 1.0\nmax = 6.0\ndirection = \"higher\"\npass = 4.0",
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        let results = vec![GradeWithEcts {
+            grade: 6.0,
+            grading_scheme: "liechtenstein".to_string(),
+            ects: 5,
+        }];
+        let info = calculate_gpa_from_results(results, GradingScheme::German, &schemes);
+        assert!((info.gpa - 1.0).abs() < 0.01);
+
+        // Case 5: Grades in an unknown scheme are left out
+        let results = vec![
+            GradeWithEcts {
+                grade: 2.0,
+                grading_scheme: "german".to_string(),
+                ects: 5,
+            },
+            GradeWithEcts {
+                grade: 6.0,
+                grading_scheme: "removed".to_string(),
+                ects: 5,
+            },
+        ];
+        let info = calculate_gpa_from_results(results, GradingScheme::German, &schemes);
+        assert_eq!(info.gpa, 2.0);
+        assert_eq!(info.total_courses, 1);
+        assert_eq!(info.total_ects, 5);
     }
 }
@@ -1,6 +1,9 @@
 use super::types::{ECTSGrade, GradingScheme};
 
 /// Convert a grade from one scheme to another
+///
+/// Grades go through a percentage, so user-defined schemes convert with their
+/// percentage mapping.
 pub fn convert_grade(
     grade: f64,
     from_scheme: GradingScheme,
@@ -48,6 +51,7 @@ fn to_percentage(grade: f64, scheme: GradingScheme) -> Option<f64> {
         }
         GradingScheme::Percentage => Some(grade),
         GradingScheme::PassFail => Some(if grade >= 1.0 { 100.0 } else { 0.0 }),
+        GradingScheme::Defined(definition) => definition.to_percentage(grade),
     }
 }
 
@@ -70,6 +74,7 @@ fn from_percentage(percentage: f64, scheme: GradingScheme) -> Option<f64> {
         GradingScheme::US => Some((percentage / 100.0) * 4.0),
         GradingScheme::Percentage => Some(percentage),
         GradingScheme::PassFail => Some(if percentage >= 50.0 { 1.0 } else { 0.0 }),
+        GradingScheme::Defined(definition) => definition.from_percentage(percentage),
     }
 }
 
@@ -132,7 +137,9 @@ pub fn calculate_weighted_average(grades: &[(f64, f64)]) -> Option<f64> {
 
 #[cfg(test)]
 mod tests {
+    use super::super::scheme::ITALIAN_LODE;
     use super::*;
+    use std::str::FromStr;
 
     #[test]
     fn test_german_to_percentage() {
@@ -170,6 +177,34 @@ mod tests {
         assert_eq!(same, 2.7);
     }
 
+    #[test]
+    fn test_italian_and_swiss_to_german() {
+        let italian = GradingScheme::from_str("italian").unwrap();
+        let swiss = GradingScheme::from_str("swiss").unwrap();
+        let to_german = |grade, scheme: &GradingScheme| {
+            convert_grade(grade, scheme.clone(), GradingScheme::German).unwrap()
+        };
+
+        assert!((to_german(ITALIAN_LODE, &italian) - 1.0).abs() < 0.01);
+        assert!((to_german(30.0, &italian) - 1.0).abs() < 0.01);
+        assert!((to_german(28.0, &italian) - 1.5).abs() < 0.01);
+        assert!((to_german(24.0, &italian) - 2.5).abs() < 0.01);
+        assert!((to_german(18.0, &italian) - 4.0).abs() < 0.01);
+        assert!(to_german(15.0, &italian) > 4.0);
+
+        assert!((to_german(6.0, &swiss) - 1.0).abs() < 0.01);
+        assert!((to_german(5.0, &swiss) - 2.5).abs() < 0.01);
+        assert!((to_german(4.0, &swiss) - 4.0).abs() < 0.01);
+        assert!(to_german(3.5, &swiss) > 4.0);
+
+        // German -> Swiss/Italian snaps to their steps, never to the lode
+        assert_eq!(convert_grade(1.7, GradingScheme::German, swiss), Some(5.5));
+        assert_eq!(
+            convert_grade(1.0, GradingScheme::German, italian),
+            Some(30.0)
+        );
+    }
+
     #[test]
     fn test_german_to_ects() {
         assert_eq!(german_to_ects(1.0), Some(ECTSGrade::A));
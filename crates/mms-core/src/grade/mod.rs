@@ -2,6 +2,7 @@ pub mod builder;
 pub mod calculation;
 pub mod conversion;
 pub mod operations;
+pub mod scheme;
 pub mod types;
 
 pub use builder::GradeBuilder;
@@ -13,6 +14,7 @@ pub use conversion::{convert_grade, german_to_ects, german_to_us, us_to_german};
 pub use operations::{
     ComponentInfo, GradeInfo, add_grade_component, delete_grade, delete_grade_component,
     get_final_grade, get_grade_by_id, list_final_grades, list_grades_by_course,
-    list_passing_grades, record_grade, update_grade,
+    list_passing_grades, parse_grade_input, record_grade, update_grade,
 };
+pub use scheme::{Direction, Honours, ITALIAN_LODE, SchemeDefinition, SchemeRegistry, Steps};
 pub use types::{ECTSGrade, GradingScheme};
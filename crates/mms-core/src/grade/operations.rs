@@ -1,4 +1,5 @@
 use super::builder::ComponentDefinition;
+use super::scheme::SchemeRegistry;
 use super::types::GradingScheme;
 use crate::db::entities::{grade_components, grades, prelude::*};
 use crate::error::{MmsError, Result};
@@ -6,7 +7,6 @@ use chrono::Utc;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
 };
-use std::str::FromStr;
 
 /// Complete grade information including components
 #[derive(Debug, Clone)]
@@ -39,6 +39,20 @@ pub struct ComponentInfo {
     pub is_completed: bool,
 }
 
+/// Parse a grade as entered in a scheme, e.g. `1,7` or `30L`
+///
+/// # Errors
+/// Returns `Parse` if the input is not a valid grade of the scheme.
+pub fn parse_grade_input(scheme: &GradingScheme, input: &str) -> Result<f64> {
+    scheme.parse_grade(input).ok_or_else(|| {
+        MmsError::Parse(format!(
+            "'{}' is not a valid {} grade",
+            input.trim(),
+            scheme
+        ))
+    })
+}
+
 /// Record a new grade in the database
 ///
 /// This function creates a grade entry and all its components atomically.
@@ -72,7 +86,7 @@ pub async fn record_grade(
         grade: Set(grade),
         grading_scheme: Set(grading_scheme.to_string()),
         original_grade: Set(original_grade),
-        original_scheme: Set(original_scheme.map(|s| s.to_string())),
+        original_scheme: Set(original_scheme.as_ref().map(|s| s.to_string())),
         is_final: Set(is_final),
         passed: Set(passed),
         attempt_number: Set(attempt_number),
@@ -138,6 +152,8 @@ pub async fn record_grade(
 }
 
 /// Get a grade by ID with all its components
+///
+/// Scheme names are resolved with the schemes in `~/.config/mms/schemes`.
 pub async fn get_grade_by_id(db: &DatabaseConnection, grade_id: i64) -> Result<GradeInfo> {
     // Get grade
     let grade = Grades::find_by_id(grade_id)
@@ -166,16 +182,15 @@ pub async fn get_grade_by_id(db: &DatabaseConnection, grade_id: i64) -> Result<G
         })
         .collect();
 
+    let schemes = SchemeRegistry::load()?;
+
     Ok(GradeInfo {
         id: grade.id,
         course_id: grade.course_id,
         grade: grade.grade,
-        grading_scheme: GradingScheme::from_str(&grade.grading_scheme)
-            .unwrap_or(GradingScheme::German),
+        grading_scheme: schemes.resolve(&grade.grading_scheme)?,
         original_grade: grade.original_grade,
-        original_scheme: grade
-            .original_scheme
-            .and_then(|s| GradingScheme::from_str(&s).ok()),
+        original_scheme: grade.original_scheme.and_then(|s| schemes.get(&s)),
         is_final: grade.is_final,
         passed: grade.passed,
         attempt_number: grade.attempt_number,
@@ -237,11 +252,10 @@ pub async fn update_grade(
     if let Some(g) = new_grade {
         grade.grade = Set(g);
         // Recalculate passed status
-        let scheme = if let Some(s) = new_scheme {
-            s
+        let scheme = if let Some(s) = &new_scheme {
+            s.clone()
         } else {
-            GradingScheme::from_str(&grade.grading_scheme.clone().unwrap())
-                .unwrap_or(GradingScheme::German)
+            SchemeRegistry::load()?.resolve(&grade.grading_scheme.clone().unwrap())?
         };
         grade.passed = Set(scheme.is_passing(g));
     }
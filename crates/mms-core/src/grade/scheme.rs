@@ -0,0 +1,594 @@
+use super::types::GradingScheme;
+use crate::error::{MmsError, Result};
+use crate::paths;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock};
+use tracing::warn;
+
+/// Tolerance when comparing grades with steps and the pass threshold
+const EPSILON: f64 = 1e-6;
+
+/// Italian "30 e lode", stored above the regular maximum of 30
+pub const ITALIAN_LODE: f64 = 31.0;
+
+/// Swiss grading: 1.0 - 6.0 in quarter steps, higher is better, 4.0 passes
+pub(super) static SWISS: LazyLock<Arc<SchemeDefinition>> = LazyLock::new(|| {
+    Arc::new(SchemeDefinition {
+        name: "swiss".to_string(),
+        description: Some("Swiss 6-point scale".to_string()),
+        min: 1.0,
+        max: 6.0,
+        direction: Direction::Higher,
+        steps: Some(Steps::Size(0.25)),
+        pass: 4.0,
+        percentage: Vec::new(),
+        honours: None,
+    })
+});
+
+/// Italian grading: 0 - 30 in whole points, 18 passes, 30 e lode above 30
+///
+/// The default percentage mapping (0 = 0 %, 18 = 50 %, 30 = 100 %) matches the
+/// modified Bavarian formula: 30 converts to German 1.0 and 18 to 4.0.
+pub(super) static ITALIAN: LazyLock<Arc<SchemeDefinition>> = LazyLock::new(|| {
+    Arc::new(SchemeDefinition {
+        name: "italian".to_string(),
+        description: Some("Italian 30-point scale".to_string()),
+        min: 0.0,
+        max: 30.0,
+        direction: Direction::Higher,
+        steps: Some(Steps::Size(1.0)),
+        pass: 18.0,
+        percentage: Vec::new(),
+        honours: Some(Honours {
+            value: ITALIAN_LODE,
+            label: "30L".to_string(),
+            aliases: ["30+", "30 e lode", "30 lode", "30 cum laude"]
+                .map(String::from)
+                .to_vec(),
+        }),
+    })
+});
+
+/// Which end of the range is the best grade
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// The minimum is best, e.g. German 1.0
+    Lower,
+    /// The maximum is best, e.g. Swiss 6.0
+    Higher,
+}
+
+/// Grades allowed between `min` and `max`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Steps {
+    /// Multiples of a step size counted from `min`, e.g. `0.25`
+    Size(f64),
+    /// An explicit list, e.g. `[1.0, 1.3, 1.7, ...]`
+    Values(Vec<f64>),
+}
+
+/// A grade beyond the best one, e.g. Italian "30 e lode"
+///
+/// It passes, converts like the best grade and is never the result of a
+/// conversion.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Honours {
+    /// Value stored in `grades.grade`, beyond the best grade
+    pub value: f64,
+    /// How the grade is written, e.g. `30L`
+    pub label: String,
+    /// Further spellings accepted as input, e.g. `30 e lode`
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+/// A grading scheme defined in `~/.config/mms/schemes/<name>.toml`
+///
+/// The built-in Swiss and Italian schemes are definitions as well.
+///
+/// ```toml
+/// description = "Swiss 6-point scale"
+/// min = 1.0
+/// max = 6.0
+/// direction = "higher"
+/// steps = 0.25
+/// pass = 4.0
+/// # Optional, defaults to worst = 0%, pass = 50%, best = 100%
+/// percentage = [[1.0, 0.0], [4.0, 50.0], [6.0, 100.0]]
+/// # Optional grade beyond the best one
+/// honours = { value = 7.0, label = "6+", aliases = ["6 with distinction"] }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemeDefinition {
+    /// Name stored in `grades.grading_scheme`, defaults to the file name
+    #[serde(default)]
+    pub name: String,
+    pub description: Option<String>,
+    pub min: f64,
+    pub max: f64,
+    pub direction: Direction,
+    /// `None` allows any value in the range
+    pub steps: Option<Steps>,
+    /// Worst passing grade
+    pub pass: f64,
+    /// `[grade, percent]` points, interpolated linearly in between
+    #[serde(default)]
+    pub percentage: Vec<(f64, f64)>,
+    #[serde(default)]
+    pub honours: Option<Honours>,
+}
+
+impl SchemeDefinition {
+    /// Check that the definition is consistent
+    ///
+    /// # Errors
+    /// Returns `InvalidGradingScheme` if the range is empty, the pass threshold
+    /// or a step lies outside it, the honours do not lie beyond the best grade,
+    /// or the percentage mapping is not strictly increasing from the worst to
+    /// the best grade. Names of built-in schemes other than definitions are
+    /// reserved.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: &str| MmsError::InvalidGradingScheme {
+            name: self.name.clone(),
+            reason: reason.to_string(),
+        };
+
+        if self.name.is_empty() {
+            return Err(invalid("missing name"));
+        }
+        if GradingScheme::from_str(&self.name)
+            .is_ok_and(|scheme| !matches!(scheme, GradingScheme::Defined(_)))
+        {
+            return Err(invalid("name is taken by a built-in scheme"));
+        }
+        if self.min.partial_cmp(&self.max) != Some(std::cmp::Ordering::Less) {
+            return Err(invalid("min must be below max"));
+        }
+        if !self.in_range(self.pass) {
+            return Err(invalid("pass threshold is outside min..max"));
+        }
+        match &self.steps {
+            Some(Steps::Size(size)) if *size <= 0.0 => {
+                return Err(invalid("step size must be positive"));
+            }
+            Some(Steps::Values(values)) if values.iter().any(|v| !self.in_range(*v)) => {
+                return Err(invalid("a step is outside min..max"));
+            }
+            _ => {}
+        }
+        if let Some(honours) = &self.honours {
+            let beyond = match self.direction {
+                Direction::Lower => honours.value < self.min - EPSILON,
+                Direction::Higher => honours.value > self.max + EPSILON,
+            };
+            if !beyond {
+                return Err(invalid("honours must lie beyond the best grade"));
+            }
+            if honours.label.trim().is_empty() {
+                return Err(invalid("honours need a label"));
+            }
+        }
+
+        let points = self.points();
+        if points
+            .iter()
+            .any(|(grade, percent)| !self.in_range(*grade) || !(0.0..=100.0).contains(percent))
+        {
+            return Err(invalid("percentage points must lie in min..max and 0..100"));
+        }
+        if points
+            .windows(2)
+            .any(|w| w[1].1 <= w[0].1 || (w[1].0 - w[0].0).abs() < EPSILON)
+        {
+            return Err(invalid(
+                "percentages must increase from the worst to the best grade",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Best grade of the scheme
+    pub fn best(&self) -> f64 {
+        match self.direction {
+            Direction::Lower => self.min,
+            Direction::Higher => self.max,
+        }
+    }
+
+    /// Worst grade of the scheme
+    pub fn worst(&self) -> f64 {
+        match self.direction {
+            Direction::Lower => self.max,
+            Direction::Higher => self.min,
+        }
+    }
+
+    /// Validate if a grade lies in the range and on a step, or is the honours
+    pub fn is_valid_grade(&self, grade: f64) -> bool {
+        self.is_honours(grade) || self.is_regular_grade(grade)
+    }
+
+    /// Whether a grade is the honours value
+    pub fn is_honours(&self, grade: f64) -> bool {
+        self.honours
+            .as_ref()
+            .is_some_and(|h| (h.value - grade).abs() < EPSILON)
+    }
+
+    /// Validate if a grade lies in the range and on a step
+    fn is_regular_grade(&self, grade: f64) -> bool {
+        if !self.in_range(grade) {
+            return false;
+        }
+        match &self.steps {
+            None => true,
+            Some(Steps::Size(size)) => {
+                let steps = (grade - self.min) / size;
+                (steps - steps.round()).abs() < EPSILON
+            }
+            Some(Steps::Values(values)) => values.iter().any(|v| (v - grade).abs() < EPSILON),
+        }
+    }
+
+    /// Check if a grade is at least as good as the pass threshold
+    ///
+    /// The honours lie beyond the best grade and always pass.
+    pub fn is_passing(&self, grade: f64) -> bool {
+        match self.direction {
+            Direction::Lower => grade <= self.pass + EPSILON,
+            Direction::Higher => grade >= self.pass - EPSILON,
+        }
+    }
+
+    /// Convert a grade to percentage (0-100), the honours count as the best grade
+    pub fn to_percentage(&self, grade: f64) -> Option<f64> {
+        if self.is_honours(grade) {
+            return Some(100.0);
+        }
+        if !self.is_regular_grade(grade) {
+            return None;
+        }
+        Some(interpolate(&self.points(), grade))
+    }
+
+    /// Convert a percentage (0-100) to a grade of this scheme
+    ///
+    /// The result is snapped to the nearest step.
+    pub fn from_percentage(&self, percentage: f64) -> Option<f64> {
+        if !(0.0..=100.0).contains(&percentage) {
+            return None;
+        }
+        let inverse: Vec<(f64, f64)> = self.points().iter().map(|(g, p)| (*p, *g)).collect();
+        Some(self.snap(interpolate(&inverse, percentage).clamp(self.min, self.max)))
+    }
+
+    /// Parse a grade as written, e.g. `5,25`, `30L` or `30 e lode`
+    ///
+    /// Returns `None` unless the input is the honours label (or an alias) or a
+    /// valid regular grade, so the stored honours value itself is rejected.
+    pub fn parse_grade(&self, input: &str) -> Option<f64> {
+        let input = input.trim();
+        if let Some(honours) = &self.honours
+            && std::iter::once(&honours.label)
+                .chain(&honours.aliases)
+                .any(|spelling| spelling.eq_ignore_ascii_case(input))
+        {
+            return Some(honours.value);
+        }
+
+        let grade = input.replace(',', ".").parse().ok()?;
+        self.is_regular_grade(grade).then_some(grade)
+    }
+
+    /// Format a grade as written, e.g. `30L`, `5.25` or `6`
+    pub fn format_grade(&self, grade: f64) -> String {
+        match &self.honours {
+            Some(honours) if self.is_honours(grade) => honours.label.clone(),
+            _ => format!("{:.2}", grade)
+                .trim_end_matches('0')
+                .trim_end_matches('.')
+                .to_string(),
+        }
+    }
+
+    /// Nearest grade on a step
+    fn snap(&self, grade: f64) -> f64 {
+        match &self.steps {
+            None => grade,
+            Some(Steps::Size(size)) => {
+                let snapped = self.min + ((grade - self.min) / size).round() * size;
+                if snapped > self.max + EPSILON {
+                    snapped - size
+                } else {
+                    snapped
+                }
+            }
+            Some(Steps::Values(values)) => values
+                .iter()
+                .copied()
+                .min_by(|a, b| (a - grade).abs().total_cmp(&(b - grade).abs()))
+                .unwrap_or(grade),
+        }
+    }
+
+    fn in_range(&self, grade: f64) -> bool {
+        (self.min - EPSILON..=self.max + EPSILON).contains(&grade)
+    }
+
+    /// Percentage points ordered from the worst to the best grade
+    fn points(&self) -> Vec<(f64, f64)> {
+        let mut points = if self.percentage.is_empty() {
+            let mut points = vec![(self.worst(), 0.0), (self.best(), 100.0)];
+            // A pass threshold at either end would repeat that grade
+            if (self.pass - self.min).abs() > EPSILON && (self.pass - self.max).abs() > EPSILON {
+                points.insert(1, (self.pass, 50.0));
+            }
+            points
+        } else {
+            self.percentage.clone()
+        };
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if self.direction == Direction::Lower {
+            points.reverse();
+        }
+        points
+    }
+}
+
+/// Piecewise linear interpolation between `(x, y)` points ordered by `x`
+///
+/// `x` may be ascending or descending; values beyond the ends are clamped.
+fn interpolate(points: &[(f64, f64)], x: f64) -> f64 {
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return 0.0;
+    };
+    let ascending = first.0 <= last.0;
+    let before = |a: f64, b: f64| if ascending { a <= b } else { a >= b };
+
+    if before(x, first.0) {
+        return first.1;
+    }
+    for w in points.windows(2) {
+        let ((x0, y0), (x1, y1)) = (w[0], w[1]);
+        if before(x, x1) {
+            return y0 + (x - x0) * (y1 - y0) / (x1 - x0);
+        }
+    }
+    last.1
+}
+
+// ============================================================================
+// Registry
+// ============================================================================
+
+/// Grading schemes available by name: user-defined ones and the built-ins
+#[derive(Debug, Clone, Default)]
+pub struct SchemeRegistry {
+    custom: Vec<Arc<SchemeDefinition>>,
+}
+
+impl SchemeRegistry {
+    /// Load the schemes in `~/.config/mms/schemes`
+    ///
+    /// A missing directory gives only the built-in schemes.
+    pub fn load() -> Result<Self> {
+        Self::load_from(&paths::schemes_dir_path()?)
+    }
+
+    /// Load every `*.toml` file in `dir` as a scheme
+    ///
+    /// Files that cannot be parsed or define an invalid scheme are skipped
+    /// with a warning, so one bad file does not hide the others.
+    ///
+    /// # Errors
+    /// Returns `Io` if the directory cannot be read.
+    pub fn load_from(dir: &Path) -> Result<Self> {
+        let mut registry = Self::default();
+        if !dir.is_dir() {
+            return Ok(registry);
+        }
+
+        let mut files: Vec<_> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        files.sort();
+
+        for path in files {
+            match Self::read_definition(&path).and_then(|d| registry.add(d)) {
+                Ok(()) => {}
+                Err(e) => warn!(file = %path.display(), "Skipping grading scheme: {}", e),
+            }
+        }
+
+        Ok(registry)
+    }
+
+    /// Parse a scheme file, naming the scheme after the file unless it sets `name`
+    fn read_definition(path: &Path) -> Result<SchemeDefinition> {
+        let content = fs::read_to_string(path)?;
+        let mut definition: SchemeDefinition =
+            toml::from_str(&content).map_err(|e| MmsError::ConfigParseError {
+                path: path.to_path_buf(),
+                source: e,
+            })?;
+        if definition.name.is_empty() {
+            definition.name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+        }
+        Ok(definition)
+    }
+
+    /// Add a scheme, replacing one with the same name
+    pub fn with_scheme(mut self, definition: SchemeDefinition) -> Result<Self> {
+        self.add(definition)?;
+        Ok(self)
+    }
+
+    fn add(&mut self, definition: SchemeDefinition) -> Result<()> {
+        definition.validate()?;
+        self.custom
+            .retain(|d| !d.name.eq_ignore_ascii_case(&definition.name));
+        self.custom.push(Arc::new(definition));
+        Ok(())
+    }
+
+    /// Look up a scheme by name (case-insensitive)
+    ///
+    /// User-defined schemes take precedence over the built-in definitions
+    /// (`swiss`, `italian`), the other built-in names are reserved.
+    pub fn get(&self, name: &str) -> Option<GradingScheme> {
+        self.custom
+            .iter()
+            .find(|d| d.name.eq_ignore_ascii_case(name))
+            .map(|d| GradingScheme::Defined(Arc::clone(d)))
+            .or_else(|| GradingScheme::from_str(name).ok())
+    }
+
+    /// Scheme of a stored grade
+    ///
+    /// # Errors
+    /// Returns `InvalidGradingScheme` if the name is neither built in nor
+    /// defined in the schemes folder (e.g. its file was removed).
+    pub fn resolve(&self, name: &str) -> Result<GradingScheme> {
+        self.get(name)
+            .ok_or_else(|| MmsError::InvalidGradingScheme {
+                name: name.to_string(),
+                reason: "unknown scheme".to_string(),
+            })
+    }
+
+    /// User-defined schemes, sorted by file name
+    pub fn custom(&self) -> impl Iterator<Item = &SchemeDefinition> {
+        self.custom.iter().map(|d| d.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn swiss() -> SchemeDefinition {
+        toml::from_str(
+            r#"
+            name = "swiss"
+            min = 1.0
+            max = 6.0
+            direction = "higher"
+            steps = 0.25
+            pass = 4.0
+            percentage = [[1.0, 0.0], [4.0, 50.0], [6.0, 100.0]]
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_definition_grades() {
+        let swiss = swiss();
+        swiss.validate().unwrap();
+
+        assert!(swiss.is_valid_grade(5.25));
+        assert!(swiss.is_valid_grade(6.0));
+        assert!(!swiss.is_valid_grade(5.1));
+        assert!(!swiss.is_valid_grade(0.75));
+
+        assert!(swiss.is_passing(4.0));
+        assert!(swiss.is_passing(5.5));
+        assert!(!swiss.is_passing(3.75));
+
+        assert_eq!(swiss.to_percentage(6.0), Some(100.0));
+        assert_eq!(swiss.to_percentage(5.0), Some(75.0));
+        assert_eq!(swiss.to_percentage(5.1), None);
+        assert_eq!(swiss.from_percentage(50.0), Some(4.0));
+    }
+
+    #[test]
+    fn test_definition_lower_is_better() {
+        let definition = SchemeDefinition {
+            name: "austrian".to_string(),
+            description: None,
+            min: 1.0,
+            max: 5.0,
+            direction: Direction::Lower,
+            steps: Some(Steps::Values(vec![1.0, 2.0, 3.0, 4.0, 5.0])),
+            pass: 4.0,
+            percentage: Vec::new(),
+            honours: None,
+        };
+        definition.validate().unwrap();
+
+        assert!(definition.is_passing(1.0) && definition.is_passing(4.0));
+        assert!(!definition.is_passing(5.0));
+        assert!(!definition.is_valid_grade(2.5));
+        // Default mapping: best 100%, pass 50%, worst 0%
+        assert_eq!(definition.to_percentage(1.0), Some(100.0));
+        assert_eq!(definition.to_percentage(4.0), Some(50.0));
+        assert_eq!(definition.to_percentage(5.0), Some(0.0));
+        // Snapped to the nearest step
+        assert_eq!(definition.from_percentage(80.0), Some(2.0));
+
+        let mut invalid = definition.clone();
+        invalid.percentage = vec![(1.0, 50.0), (5.0, 100.0)];
+        assert!(invalid.validate().is_err());
+        invalid.name = "german".to_string();
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_definition_honours() {
+        let italian = ITALIAN.as_ref().clone();
+        italian.validate().unwrap();
+
+        assert!(italian.is_valid_grade(ITALIAN_LODE));
+        assert!(italian.is_passing(ITALIAN_LODE));
+        assert_eq!(italian.to_percentage(ITALIAN_LODE), Some(100.0));
+        assert_eq!(italian.from_percentage(100.0), Some(30.0));
+        assert_eq!(italian.format_grade(ITALIAN_LODE), "30L");
+        assert_eq!(italian.format_grade(28.0), "28");
+
+        let mut invalid = italian.clone();
+        invalid.honours.as_mut().unwrap().value = 29.0;
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_registry_load_from() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("swiss.toml"),
+            "min = 1.0\nmax = 6.0\ndirection = \"higher\"\nsteps = 0.25\npass = 4.0\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("README.md"), "not a scheme").unwrap();
+
+        let registry = SchemeRegistry::load_from(temp_dir.path()).unwrap();
+        assert_eq!(registry.custom().count(), 1);
+
+        let scheme = registry.get("Swiss").unwrap();
+        assert_eq!(scheme.to_string(), "swiss");
+        assert!(scheme.is_passing(4.5));
+        assert_eq!(registry.get("german"), Some(GradingScheme::German));
+        assert!(registry.resolve("unknown").is_err());
+
+        let missing = SchemeRegistry::load_from(&temp_dir.path().join("missing")).unwrap();
+        assert_eq!(missing.custom().count(), 0);
+
+        // Bad files are skipped, the others still load
+        fs::write(temp_dir.path().join("broken.toml"), "min = 6.0\nmax = 1.0").unwrap();
+        fs::write(temp_dir.path().join("invalid.toml"), "min = [").unwrap();
+        let registry = SchemeRegistry::load_from(temp_dir.path()).unwrap();
+        assert_eq!(registry.custom().count(), 1);
+        assert!(registry.get("broken").is_none());
+    }
+}
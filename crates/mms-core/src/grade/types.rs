@@ -1,10 +1,15 @@
 use std::str::FromStr;
+use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
+use super::scheme::{ITALIAN, SWISS, SchemeDefinition};
+
 /// Grading scheme used for a course
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+///
+/// Built-in schemes parse with `from_str`; user-defined ones are looked up in
+/// a [`SchemeRegistry`](super::SchemeRegistry).
+#[derive(Debug, Clone, PartialEq)]
 pub enum GradingScheme {
     /// German grading (1.0 - 5.0, lower is better)
     German,
@@ -16,6 +21,9 @@ pub enum GradingScheme {
     Percentage,
     /// Pass/Fail
     PassFail,
+    /// Scheme given by a definition: the built-in Swiss and Italian ones or
+    /// one loaded from `~/.config/mms/schemes`
+    Defined(Arc<SchemeDefinition>),
 }
 
 impl FromStr for GradingScheme {
@@ -29,6 +37,8 @@ impl FromStr for GradingScheme {
             "us" | "gpa" | "american" => Ok(GradingScheme::US),
             "percentage" | "percent" | "%" => Ok(GradingScheme::Percentage),
             "passfail" | "pass/fail" | "pf" | "passed" => Ok(GradingScheme::PassFail),
+            "italian" | "it" | "ita" => Ok(GradingScheme::Defined(Arc::clone(&ITALIAN))),
+            "swiss" | "ch" | "switzerland" => Ok(GradingScheme::Defined(Arc::clone(&SWISS))),
             _ => Err(()),
         }
     }
@@ -43,6 +53,7 @@ impl GradingScheme {
             GradingScheme::US => grade >= 2.0, // Typically D (2.0) is minimum passing
             GradingScheme::Percentage => grade >= 50.0,
             GradingScheme::PassFail => grade >= 1.0, // 1 = pass, 0 = fail
+            GradingScheme::Defined(definition) => definition.is_passing(grade),
         }
     }
 
@@ -54,6 +65,30 @@ impl GradingScheme {
             GradingScheme::US => (0.0..=4.0).contains(&grade),
             GradingScheme::Percentage => (0.0..=100.0).contains(&grade),
             GradingScheme::PassFail => grade == 0.0 || grade == 1.0,
+            GradingScheme::Defined(definition) => definition.is_valid_grade(grade),
+        }
+    }
+
+    /// Parse a grade as written in this scheme
+    ///
+    /// Accepts decimal commas (`5,25`) and, for Italian grades, `30L`, `30+`
+    /// and `30 e lode`. Returns `None` unless the grade is valid.
+    pub fn parse_grade(&self, input: &str) -> Option<f64> {
+        match self {
+            GradingScheme::Defined(definition) => definition.parse_grade(input),
+            _ => {
+                let grade = input.trim().replace(',', ".").parse().ok()?;
+                self.is_valid_grade(grade).then_some(grade)
+            }
+        }
+    }
+
+    /// Format a grade as written in this scheme (e.g. `30L`, `5.25`)
+    pub fn format_grade(&self, grade: f64) -> String {
+        match self {
+            GradingScheme::Defined(definition) => definition.format_grade(grade),
+            GradingScheme::Percentage => format!("{}", grade),
+            _ => format!("{:.1}", grade),
         }
     }
 }
@@ -66,6 +101,7 @@ impl std::fmt::Display for GradingScheme {
             GradingScheme::US => write!(f, "us"),
             GradingScheme::Percentage => write!(f, "percentage"),
             GradingScheme::PassFail => write!(f, "passfail"),
+            GradingScheme::Defined(definition) => write!(f, "{}", definition.name),
         }
     }
 }
@@ -135,8 +171,17 @@ impl std::fmt::Display for ECTSGrade {
 
 #[cfg(test)]
 mod tests {
+    use super::super::scheme::ITALIAN_LODE;
     use super::*;
 
+    fn italian() -> GradingScheme {
+        GradingScheme::from_str("italian").unwrap()
+    }
+
+    fn swiss() -> GradingScheme {
+        GradingScheme::from_str("swiss").unwrap()
+    }
+
     #[test]
     fn test_grading_scheme_from_str() {
         assert_eq!(GradingScheme::from_str("german"), Ok(GradingScheme::German));
@@ -155,9 +200,58 @@ mod tests {
             Ok(GradingScheme::PassFail)
         );
 
+        assert_eq!(italian().to_string(), "italian");
+        assert_eq!(GradingScheme::from_str("CH"), Ok(swiss()));
+
         assert_eq!(GradingScheme::from_str("invalid"), Err(()));
     }
 
+    #[test]
+    fn test_italian_and_swiss_grades() {
+        let italian = italian();
+        assert!(italian.is_passing(18.0));
+        assert!(italian.is_passing(ITALIAN_LODE));
+        assert!(!italian.is_passing(17.0));
+        assert!(!italian.is_valid_grade(27.5));
+        assert!(!italian.is_valid_grade(32.0));
+
+        let swiss = swiss();
+        assert!(swiss.is_passing(4.0));
+        assert!(swiss.is_passing(6.0));
+        assert!(!swiss.is_passing(3.75));
+        assert!(swiss.is_valid_grade(5.25));
+        assert!(!swiss.is_valid_grade(5.3));
+        assert!(!swiss.is_valid_grade(0.75));
+    }
+
+    #[test]
+    fn test_parse_and_format_grade() {
+        let italian = italian();
+        assert_eq!(italian.parse_grade("30L"), Some(ITALIAN_LODE));
+        assert_eq!(italian.parse_grade("30l"), Some(ITALIAN_LODE));
+        assert_eq!(italian.parse_grade("30+"), Some(ITALIAN_LODE));
+        assert_eq!(italian.parse_grade("30 e lode"), Some(ITALIAN_LODE));
+        assert_eq!(italian.parse_grade("28"), Some(28.0));
+        assert_eq!(italian.parse_grade("27,0"), Some(27.0));
+        assert_eq!(italian.parse_grade("28L"), None);
+        assert_eq!(italian.parse_grade("27,5"), None);
+        // The stored value of the lode is not a grade on its own
+        assert_eq!(italian.parse_grade("31"), None);
+        assert_eq!(italian.format_grade(ITALIAN_LODE), "30L");
+        assert_eq!(italian.format_grade(28.0), "28");
+
+        let swiss = swiss();
+        assert_eq!(swiss.parse_grade("5.25"), Some(5.25));
+        assert_eq!(swiss.parse_grade("5,5"), Some(5.5));
+        assert_eq!(swiss.parse_grade("5.3"), None);
+        assert_eq!(swiss.format_grade(5.25), "5.25");
+        assert_eq!(swiss.format_grade(4.5), "4.5");
+        assert_eq!(swiss.format_grade(6.0), "6");
+
+        assert_eq!(GradingScheme::German.parse_grade("1,7"), Some(1.7));
+        assert_eq!(GradingScheme::German.format_grade(1.7), "1.7");
+    }
+
     #[test]
     fn test_grading_scheme_is_passing() {
         assert!(GradingScheme::German.is_passing(1.0));
@@ -0,0 +1,5 @@
+pub mod operations;
+pub mod types;
+
+pub use operations::{HistoryEntry, HistoryFilter, last_active, list_history, record_switch};
+pub use types::SwitchReason;
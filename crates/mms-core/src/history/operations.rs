@@ -0,0 +1,275 @@
+use crate::db::queries;
+use crate::error::{MmsError, Result};
+use crate::history::types::SwitchReason;
+use chrono::{DateTime, Days, Local, NaiveDate, TimeZone, Utc};
+use sea_orm::DatabaseConnection;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A recorded change of the active course
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub id: i64,
+    pub switched_at: DateTime<Utc>,
+    pub from_course_id: Option<i64>,
+    /// Short name of the previous course (`None` if no course was active or it was deleted)
+    pub from: Option<String>,
+    pub to_course_id: Option<i64>,
+    /// Short name of the new course (`None` if no course is active afterwards)
+    pub to: Option<String>,
+    pub reason: SwitchReason,
+}
+
+/// Which history entries to list
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HistoryFilter {
+    /// First day to include (local time)
+    pub from: Option<NaiveDate>,
+    /// Last day to include (local time)
+    pub to: Option<NaiveDate>,
+    /// Only switches from or to this course
+    pub course_id: Option<i64>,
+    /// Maximum number of entries (newest first)
+    pub limit: Option<u64>,
+}
+
+// ============================================================================
+// Recording
+// ============================================================================
+
+/// Record a change of the active course
+///
+/// Nothing is recorded if `from` and `to` are the same.
+pub async fn record_switch(
+    db: &DatabaseConnection,
+    from: Option<i64>,
+    to: Option<i64>,
+    reason: SwitchReason,
+    at: DateTime<Utc>,
+) -> Result<()> {
+    if from == to {
+        return Ok(());
+    }
+    queries::switch_history::record(db, from, to, &reason.to_string(), at).await?;
+    Ok(())
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// List recorded switches matching `filter`, newest first
+pub async fn list_history(
+    db: &DatabaseConnection,
+    filter: &HistoryFilter,
+) -> Result<Vec<HistoryEntry>> {
+    let from = filter.from.map(start_of_day).transpose()?;
+    let to = filter
+        .to
+        .map(|to| start_of_day(to + Days::new(1)))
+        .transpose()?;
+
+    let rows = queries::switch_history::list(db, from, to, filter.course_id, filter.limit).await?;
+
+    // Resolve short names once per course
+    let mut names: HashMap<i64, Option<String>> = HashMap::new();
+    let mut entries = Vec::with_capacity(rows.len());
+    for row in rows {
+        let from = short_name(db, &mut names, row.from_course_id).await?;
+        let to = short_name(db, &mut names, row.to_course_id).await?;
+        let reason = SwitchReason::from_str(&row.reason)
+            .map_err(|_| MmsError::InvalidSwitchReason(row.reason.clone()))?;
+
+        entries.push(HistoryEntry {
+            id: row.id,
+            switched_at: row.switched_at,
+            from_course_id: row.from_course_id,
+            from,
+            to_course_id: row.to_course_id,
+            to,
+            reason,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// When the course was last active
+///
+/// Returns `now` if the course is still active and `None` if it never was.
+pub async fn last_active(
+    db: &DatabaseConnection,
+    course_id: i64,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>> {
+    let latest = queries::switch_history::list(db, None, None, Some(course_id), Some(1)).await?;
+
+    Ok(latest.first().map(|entry| {
+        // Switching to the course without a later switch away means it is still active
+        if entry.to_course_id == Some(course_id) {
+            now
+        } else {
+            entry.switched_at
+        }
+    }))
+}
+
+/// Look up a course's short name, caching the result
+async fn short_name(
+    db: &DatabaseConnection,
+    cache: &mut HashMap<i64, Option<String>>,
+    course_id: Option<i64>,
+) -> Result<Option<String>> {
+    let Some(id) = course_id else {
+        return Ok(None);
+    };
+    if let Some(name) = cache.get(&id) {
+        return Ok(name.clone());
+    }
+
+    let name = queries::course::get_by_id(db, id)
+        .await
+        .ok()
+        .map(|course| course.short_name);
+    cache.insert(id, name.clone());
+    Ok(name)
+}
+
+/// Midnight of a local date in UTC
+fn start_of_day(date: NaiveDate) -> Result<DateTime<Utc>> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or_else(|| MmsError::Other(format!("Invalid local date: {}", date)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{course, semester_with_course};
+    use tempfile::TempDir;
+
+    async fn setup() -> (DatabaseConnection, TempDir, i64, i64) {
+        let temp_dir = TempDir::new().unwrap();
+        let (db, algo) = semester_with_course(temp_dir.path(), "algo", "Algorithms").await;
+        let ana = course(&db, algo.semester_id, "ana", "Analysis", 6).await;
+
+        (db, temp_dir, algo.id, ana.id)
+    }
+
+    fn local(date: &str, time: &str) -> DateTime<Utc> {
+        let naive =
+            chrono::NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M")
+                .unwrap();
+        Local
+            .from_local_datetime(&naive)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[tokio::test]
+    async fn test_history_filters() {
+        let (db, _temp_dir, algo, ana) = setup().await;
+
+        let switches = [
+            (
+                None,
+                Some(algo),
+                SwitchReason::Schedule,
+                local("2024-10-07", "09:50"),
+            ),
+            (
+                Some(algo),
+                None,
+                SwitchReason::Schedule,
+                local("2024-10-07", "12:00"),
+            ),
+            (
+                None,
+                Some(ana),
+                SwitchReason::Manual,
+                local("2024-10-08", "14:00"),
+            ),
+            (
+                Some(ana),
+                Some(algo),
+                SwitchReason::Override,
+                local("2024-10-09", "08:00"),
+            ),
+        ];
+        for (from, to, reason, at) in switches {
+            record_switch(&db, from, to, reason, at).await.unwrap();
+        }
+        // Not a change
+        record_switch(
+            &db,
+            Some(algo),
+            Some(algo),
+            SwitchReason::Manual,
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+
+        let all = list_history(&db, &HistoryFilter::default()).await.unwrap();
+        assert_eq!(all.len(), 4);
+        assert_eq!(all[0].reason, SwitchReason::Override);
+        assert_eq!(all[0].from.as_deref(), Some("ana"));
+        assert_eq!(all[0].to.as_deref(), Some("algo"));
+
+        let filter = HistoryFilter {
+            from: NaiveDate::from_ymd_opt(2024, 10, 8),
+            to: NaiveDate::from_ymd_opt(2024, 10, 8),
+            ..Default::default()
+        };
+        let day = list_history(&db, &filter).await.unwrap();
+        assert_eq!(day.len(), 1);
+        assert_eq!(day[0].reason, SwitchReason::Manual);
+
+        let filter = HistoryFilter {
+            course_id: Some(ana),
+            ..Default::default()
+        };
+        assert_eq!(list_history(&db, &filter).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_last_active() {
+        let (db, _temp_dir, algo, ana) = setup().await;
+        let now = local("2024-10-10", "12:00");
+
+        assert_eq!(last_active(&db, algo, now).await.unwrap(), None);
+
+        record_switch(
+            &db,
+            None,
+            Some(algo),
+            SwitchReason::Schedule,
+            local("2024-10-07", "09:50"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(last_active(&db, algo, now).await.unwrap(), Some(now));
+
+        record_switch(
+            &db,
+            Some(algo),
+            Some(ana),
+            SwitchReason::Manual,
+            local("2024-10-07", "12:00"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            last_active(&db, algo, now).await.unwrap(),
+            Some(local("2024-10-07", "12:00"))
+        );
+        assert_eq!(last_active(&db, ana, now).await.unwrap(), Some(now));
+    }
+}
@@ -0,0 +1,43 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Why the active course changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SwitchReason {
+    /// The daemon followed the recurring schedule
+    Schedule,
+    /// The user selected the course
+    Manual,
+    /// A one-time or override event took precedence over the schedule
+    Override,
+    /// The active course was archived
+    Archive,
+}
+
+impl FromStr for SwitchReason {
+    type Err = ();
+
+    /// Parse from a string (case-insensitive)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "schedule" => Ok(SwitchReason::Schedule),
+            "manual" => Ok(SwitchReason::Manual),
+            "override" => Ok(SwitchReason::Override),
+            "archive" => Ok(SwitchReason::Archive),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for SwitchReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SwitchReason::Schedule => write!(f, "schedule"),
+            SwitchReason::Manual => write!(f, "manual"),
+            SwitchReason::Override => write!(f, "override"),
+            SwitchReason::Archive => write!(f, "archive"),
+        }
+    }
+}
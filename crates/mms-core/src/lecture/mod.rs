@@ -0,0 +1,3 @@
+pub mod operations;
+
+pub use operations::{sync_lectures, sync_semester_lectures};
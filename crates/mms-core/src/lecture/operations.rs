@@ -0,0 +1,275 @@
+use crate::db::entities::lectures;
+use crate::db::queries;
+use crate::error::Result;
+use crate::service::ScheduleEngine;
+use chrono::{NaiveDate, NaiveDateTime};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, Set, TransactionTrait};
+use std::collections::HashSet;
+
+/// Schedule type whose occurrences are recorded as lectures
+const LECTURE_SCHEDULE_TYPE: &str = "lecture";
+
+// ============================================================================
+// Lecture Sync
+// ============================================================================
+
+/// Create lecture rows for all elapsed lecture occurrences of a course
+///
+/// This function performs the following steps:
+/// 1. Enumerates all "lecture" schedule occurrences that ended before `now`
+///    (cancellations and holidays are skipped by the schedule engine)
+/// 2. Skips occurrences that already have a lecture from the same schedule on the
+///    same date (or, for lectures without a schedule, the same date and start time),
+///    so a time change does not record an occurrence twice
+/// 3. Inserts the remaining occurrences, numbered consecutively after the
+///    highest existing lecture number
+///
+/// Running it repeatedly is safe: already recorded occurrences are never duplicated.
+///
+/// Returns the newly created lectures in chronological order.
+pub async fn sync_lectures(
+    db: &DatabaseConnection,
+    course_id: i64,
+    now: NaiveDateTime,
+) -> Result<Vec<lectures::Model>> {
+    let schedules = queries::schedule::list_by_course(db, course_id).await?;
+
+    // Earliest start date of any lecture schedule bounds the enumeration
+    let mut from: Option<NaiveDate> = None;
+    for schedule in schedules
+        .iter()
+        .filter(|s| s.schedule_type.eq_ignore_ascii_case(LECTURE_SCHEDULE_TYPE))
+    {
+        let start = NaiveDate::parse_from_str(&schedule.start_date, "%Y-%m-%d")?;
+        from = Some(from.map_or(start, |f| f.min(start)));
+    }
+
+    let Some(from) = from else {
+        return Ok(Vec::new());
+    };
+
+    let occurrences = ScheduleEngine::occurrences_for_course(db, course_id, from, now.date())
+        .await?
+        .into_iter()
+        .filter(|o| o.is_type(LECTURE_SCHEDULE_TYPE))
+        .filter(|o| NaiveDateTime::new(o.date, o.end_time) <= now);
+
+    let existing = queries::lectures::list_by_course(db, course_id).await?;
+    let recorded: HashSet<(i64, String)> = existing
+        .iter()
+        .filter_map(|l| l.schedule_id.map(|id| (id, l.date.clone())))
+        .collect();
+    let recorded_unscheduled: HashSet<(String, String)> = existing
+        .iter()
+        .filter(|l| l.schedule_id.is_none())
+        .map(|l| (l.date.clone(), l.start_time.clone()))
+        .collect();
+    let mut next_number = existing.iter().map(|l| l.lecture_number).max().unwrap_or(0) + 1;
+
+    let txn = db.begin().await?;
+    let mut created = Vec::new();
+
+    for occurrence in occurrences {
+        let date = occurrence.date.format("%Y-%m-%d").to_string();
+        let start_time = occurrence.start_time.format("%H:%M").to_string();
+
+        if recorded.contains(&(occurrence.schedule_id, date.clone()))
+            || recorded_unscheduled.contains(&(date.clone(), start_time.clone()))
+        {
+            continue;
+        }
+
+        let now_utc = chrono::Utc::now();
+        let lecture = lectures::ActiveModel {
+            course_id: Set(course_id),
+            lecture_number: Set(next_number),
+            schedule_type: Set(occurrence.schedule_type),
+            date: Set(date),
+            start_time: Set(start_time),
+            end_time: Set(occurrence.end_time.format("%H:%M").to_string()),
+            room: Set(occurrence.room),
+            building: Set(occurrence.building),
+            location: Set(occurrence.location),
+            schedule_id: Set(Some(occurrence.schedule_id)),
+            created_at: Set(now_utc),
+            updated_at: Set(now_utc),
+            ..Default::default()
+        };
+
+        created.push(lecture.insert(&txn).await?);
+        next_number += 1;
+    }
+
+    txn.commit().await?;
+
+    Ok(created)
+}
+
+/// Create lecture rows for all courses of the current semester
+///
+/// Returns the newly created lectures across all courses.
+pub async fn sync_semester_lectures(
+    db: &DatabaseConnection,
+    now: NaiveDateTime,
+) -> Result<Vec<lectures::Model>> {
+    let Some(semester) = queries::semester::get_current(db).await? else {
+        return Ok(Vec::new());
+    };
+
+    let mut created = Vec::new();
+    for course in queries::course::list_unarchived_by_semester(db, semester.id).await? {
+        created.extend(sync_lectures(db, course.id, now).await?);
+    }
+
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::entities::{course_events, course_schedules, holidays};
+    use crate::test_support::semester_with_course;
+    use tempfile::TempDir;
+
+    /// Set up an in-memory database with a semester and a single course
+    async fn setup_course() -> (DatabaseConnection, TempDir, i64) {
+        let temp_dir = TempDir::new().unwrap();
+        let (db, course) = semester_with_course(temp_dir.path(), "algo", "Algorithms").await;
+
+        (db, temp_dir, course.id)
+    }
+
+    /// Add a weekly schedule (Monday 10:00-12:00, October 2024)
+    async fn add_schedule(db: &DatabaseConnection, course_id: i64, schedule_type: &str) -> i64 {
+        course_schedules::ActiveModel {
+            course_id: Set(course_id),
+            schedule_type: Set(schedule_type.to_string()),
+            day_of_week: Set(0),
+            start_time: Set("10:00".to_string()),
+            end_time: Set("12:00".to_string()),
+            start_date: Set("2024-10-01".to_string()),
+            end_date: Set("2024-10-31".to_string()),
+            room: Set(Some("HS1".to_string())),
+            priority: Set(0),
+            created_at: Set(chrono::Utc::now()),
+            updated_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap()
+        .id
+    }
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sync_creates_numbered_lectures() {
+        let (db, _temp_dir, course_id) = setup_course().await;
+        add_schedule(&db, course_id, "Lecture").await;
+        add_schedule(&db, course_id, "Tutorium").await;
+
+        // Mondays in October 2024: 7, 14, 21, 28 - the one on the 21st is still running
+        let created = sync_lectures(&db, course_id, at("2024-10-21", "11:00"))
+            .await
+            .unwrap();
+
+        let dates: Vec<_> = created.iter().map(|l| l.date.as_str()).collect();
+        assert_eq!(dates, vec!["2024-10-07", "2024-10-14"]);
+        assert_eq!(created[0].lecture_number, 1);
+        assert_eq!(created[1].lecture_number, 2);
+        assert_eq!(created[0].room, Some("HS1".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_sync_is_idempotent() {
+        let (db, _temp_dir, course_id) = setup_course().await;
+        add_schedule(&db, course_id, "lecture").await;
+
+        let now = at("2024-10-15", "00:00");
+        assert_eq!(sync_lectures(&db, course_id, now).await.unwrap().len(), 2);
+        assert!(sync_lectures(&db, course_id, now).await.unwrap().is_empty());
+
+        // Later runs continue the numbering
+        let created = sync_lectures(&db, course_id, at("2024-11-01", "00:00"))
+            .await
+            .unwrap();
+        let numbers: Vec<_> = created.iter().map(|l| l.lecture_number).collect();
+        assert_eq!(numbers, vec![3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_sync_skips_cancellations_and_holidays() {
+        let (db, _temp_dir, course_id) = setup_course().await;
+        let schedule_id = add_schedule(&db, course_id, "Lecture").await;
+
+        course_events::ActiveModel {
+            course_id: Set(course_id),
+            schedule_id: Set(Some(schedule_id)),
+            event_type: Set("Cancellation".to_string()),
+            date: Set("2024-10-14".to_string()),
+            created_at: Set(chrono::Utc::now()),
+            updated_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        holidays::ActiveModel {
+            name: Set("Autumn break".to_string()),
+            start_date: Set("2024-10-20".to_string()),
+            end_date: Set("2024-10-22".to_string()),
+            holiday_type: Set("break".to_string()),
+            created_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let created = sync_lectures(&db, course_id, at("2024-11-01", "00:00"))
+            .await
+            .unwrap();
+
+        let dates: Vec<_> = created.iter().map(|l| l.date.as_str()).collect();
+        assert_eq!(dates, vec!["2024-10-07", "2024-10-28"]);
+        assert_eq!(created[1].lecture_number, 2);
+    }
+
+    #[tokio::test]
+    async fn test_sync_time_change_keeps_lecture() {
+        let (db, _temp_dir, course_id) = setup_course().await;
+        let schedule_id = add_schedule(&db, course_id, "Lecture").await;
+
+        let created = sync_lectures(&db, course_id, at("2024-10-15", "00:00"))
+            .await
+            .unwrap();
+        assert_eq!(created.len(), 2);
+
+        // The lecture on the 14th is moved after it was recorded
+        course_events::ActiveModel {
+            course_id: Set(course_id),
+            schedule_id: Set(Some(schedule_id)),
+            event_type: Set("TimeChange".to_string()),
+            date: Set("2024-10-14".to_string()),
+            start_time: Set(Some("14:00".to_string())),
+            end_time: Set(Some("16:00".to_string())),
+            created_at: Set(chrono::Utc::now()),
+            updated_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let created = sync_lectures(&db, course_id, at("2024-10-22", "00:00"))
+            .await
+            .unwrap();
+        let dates: Vec<_> = created.iter().map(|l| l.date.as_str()).collect();
+        assert_eq!(dates, vec!["2024-10-21"]);
+        assert_eq!(created[0].lecture_number, 3);
+    }
+}
@@ -1,13 +1,23 @@
+pub mod archive;
+pub mod attendance;
 pub mod config;
 pub mod course;
 pub mod db;
 pub mod degree;
 pub mod error;
+pub mod exercise;
 pub mod grade;
+pub mod history;
+pub mod lecture;
+pub mod notes;
 pub mod paths;
 pub mod semester;
 pub mod service;
+pub mod slides;
+pub mod study_time;
 pub mod symlink;
 pub mod sync;
+#[cfg(test)]
+pub(crate) mod test_support;
 pub mod toml;
 pub mod utils;
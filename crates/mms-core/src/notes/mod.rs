@@ -0,0 +1,5 @@
+pub mod operations;
+
+pub use operations::{
+    DEFAULT_LECTURE_TEMPLATE, LectureNote, create_lecture_note, lecture_note_path, load_template,
+};
@@ -0,0 +1,167 @@
+use crate::error::Result;
+use crate::paths;
+use chrono::NaiveDate;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Template used if no template file with the configured name exists
+pub const DEFAULT_LECTURE_TEMPLATE: &str = r#"#set document(title: "{{course_code}} - Lecture {{lecture_number}}")
+
+= {{course_name}} - Lecture {{lecture_number}}
+{{date}}
+
+// === YOUR NOTES START HERE ===
+"#;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// The note file of a single lecture
+///
+/// Notes live in `<course>/notes/lectures/lecture_NN.typ` and compile to
+/// `<course>/notes/build/lecture_NN.pdf`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LectureNote {
+    pub course_dir: PathBuf,
+    pub course_code: String,
+    pub course_name: String,
+    pub semester: String,
+    pub number: i64,
+    pub date: NaiveDate,
+}
+
+impl LectureNote {
+    /// Path of the Typst source
+    pub fn source_path(&self) -> PathBuf {
+        lecture_note_path(&self.course_dir, self.number)
+    }
+
+    /// Path of the compiled PDF
+    pub fn pdf_path(&self) -> PathBuf {
+        notes_dir(&self.course_dir)
+            .join("build")
+            .join(format!("lecture_{:02}.pdf", self.number))
+    }
+
+    /// Values of the `{{...}}` template variables
+    fn variables(&self, student_name: Option<&str>) -> Vec<(&'static str, String)> {
+        vec![
+            ("course_code", self.course_code.clone()),
+            ("course_name", self.course_name.clone()),
+            ("semester", self.semester.clone()),
+            ("lecture_number", self.number.to_string()),
+            ("date", self.date.format("%d.%m.%Y").to_string()),
+            ("student_name", student_name.unwrap_or_default().to_string()),
+        ]
+    }
+}
+
+// ============================================================================
+// Notes
+// ============================================================================
+
+/// Folder holding a course's notes
+pub fn notes_dir(course_dir: &Path) -> PathBuf {
+    course_dir.join("notes")
+}
+
+/// Typst source of a course's lecture note
+pub fn lecture_note_path(course_dir: &Path, number: i64) -> PathBuf {
+    notes_dir(course_dir)
+        .join("lectures")
+        .join(format!("lecture_{:02}.typ", number))
+}
+
+/// Load a lecture note template by name
+///
+/// Looks for `<config dir>/templates/<name>.typ` and falls back to
+/// [`DEFAULT_LECTURE_TEMPLATE`].
+pub fn load_template(name: &str) -> Result<String> {
+    let path = paths::templates_dir_path()?.join(format!("{}.typ", name));
+    if path.exists() {
+        return Ok(fs::read_to_string(path)?);
+    }
+    Ok(DEFAULT_LECTURE_TEMPLATE.to_string())
+}
+
+/// Create the note file of a lecture from a template
+///
+/// An existing note is never overwritten. Returns whether the file was created.
+pub fn create_lecture_note(
+    note: &LectureNote,
+    template: &str,
+    student_name: Option<&str>,
+) -> Result<bool> {
+    let path = note.source_path();
+    if path.exists() {
+        return Ok(false);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::create_dir_all(notes_dir(&note.course_dir).join("build"))?;
+    fs::write(
+        &path,
+        render_template(template, &note.variables(student_name)),
+    )?;
+    Ok(true)
+}
+
+/// Replace `{{name}}` placeholders; unknown placeholders are left as they are
+fn render_template(template: &str, variables: &[(&str, String)]) -> String {
+    variables
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{{{}}}}}", name), value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn note(course_dir: &Path) -> LectureNote {
+        LectureNote {
+            course_dir: course_dir.to_path_buf(),
+            course_code: "cs101".to_string(),
+            course_name: "Algorithms".to_string(),
+            semester: "b3".to_string(),
+            number: 4,
+            date: NaiveDate::from_ymd_opt(2024, 10, 28).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_create_lecture_note() {
+        let temp_dir = TempDir::new().unwrap();
+        let note = note(temp_dir.path());
+        assert_eq!(
+            note.source_path(),
+            temp_dir.path().join("notes/lectures/lecture_04.typ")
+        );
+        assert_eq!(
+            note.pdf_path(),
+            temp_dir.path().join("notes/build/lecture_04.pdf")
+        );
+
+        let template =
+            "= {{course_name}} {{lecture_number}} ({{date}}, {{student_name}}) {{tutor}}";
+        assert!(create_lecture_note(&note, template, Some("Alice")).unwrap());
+        assert_eq!(
+            fs::read_to_string(note.source_path()).unwrap(),
+            "= Algorithms 4 (28.10.2024, Alice) {{tutor}}"
+        );
+        assert!(temp_dir.path().join("notes/build").is_dir());
+
+        // Existing notes are kept
+        assert!(!create_lecture_note(&note, "overwritten", None).unwrap());
+        assert!(
+            fs::read_to_string(note.source_path())
+                .unwrap()
+                .starts_with("= Algorithms")
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::error::{MmsError, Result};
+use crate::semester::SemesterType;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -45,18 +46,169 @@ pub fn database_path() -> Result<PathBuf> {
     data_dir_path().map(|it| it.join("mms.db"))
 }
 
+/// Returns the directory of the daemon's log files. Created if missing.
+pub fn log_dir_path() -> Result<PathBuf> {
+    let log_dir = data_dir_path()?.join("logs");
+    ensure_exists(&log_dir)?;
+    Ok(log_dir)
+}
+
+/// Returns the directory of user templates. Directory may not exist.
+pub fn templates_dir_path() -> Result<PathBuf> {
+    config_dir_path().map(|it| it.join("templates"))
+}
+
+/// Returns the directory of user-defined grading schemes. Directory may not exist.
+pub fn schemes_dir_path() -> Result<PathBuf> {
+    config_dir_path().map(|it| it.join("schemes"))
+}
+
+/// Returns path to the daemon's control socket. Socket may not exist.
+pub fn control_socket_path() -> Result<PathBuf> {
+    data_dir_path().map(|it| it.join("daemon.sock"))
+}
+
+// ====================================
+
+// === SEMESTER FOLDERS ===============
+
+/// Default semester folder pattern (`b3`, `m1`)
+pub const DEFAULT_SEMESTER_FOLDER: &str = "{type}{number}";
+
+/// Part of a semester folder pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FolderToken {
+    Literal(String),
+    Type,
+    /// Semester number, zero-padded to the given width
+    Number(usize),
+}
+
+/// Split a semester folder pattern into its parts
+///
+/// Returns `None` unless the pattern contains `{type}` and `{number}` (or `{number:0N}`)
+/// exactly once and no path separators.
+fn folder_tokens(pattern: &str) -> Option<Vec<FolderToken>> {
+    let mut tokens = Vec::new();
+    let mut rest = pattern;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("{type}") {
+            tokens.push(FolderToken::Type);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("{number}") {
+            tokens.push(FolderToken::Number(0));
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("{number:0") {
+            let (width, after) = after.split_once('}')?;
+            tokens.push(FolderToken::Number(width.parse().ok()?));
+            rest = after;
+        } else {
+            let end = rest
+                .char_indices()
+                .skip(1)
+                .find(|&(_, c)| c == '{')
+                .map_or(rest.len(), |(i, _)| i);
+            let literal = &rest[..end];
+            if literal.contains(['{', '}', '/', '\\']) {
+                return None;
+            }
+            tokens.push(FolderToken::Literal(literal.to_string()));
+            rest = &rest[end..];
+        }
+    }
+
+    let count = |f: fn(&FolderToken) -> bool| tokens.iter().filter(|t| f(t)).count();
+    let types = count(|t| matches!(t, FolderToken::Type));
+    let numbers = count(|t| matches!(t, FolderToken::Number(_)));
+    (types == 1 && numbers == 1).then_some(tokens)
+}
+
+/// Check that a semester folder pattern can be used
+pub fn validate_semester_folder(pattern: &str) -> Result<()> {
+    folder_tokens(pattern)
+        .map(|_| ())
+        .ok_or_else(|| MmsError::InvalidSemesterFolder(pattern.to_string()))
+}
+
+/// Tokens of `pattern`, or of the default pattern if it is invalid
+fn folder_tokens_or_default(pattern: &str) -> Vec<FolderToken> {
+    folder_tokens(pattern)
+        .or_else(|| folder_tokens(DEFAULT_SEMESTER_FOLDER))
+        .unwrap_or_default()
+}
+
+/// Folder name of a semester, e.g. `b03` for `{type}{number:02}`
+pub fn semester_folder_name(pattern: &str, semester_type: SemesterType, number: i64) -> String {
+    folder_tokens_or_default(pattern)
+        .iter()
+        .map(|token| match token {
+            FolderToken::Literal(literal) => literal.clone(),
+            FolderToken::Type => semester_type.prefix().to_string(),
+            FolderToken::Number(width) => format!("{:0width$}", number, width = *width),
+        })
+        .collect()
+}
+
+/// Parse a folder name created with `pattern` into semester type and number
+///
+/// Only the exact name `semester_folder_name` would create is accepted,
+/// e.g. `b3` does not match `{type}{number:02}`.
+pub fn parse_semester_folder(pattern: &str, folder_name: &str) -> Option<(SemesterType, i64)> {
+    let tokens = folder_tokens_or_default(pattern);
+    let mut rest = folder_name;
+    let mut semester_type = None;
+    let mut number = None;
+
+    for token in &tokens {
+        match token {
+            FolderToken::Literal(literal) => rest = rest.strip_prefix(literal.as_str())?,
+            FolderToken::Type => {
+                let (found, after) = SemesterType::strip_prefix(rest)?;
+                rest = after;
+                semester_type = Some(found);
+            }
+            FolderToken::Number(_) => {
+                let end = rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                number = Some(rest[..end].parse::<i64>().ok()?);
+                rest = &rest[end..];
+            }
+        }
+    }
+
+    let parsed = (semester_type?, number?);
+    (rest.is_empty() && semester_folder_name(pattern, parsed.0, parsed.1) == folder_name)
+        .then_some(parsed)
+}
+
+/// Get the directory path for a semester
+/// Precondition: `base_path` must be canonicalized.
+pub fn semester_directory(
+    base_path: &Path,
+    pattern: &str,
+    semester_type: SemesterType,
+    semester_number: i64,
+) -> PathBuf {
+    assert!(base_path.is_absolute(), "Paths must be canonicalized!");
+    base_path.join(semester_folder_name(
+        pattern,
+        semester_type,
+        semester_number,
+    ))
+}
+
 /// Get the directory path for a course
 /// Precondition: `base_path` must be canonicalized.
 pub fn course_directory(
     base_path: &Path,
-    semester_type_initial: char,
-    semester_number: i32,
+    pattern: &str,
+    semester_type: SemesterType,
+    semester_number: i64,
     course_short_name: &str,
 ) -> PathBuf {
-    assert!(base_path.is_absolute(), "Paths must be canonicalized!");
-    base_path
-        .join(format!("{}{:02}", semester_type_initial, semester_number))
-        .join(course_short_name)
+    semester_directory(base_path, pattern, semester_type, semester_number).join(course_short_name)
 }
 
 // ====================================
@@ -69,16 +221,84 @@ mod tests {
     #[should_panic(expected = "Paths must be canonicalized!")]
     fn test_course_directory_precondtion() {
         let base = PathBuf::from("uni");
-        let _ = course_directory(&base, 'm', 5, "ml");
+        let _ = course_directory(
+            &base,
+            DEFAULT_SEMESTER_FOLDER,
+            SemesterType::Master,
+            5,
+            "ml",
+        );
     }
 
     #[test]
     fn test_course_directory() {
         let base = PathBuf::from("/tmp/uni");
-        let path = course_directory(&base, 'm', 5, "ml");
-        assert_eq!(path, PathBuf::from("/tmp/uni/m05/ml"));
+        let path = course_directory(
+            &base,
+            DEFAULT_SEMESTER_FOLDER,
+            SemesterType::Master,
+            5,
+            "ml",
+        );
+        assert_eq!(path, PathBuf::from("/tmp/uni/m5/ml"));
 
-        let path = course_directory(&base, 'b', 2, "algo");
+        let path = course_directory(
+            &base,
+            "{type}{number:02}",
+            SemesterType::Bachelor,
+            2,
+            "algo",
+        );
         assert_eq!(path, PathBuf::from("/tmp/uni/b02/algo"));
     }
+
+    #[test]
+    fn test_semester_folder_pattern() {
+        assert!(validate_semester_folder("{type}{number}").is_ok());
+        assert!(validate_semester_folder("sem-{number:02}-{type}").is_ok());
+        assert!(validate_semester_folder("{type}").is_err());
+        assert!(validate_semester_folder("{type}{number}{number}").is_err());
+        assert!(validate_semester_folder("{type}/{number}").is_err());
+        assert!(validate_semester_folder("{kind}{number}").is_err());
+
+        assert_eq!(
+            semester_folder_name("{type}{number:02}", SemesterType::Master, 12),
+            "m12"
+        );
+        assert_eq!(
+            semester_folder_name("sem-{number}-{type}", SemesterType::Bachelor, 3),
+            "sem-3-b"
+        );
+    }
+
+    #[test]
+    fn test_parse_semester_folder() {
+        assert_eq!(
+            parse_semester_folder("{type}{number}", "b3"),
+            Some((SemesterType::Bachelor, 3))
+        );
+        assert_eq!(
+            parse_semester_folder("{type}{number:02}", "m01"),
+            Some((SemesterType::Master, 1))
+        );
+        assert_eq!(
+            parse_semester_folder("sem-{number}-{type}", "sem-4-m"),
+            Some((SemesterType::Master, 4))
+        );
+        assert_eq!(
+            parse_semester_folder("{type}{number:02}", "phd02"),
+            Some((SemesterType::PhD, 2))
+        );
+        assert_eq!(
+            parse_semester_folder("sem-{number}-{type}", "sem-1-prep"),
+            Some((SemesterType::Preparatory, 1))
+        );
+
+        // Only the canonical name matches
+        assert_eq!(parse_semester_folder("{type}{number:02}", "b3"), None);
+        assert_eq!(parse_semester_folder("{type}{number}", "b03"), None);
+        assert_eq!(parse_semester_folder("{type}{number}", "x3"), None);
+        assert_eq!(parse_semester_folder("{type}{number}", "b3-old"), None);
+        assert_eq!(parse_semester_folder("{type}{number}", ".archive"), None);
+    }
 }
@@ -1,7 +1,7 @@
 use crate::config::Config;
 use crate::error::Result;
+use crate::semester::SemesterType;
 use crate::semester::operations::{SemesterInfo, create_semester};
-use crate::toml::SemesterType;
 use sea_orm::DatabaseConnection;
 
 /// Builder for creating a new semester
@@ -39,7 +39,7 @@ impl SemesterBuilder {
     /// Create a new semester builder with required fields
     ///
     /// # Arguments
-    /// * `semester_type` - Bachelor or Master
+    /// * `semester_type` - Kind of semester (bachelor, master, phd, ...)
     /// * `number` - Semester number (e.g., 1, 2, 3...)
     pub fn new(semester_type: SemesterType, number: i64) -> Self {
         Self {
@@ -116,7 +116,7 @@ impl SemesterBuilder {
 
     /// Get the semester code (e.g., "b3", "m1")
     pub fn code(&self) -> String {
-        format!("{}{}", self.semester_type.prefix(), self.number)
+        self.semester_type.code(self.number)
     }
 }
 
@@ -1,7 +1,9 @@
 use crate::config::Config;
 use crate::db::entities::semesters;
 use crate::error::{MmsError, Result};
-use crate::toml::{SemesterToml, SemesterType};
+use crate::paths;
+use crate::semester::SemesterType;
+use crate::toml::SemesterToml;
 use crate::utils::date_validation::{validate_date_format, validate_date_range};
 use chrono::Utc;
 use sea_orm::{
@@ -9,6 +11,7 @@ use sea_orm::{
     TransactionTrait,
 };
 use std::path::PathBuf;
+use uuid::Uuid;
 
 /// Validate semester number is positive
 fn validate_semester_number(number: i64) -> Result<()> {
@@ -23,7 +26,7 @@ fn validate_semester_number(number: i64) -> Result<()> {
 pub struct SemesterInfo {
     /// Database ID
     pub id: i64,
-    /// Semester type (Bachelor or Master)
+    /// Semester type
     pub semester_type: SemesterType,
     /// Semester number
     pub number: i64,
@@ -56,7 +59,7 @@ impl TryFrom<semesters::Model> for SemesterInfo {
             id: model.id,
             semester_type,
             number: model.number,
-            code: format!("{}{}", semester_type.prefix(), model.number),
+            code: semester_type.code(model.number),
             directory_path: PathBuf::from(model.directory_path),
             start_date: model.start_date,
             end_date: model.end_date,
@@ -78,7 +81,7 @@ impl TryFrom<semesters::Model> for SemesterInfo {
 /// # Arguments
 /// * `config` - Application configuration
 /// * `db` - Database connection
-/// * `semester_type` - Bachelor or Master
+/// * `semester_type` - Kind of semester (bachelor, master, phd, ...)
 /// * `number` - Semester number
 /// * `start_date` - Optional start date (German format: DD.MM.YYYY, e.g., "01.10.2024")
 /// * `end_date` - Optional end date (German format: DD.MM.YYYY, e.g., "31.03.2025")
@@ -141,8 +144,11 @@ pub async fn create_semester(
 
     // Setup paths
     let base_path = &config.university_base_path;
-    let code = format!("{}{}", semester_type.prefix(), number);
-    let semester_dir = base_path.join(&code);
+    let semester_dir = base_path.join(paths::semester_folder_name(
+        config.semester_folder(),
+        semester_type,
+        number,
+    ));
 
     let final_location = location.as_ref().cloned().or_else(|| {
         config
@@ -151,6 +157,9 @@ pub async fn create_semester(
             .and_then(|g| g.default_location.clone())
     });
 
+    // Stable ID recognising the folder after a rename or move
+    let uid = Uuid::new_v4().to_string();
+
     // Use transaction for atomicity
     let txn = db.begin().await?;
 
@@ -168,6 +177,7 @@ pub async fn create_semester(
         university: ActiveValue::Set(university.clone()),
         is_current: ActiveValue::Set(is_current),
         is_archived: ActiveValue::Set(is_archived),
+        uid: ActiveValue::Set(Some(uid.clone())),
         created_at: ActiveValue::Set(Utc::now()),
         updated_at: ActiveValue::Set(Utc::now()),
     };
@@ -185,7 +195,7 @@ pub async fn create_semester(
     }
 
     // Create TOML
-    let mut toml = SemesterToml::new(semester_type, number);
+    let mut toml = SemesterToml::new(semester_type, number).with_uid(uid);
     if let Some(start) = &start_date {
         toml = toml.with_start_date(start.clone());
     }
@@ -220,7 +230,7 @@ pub async fn create_semester(
         id: final_model.id,
         semester_type,
         number,
-        code,
+        code: semester_type.code(number),
         directory_path: semester_dir,
         start_date,
         end_date,
@@ -376,34 +386,16 @@ pub async fn get_semester_by_id(db: &DatabaseConnection, semester_id: i64) -> Re
     semester.try_into()
 }
 
-/// Get a semester by its code (e.g., "b3", "m1")
+/// Get a semester by its code (e.g., "b3", "m1", "phd2")
 pub async fn get_semester_by_code(db: &DatabaseConnection, code: &str) -> Result<SemesterInfo> {
     // Parse code into type and number
-    let first_char = code
-        .chars()
-        .next()
-        .ok_or_else(|| MmsError::InvalidSemesterCode {
-            code: code.to_string(),
-        })?;
-
-    let semester_type = match first_char {
-        'b' | 'B' => "bachelor",
-        'm' | 'M' => "master",
-        _ => {
-            return Err(MmsError::InvalidSemesterCode {
-                code: code.to_string(),
-            });
-        }
-    };
-
-    let number: i64 = code[1..]
-        .parse()
-        .map_err(|_| MmsError::InvalidSemesterCode {
+    let (semester_type, number) =
+        SemesterType::parse_code(code).ok_or_else(|| MmsError::InvalidSemesterCode {
             code: code.to_string(),
         })?;
 
     let semester = semesters::Entity::find()
-        .filter(semesters::Column::Type.eq(semester_type))
+        .filter(semesters::Column::Type.eq(semester_type.to_string()))
         .filter(semesters::Column::Number.eq(number))
         .one(db)
         .await?
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+
+/// Kind of a semester, shared by TOML files, folder names and semester codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SemesterType {
+    Bachelor,
+    Master,
+    #[serde(rename = "phd")]
+    PhD,
+    /// Semester abroad or at a guest university
+    Exchange,
+    /// Preparatory course before the first semester
+    Preparatory,
+    /// Orientierungssemester, an orientation semester before choosing a degree
+    Orientation,
+}
+
+impl SemesterType {
+    /// All semester types
+    pub const ALL: [SemesterType; 6] = [
+        SemesterType::Bachelor,
+        SemesterType::Master,
+        SemesterType::PhD,
+        SemesterType::Exchange,
+        SemesterType::Preparatory,
+        SemesterType::Orientation,
+    ];
+
+    /// Get the prefix of codes and folder names (e.g. "b" in "b3")
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            SemesterType::Bachelor => "b",
+            SemesterType::Master => "m",
+            SemesterType::PhD => "phd",
+            SemesterType::Exchange => "ex",
+            SemesterType::Preparatory => "prep",
+            SemesterType::Orientation => "o",
+        }
+    }
+
+    /// Get the semester code (e.g. "b3", "phd1")
+    pub fn code(&self, number: i64) -> String {
+        format!("{}{}", self.prefix(), number)
+    }
+
+    /// Parse from a name or prefix (case-insensitive)
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "bachelor" | "b" => Some(SemesterType::Bachelor),
+            "master" | "m" => Some(SemesterType::Master),
+            "phd" | "doctorate" => Some(SemesterType::PhD),
+            "exchange" | "ex" => Some(SemesterType::Exchange),
+            "preparatory" | "prep" => Some(SemesterType::Preparatory),
+            "orientation" | "orientierungssemester" | "o" => Some(SemesterType::Orientation),
+            _ => None,
+        }
+    }
+
+    /// Type whose prefix starts `s`, preferring the longest prefix
+    pub fn strip_prefix(s: &str) -> Option<(Self, &str)> {
+        Self::ALL
+            .into_iter()
+            .filter_map(|t| s.strip_prefix(t.prefix()).map(|rest| (t, rest)))
+            .min_by_key(|(_, rest)| rest.len())
+    }
+
+    /// Parse a semester code (e.g. "b3", "PhD1") into type and number
+    pub fn parse_code(code: &str) -> Option<(Self, i64)> {
+        let code = code.to_lowercase();
+        let (semester_type, number) = Self::strip_prefix(&code)?;
+        if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        Some((semester_type, number.parse().ok()?))
+    }
+}
+
+impl std::fmt::Display for SemesterType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SemesterType::Bachelor => write!(f, "bachelor"),
+            SemesterType::Master => write!(f, "master"),
+            SemesterType::PhD => write!(f, "phd"),
+            SemesterType::Exchange => write!(f, "exchange"),
+            SemesterType::Preparatory => write!(f, "preparatory"),
+            SemesterType::Orientation => write!(f, "orientation"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_semester_type_prefix() {
+        assert_eq!(SemesterType::Bachelor.prefix(), "b");
+        assert_eq!(SemesterType::Master.prefix(), "m");
+        assert_eq!(SemesterType::PhD.code(2), "phd2");
+        assert_eq!(SemesterType::Orientation.code(1), "o1");
+    }
+
+    #[test]
+    fn test_semester_type_from_str() {
+        assert_eq!(
+            SemesterType::from_str("bachelor"),
+            Some(SemesterType::Bachelor)
+        );
+        assert_eq!(
+            SemesterType::from_str("Bachelor"),
+            Some(SemesterType::Bachelor)
+        );
+        assert_eq!(SemesterType::from_str("b"), Some(SemesterType::Bachelor));
+        assert_eq!(SemesterType::from_str("master"), Some(SemesterType::Master));
+        assert_eq!(SemesterType::from_str("m"), Some(SemesterType::Master));
+        assert_eq!(SemesterType::from_str("invalid"), None);
+
+        // Display and serde names parse back
+        for semester_type in SemesterType::ALL {
+            assert_eq!(
+                SemesterType::from_str(&semester_type.to_string()),
+                Some(semester_type)
+            );
+            assert_eq!(
+                SemesterType::from_str(semester_type.prefix()),
+                Some(semester_type)
+            );
+        }
+        assert_eq!(
+            SemesterType::from_str("Orientierungssemester"),
+            Some(SemesterType::Orientation)
+        );
+    }
+
+    #[test]
+    fn test_parse_code() {
+        assert_eq!(
+            SemesterType::parse_code("b3"),
+            Some((SemesterType::Bachelor, 3))
+        );
+        assert_eq!(
+            SemesterType::parse_code("PhD1"),
+            Some((SemesterType::PhD, 1))
+        );
+        assert_eq!(
+            SemesterType::parse_code("prep1"),
+            Some((SemesterType::Preparatory, 1))
+        );
+        assert_eq!(
+            SemesterType::parse_code("ex2"),
+            Some((SemesterType::Exchange, 2))
+        );
+        assert_eq!(SemesterType::parse_code("b"), None);
+        assert_eq!(SemesterType::parse_code("x3"), None);
+        assert_eq!(SemesterType::parse_code("b3a"), None);
+    }
+}
@@ -0,0 +1,298 @@
+//! Control socket of the daemon
+//!
+//! The protocol is line-delimited JSON over a Unix-domain socket: the client
+//! writes one request, the daemon answers with one response and closes the
+//! connection.
+//!
+//! ```text
+//! > {"command":"pause","minutes":60}
+//! < {"ok":true,"state":{"active_course":"cs101",...}}
+//! ```
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+#[cfg(unix)]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::path::Path;
+#[cfg(unix)]
+use std::time::Duration;
+#[cfg(unix)]
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+#[cfg(unix)]
+use tokio::net::UnixListener;
+#[cfg(unix)]
+use tokio::sync::mpsc;
+use tokio::sync::oneshot;
+
+#[cfg(unix)]
+use crate::error::{MmsError, Result};
+#[cfg(unix)]
+use crate::paths;
+
+/// How long the client waits for the daemon
+#[cfg(unix)]
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// First and longest pause after a failed `accept` (e.g. out of file descriptors)
+#[cfg(unix)]
+const ACCEPT_BACKOFF: (Duration, Duration) = (Duration::from_millis(100), Duration::from_secs(5));
+
+// ==================================================================================
+// Protocol
+// ==================================================================================
+
+/// Request sent to the daemon
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlRequest {
+    /// Report the current state
+    Status,
+    /// Re-check the schedule now
+    Recheck,
+    /// Stop switching courses automatically, indefinitely if `minutes` is `None`
+    Pause { minutes: Option<u64> },
+    /// Resume automatic switching
+    Resume,
+    /// Reload the configuration file
+    Reload,
+}
+
+/// Response of the daemon
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ControlResponse {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<DaemonState>,
+}
+
+impl ControlResponse {
+    pub fn ok(state: Option<DaemonState>) -> Self {
+        Self {
+            ok: true,
+            error: None,
+            state,
+        }
+    }
+
+    pub fn error(error: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            error: Some(error.into()),
+            state: None,
+        }
+    }
+}
+
+/// Snapshot of the daemon's state
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaemonState {
+    /// Short name of the active course
+    pub active_course: Option<String>,
+    /// When the daemon checks the schedule next
+    pub next_check: Option<NaiveDateTime>,
+    /// The next course switch according to the schedule
+    pub next_switch: Option<PlannedSwitch>,
+    /// Whether the active course was selected manually and is kept
+    pub manual: bool,
+    /// When the manual selection ends (`None` while manual means not within a week)
+    pub manual_until: Option<NaiveDateTime>,
+    /// Whether automatic switching is paused
+    pub paused: bool,
+    /// When automatic switching resumes (`None` while paused means indefinitely)
+    pub paused_until: Option<NaiveDateTime>,
+}
+
+/// A course switch planned by the schedule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedSwitch {
+    pub at: NaiveDateTime,
+    /// Short name of the course switched to, `None` if no course will be active
+    pub course: Option<String>,
+}
+
+/// A request together with the channel its response is sent on
+pub type ControlMessage = (ControlRequest, oneshot::Sender<ControlResponse>);
+
+// ==================================================================================
+// Server
+// ==================================================================================
+
+/// Listen on the control socket and forward requests to the daemon
+///
+/// A stale socket file is replaced; callers must make sure no other daemon
+/// is running (the PID file lock does).
+#[cfg(unix)]
+pub fn start_server(path: &Path) -> Result<mpsc::Receiver<ControlMessage>> {
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    let (sender, receiver) = mpsc::channel(8);
+
+    tokio::spawn(async move {
+        let (initial, max) = ACCEPT_BACKOFF;
+        let mut backoff = initial;
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => {
+                    backoff = initial;
+                    stream
+                }
+                Err(e) => {
+                    tracing::warn!("Control socket accept failed: {}", e);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(max);
+                    continue;
+                }
+            };
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                if let Err(e) = serve_connection(stream, sender).await {
+                    tracing::warn!("Control connection failed: {}", e);
+                }
+            });
+        }
+    });
+
+    Ok(receiver)
+}
+
+/// Read one request, wait for the daemon's response and write it back
+#[cfg(unix)]
+async fn serve_connection(
+    stream: tokio::net::UnixStream,
+    sender: mpsc::Sender<ControlMessage>,
+) -> std::io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    tokio::io::BufReader::new(reader)
+        .read_line(&mut line)
+        .await?;
+
+    let response = match serde_json::from_str::<ControlRequest>(&line) {
+        Ok(request) => {
+            let (reply, response) = oneshot::channel();
+            if sender.send((request, reply)).await.is_err() {
+                ControlResponse::error("daemon is shutting down")
+            } else {
+                response
+                    .await
+                    .unwrap_or_else(|_| ControlResponse::error("daemon dropped the request"))
+            }
+        }
+        Err(e) => ControlResponse::error(format!("invalid request: {}", e)),
+    };
+
+    let mut json = serde_json::to_string(&response)?;
+    json.push('\n');
+    writer.write_all(json.as_bytes()).await?;
+    writer.shutdown().await
+}
+
+// ==================================================================================
+// Client
+// ==================================================================================
+
+/// Send a request to the running daemon
+#[cfg(unix)]
+pub fn send(request: &ControlRequest) -> Result<ControlResponse> {
+    send_to(&paths::control_socket_path()?, request)
+}
+
+/// Send a request to the daemon listening on `path`
+#[cfg(unix)]
+pub fn send_to(path: &Path, request: &ControlRequest) -> Result<ControlResponse> {
+    let unreachable = |e: std::io::Error| MmsError::DaemonUnreachable(e.to_string());
+
+    let mut stream = UnixStream::connect(path).map_err(unreachable)?;
+    stream
+        .set_read_timeout(Some(CLIENT_TIMEOUT))
+        .map_err(unreachable)?;
+
+    let mut json =
+        serde_json::to_string(request).map_err(|e| MmsError::DaemonUnreachable(e.to_string()))?;
+    json.push('\n');
+    stream.write_all(json.as_bytes()).map_err(unreachable)?;
+
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .map_err(unreachable)?;
+    serde_json::from_str(&line)
+        .map_err(|e| MmsError::DaemonUnreachable(format!("invalid response: {}", e)))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_request_format() {
+        let json = serde_json::to_string(&ControlRequest::Pause { minutes: Some(30) }).unwrap();
+        assert_eq!(json, r#"{"command":"pause","minutes":30}"#);
+
+        let request: ControlRequest = serde_json::from_str(r#"{"command":"recheck"}"#).unwrap();
+        assert_eq!(request, ControlRequest::Recheck);
+
+        let request: ControlRequest = serde_json::from_str(r#"{"command":"pause"}"#).unwrap();
+        assert_eq!(request, ControlRequest::Pause { minutes: None });
+    }
+
+    #[tokio::test]
+    async fn test_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("daemon.sock");
+        let mut receiver = start_server(&path).unwrap();
+
+        // Answer requests like the daemon loop would
+        tokio::spawn(async move {
+            while let Some((request, reply)) = receiver.recv().await {
+                let response = match request {
+                    ControlRequest::Status => ControlResponse::ok(Some(DaemonState {
+                        active_course: Some("cs101".to_string()),
+                        ..Default::default()
+                    })),
+                    _ => ControlResponse::error("unsupported"),
+                };
+                let _ = reply.send(response);
+            }
+        });
+
+        let client_path = path.clone();
+        let response =
+            tokio::task::spawn_blocking(move || send_to(&client_path, &ControlRequest::Status))
+                .await
+                .unwrap()
+                .unwrap();
+        assert!(response.ok);
+        assert_eq!(
+            response.state.unwrap().active_course.as_deref(),
+            Some("cs101")
+        );
+
+        let client_path = path.clone();
+        let response =
+            tokio::task::spawn_blocking(move || send_to(&client_path, &ControlRequest::Reload))
+                .await
+                .unwrap()
+                .unwrap();
+        assert!(!response.ok);
+        assert_eq!(response.error.as_deref(), Some("unsupported"));
+    }
+
+    #[test]
+    fn test_daemon_not_running() {
+        let temp_dir = TempDir::new().unwrap();
+        let result = send_to(
+            &temp_dir.path().join("daemon.sock"),
+            &ControlRequest::Status,
+        );
+        assert!(matches!(result, Err(MmsError::DaemonUnreachable(_))));
+    }
+}
@@ -1,136 +1,773 @@
-use std::fs;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use std::time::Duration;
-use tokio::time::sleep;
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
 
+use crate::attendance;
 use crate::config::Config;
+use crate::config::settings::{GeneralConfig, HooksConfig, LoggingConfig, NotesConfig};
 use crate::db::connection_seaorm;
+use crate::db::entities::{
+    active_course, course_events, course_schedules, courses, exercises, holiday_exceptions,
+    holidays, semesters, todos,
+};
 use crate::db::queries;
 use crate::error::{MmsError, Result};
-use crate::service::scheduler::ScheduleEngine;
-use crate::symlink;
+use crate::history::{self, SwitchReason};
+use crate::lecture;
+use crate::paths;
+#[cfg(unix)]
+use crate::service::control::{
+    self, ControlMessage, ControlRequest, ControlResponse, DaemonState, PlannedSwitch,
+};
+use crate::service::hooks::{self, HookContext, HookEvent};
+use crate::service::logging;
+use crate::service::notifier::{self, Notifier};
+use crate::service::scheduler::{Occurrence, ScheduleEngine};
+use crate::service::session::LectureSession;
+use crate::service::watcher::{self, FileWatcher};
+use crate::slides;
+use crate::symlink::Symlinks;
 use sea_orm::DatabaseConnection;
 
+/// How often the open activation interval is marked as seen while a course is active
+const HEARTBEAT_INTERVAL: chrono::Duration = chrono::Duration::minutes(15);
+
+/// How far ahead the next slot ending a manual selection is searched
+const MANUAL_HORIZON: chrono::Duration = chrono::Duration::days(7);
+
 /// Daemon that runs in the background and automatically switches courses
 pub struct Daemon {
+    /// Fallback interval if the next schedule boundary cannot be determined
     check_interval: Duration,
+    /// How long before a slot starts the course is switched
+    switch_window: chrono::Duration,
     pid_file: PathBuf,
+    auto_mark_attendance: bool,
+    notifier: Option<Arc<dyn Notifier>>,
+    /// Keys of sent notifications and the day they were sent
+    sent_notifications: HashMap<String, chrono::NaiveDate>,
+    hooks: Option<HooksConfig>,
+    /// Keys of deadlines whose hooks already ran
+    deadlines_run: HashSet<String>,
+    notes: Option<NotesConfig>,
+    general: Option<GeneralConfig>,
+    /// Notes opened for running lectures
+    sessions: Vec<LectureSession>,
+    /// Watches the config, the database and the slides folders while waiting
+    watcher: Option<FileWatcher>,
+    /// When slot start/end hooks were last checked
+    last_slot_check: Option<chrono::NaiveDateTime>,
+    /// State shared with the control task
+    shared: Arc<Mutex<SharedState>>,
+    /// Wake ups requested by the control task, `None` without a control socket
+    control: Option<mpsc::UnboundedReceiver<Wake>>,
+    logging: LoggingConfig,
+    /// Links in `general.symlink_path`, `None` without `[general]`
+    symlinks: Option<Symlinks>,
+}
+
+/// Daemon state the control task reads and changes
+#[derive(Debug, Default)]
+struct SharedState {
+    /// Automatic switching is paused until then (`NaiveDateTime::MAX` = indefinitely)
+    paused_until: Option<chrono::NaiveDateTime>,
+    /// When the schedule is checked next
+    next_check: Option<chrono::NaiveDateTime>,
+    /// Copy of the daemon's switch window
+    switch_window: chrono::Duration,
+}
+
+/// Lock the shared state, ignoring a panic of another holder
+fn lock(shared: &Mutex<SharedState>) -> MutexGuard<'_, SharedState> {
+    shared.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Receive the next wake up from the control task, never resolving without a control socket
+async fn recv_control(control: &mut Option<mpsc::UnboundedReceiver<Wake>>) -> Option<Wake> {
+    match control {
+        Some(receiver) => receiver.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Reason the daemon woke up from waiting
+enum Wake {
+    /// The planned boundary was reached
+    Due,
+    /// The database or config file changed
+    Changed,
+    /// A file in a slides folder changed
+    SlidesChanged,
+    /// A signal was received
+    Signal(DaemonSignal),
 }
 
 impl Daemon {
     /// Create a new daemon instance
     pub fn new() -> Result<Self> {
         let config = Config::load()?;
+        let pid_file = Self::get_pid_file_path()?;
+        let mut daemon = Self {
+            check_interval: Duration::ZERO,
+            switch_window: chrono::Duration::zero(),
+            pid_file,
+            auto_mark_attendance: false,
+            notifier: None,
+            sent_notifications: HashMap::new(),
+            hooks: None,
+            deadlines_run: HashSet::new(),
+            notes: None,
+            general: None,
+            sessions: Vec::new(),
+            watcher: None,
+            last_slot_check: None,
+            shared: Arc::default(),
+            control: None,
+            logging: LoggingConfig::default(),
+            symlinks: None,
+        };
+        daemon.apply_config(&config)?;
+
+        Ok(daemon)
+    }
 
+    /// Apply the daemon-relevant settings of a configuration
+    fn apply_config(&mut self, config: &Config) -> Result<()> {
         // Get schedule config or return error
         let schedule = config.schedule.as_ref().ok_or(MmsError::ScheduleNotSet)?;
 
-        let check_interval = Duration::from_secs(schedule.check_interval_minutes * 60);
-        let pid_file = Self::get_pid_file_path()?;
+        self.check_interval = Duration::from_secs(schedule.check_interval_minutes * 60);
+        self.switch_window = chrono::Duration::minutes(schedule.switch_window_minutes as i64);
+        lock(&self.shared).switch_window = self.switch_window;
+        self.auto_mark_attendance = config.attendance.as_ref().is_some_and(|a| a.auto_mark);
+        self.notifier = notifier::notifier_from_config(config);
+        self.hooks = config.hooks.clone();
+        self.notes = config.notes.clone();
+        self.general = config.general.clone();
+        self.logging = config.logging.clone().unwrap_or_default();
+        self.symlinks = Symlinks::from_config(config).ok();
 
-        Ok(Self {
-            check_interval,
-            pid_file,
-        })
+        Ok(())
     }
 
     /// Start the daemon loop
-    pub async fn run(&self) -> Result<()> {
-        // Check if another instance is already running
-        if self.is_running()? {
-            return Err(MmsError::Other(
-                "Daemon is already running. Use 'mms service stop' to stop it first.".to_string(),
-            ));
-        }
+    pub async fn run(&mut self) -> Result<()> {
+        // Lock the PID file - fails if another instance holds it
+        let pid_lock = self.acquire_pid_file()?;
 
-        // Write PID file
-        self.write_pid_file()?;
+        // Keep the guard alive so buffered log lines are flushed on exit
+        let _log_guard = logging::init(&self.logging)?;
 
-        println!("Starting MMS daemon...");
-        println!(
-            "Check interval: {} minutes",
-            self.check_interval.as_secs() / 60
+        info!(
+            pid = std::process::id(),
+            switch_window_minutes = self.switch_window.num_minutes(),
+            pid_file = %self.pid_file.display(),
+            "Starting MMS daemon"
         );
-        println!("PID file: {}", self.pid_file.display());
 
-        // Set up signal handlers for graceful shutdown
-        let running = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
-        let r = running.clone();
+        // Set up signal handlers for graceful shutdown and reload
+        let mut signals = Signals::new()?;
 
-        ctrlc::set_handler(move || {
-            println!("\nReceived shutdown signal. Stopping daemon...");
-            r.store(false, std::sync::atomic::Ordering::SeqCst);
-        })
-        .map_err(|e| MmsError::Other(format!("Failed to set signal handler: {}", e)))?;
+        // Accept requests from `mms service ...` and other clients, answered
+        // by a separate task so they do not wait for hooks or slide scans
+        #[cfg(unix)]
+        match paths::control_socket_path().and_then(|path| control::start_server(&path)) {
+            Ok(requests) => {
+                let (wake, receiver) = mpsc::unbounded_channel();
+                let task = ControlTask {
+                    shared: Arc::clone(&self.shared),
+                    wake,
+                };
+                tokio::spawn(task.run(requests));
+                self.control = Some(receiver);
+            }
+            Err(e) => warn!("Control socket unavailable: {}", e),
+        }
+
+        // Re-plan when the config or database changes, rescan changed slides
+        match paths::config_path()
+            .and_then(|config| Ok((config, paths::database_path()?)))
+            .and_then(|(config, database)| FileWatcher::new(config, database))
+        {
+            Ok(watcher) => self.watcher = Some(watcher),
+            Err(e) => warn!("File watcher unavailable: {}", e),
+        }
+
+        // Catch up on lectures that ended while the daemon was not running
+        if let Err(e) = self.sync_all_lectures().await {
+            error!("Error syncing lectures: {}", e);
+        }
 
         // Main daemon loop
-        while running.load(std::sync::atomic::Ordering::SeqCst) {
-            if let Err(e) = self.check_and_update().await {
-                eprintln!("Error checking schedule: {}", e);
+        loop {
+            if let Err(e) = self.heartbeat().await {
+                error!("Error recording heartbeat: {}", e);
+            }
+
+            if !self.is_paused()
+                && let Err(e) = self.check_and_update().await
+            {
+                error!("Error checking schedule: {}", e);
                 // Continue running despite errors
             }
 
-            // Sleep for the configured interval
-            sleep(self.check_interval).await;
+            if let Err(e) = self.handle_slots().await {
+                error!("Error handling slot start/end: {}", e);
+            }
+
+            if let Err(e) = self.handle_deadlines().await {
+                error!("Error handling deadlines: {}", e);
+            }
+
+            if let Err(e) = self.send_notifications().await {
+                error!("Error sending notifications: {}", e);
+            }
+
+            if let Err(e) = self.scan_slides().await {
+                error!("Error scanning slides: {}", e);
+            }
+
+            // Sleep until the next schedule boundary
+            let wake_at = self.plan_wake_up().await;
+            lock(&self.shared).next_check = Some(wake_at);
+            debug!(next_check = %wake_at, "Planned next check");
+            match self.wait_until(wake_at, &mut signals).await {
+                Wake::Due | Wake::SlidesChanged => {}
+                Wake::Changed => {
+                    info!("Database or configuration changed, re-planning");
+                    if let Err(e) = Config::load().and_then(|c| self.apply_config(&c)) {
+                        error!("Error reloading configuration: {}", e);
+                    }
+                }
+                Wake::Signal(DaemonSignal::Shutdown) => {
+                    info!("Received shutdown signal, stopping daemon");
+                    break;
+                }
+                Wake::Signal(DaemonSignal::Reload) => {
+                    info!("Received SIGHUP, reloading configuration");
+                    if let Err(e) = Config::load().and_then(|c| self.apply_config(&c)) {
+                        error!("Error reloading configuration: {}", e);
+                    }
+                }
+            }
         }
 
         // Cleanup on exit
+        self.stop_sessions(|_| true).await;
         self.cleanup()?;
-        println!("Daemon stopped.");
+        drop(pid_lock);
+        info!("Daemon stopped");
 
         Ok(())
     }
 
-    /// Check schedule and update active course if needed
-    async fn check_and_update(&self) -> Result<()> {
-        eprintln!("[DEBUG] Starting check_and_update...");
+    /// Mark the active course's interval as seen
+    ///
+    /// After a gap (suspend or downtime) the interval is split, so the study
+    /// time does not include the time nobody was around.
+    async fn heartbeat(&self) -> Result<()> {
+        let conn = connection_seaorm::get_connection().await?;
+        queries::activation_intervals::heartbeat(&conn, chrono::Utc::now(), HEARTBEAT_INTERVAL * 2)
+            .await
+    }
+
+    /// Handle slots that began or ended since the last check
+    ///
+    /// Runs the start/end hooks, opens or closes the notes of lectures,
+    /// records ended lectures and marks ended slots as attended if their
+    /// course was active.
+    async fn handle_slots(&mut self) -> Result<()> {
+        let now = chrono::Local::now().naive_local();
+        let Some(since) = self.last_slot_check.replace(now) else {
+            return Ok(());
+        };
+
+        // Close the notes of lectures that are over
+        self.stop_sessions(|session| session.ends_at <= now).await;
+
+        let notes = self.notes.clone().filter(LectureSession::is_enabled);
+        let conn = connection_seaorm::get_connection().await?;
+        let Some(semester) = queries::semester::get_current(&conn).await? else {
+            return Ok(());
+        };
+
+        let mut started = false;
+        for course in queries::course::list_unarchived_by_semester(&conn, semester.id).await? {
+            let occurrences =
+                ScheduleEngine::occurrences_for_course(&conn, course.id, since.date(), now.date())
+                    .await?;
+
+            for occurrence in occurrences {
+                let start = occurrence.date.and_time(occurrence.start_time);
+                let end = occurrence.date.and_time(occurrence.end_time);
+
+                // Only start slots that are still running
+                if start > since && start <= now && end > now {
+                    let context =
+                        HookContext::for_course(&conn, course.id, Some(&occurrence)).await?;
+
+                    if let Some(notes) = &notes
+                        && occurrence.is_type("lecture")
+                    {
+                        started |= self.start_session(notes, &context, &occurrence);
+                    }
+                    if let Some(hooks) = &self.hooks {
+                        hooks::spawn_hooks(hooks, HookEvent::SlotStart, context);
+                    }
+                }
+                if end > since && end <= now {
+                    // Also covers back-to-back slots of one course and manual holds,
+                    // during which the course is never switched away from
+                    if occurrence.is_type("lecture") {
+                        self.sync_course_lectures(&conn, course.id).await?;
+                    }
+
+                    // The daemon may have switched to the next course before this slot ended
+                    if self.auto_mark_attendance
+                        && let Some(record) =
+                            attendance::mark_ended_slot(&conn, &occurrence).await?
+                    {
+                        info!(
+                            event = "attendance",
+                            course_id = course.id,
+                            date = %record.date,
+                            start = %record.start_time,
+                            "Marked attendance"
+                        );
+                    }
+                    if let Some(hooks) = &self.hooks {
+                        let context =
+                            HookContext::for_course(&conn, course.id, Some(&occurrence)).await?;
+                        hooks::spawn_hooks(hooks, HookEvent::SlotEnd, context);
+                    }
+                }
+            }
+        }
+
+        // Point the lecture notes link at the note just created
+        if started {
+            self.refresh_course_context(&conn).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Open the note of a starting lecture, returning whether it was opened
+    fn start_session(
+        &mut self,
+        notes: &NotesConfig,
+        context: &HookContext,
+        occurrence: &Occurrence,
+    ) -> bool {
+        let general = self.general.as_ref();
+        let session = LectureSession::start(
+            notes,
+            general.and_then(|g| g.default_editor.as_deref()),
+            general.and_then(|g| g.default_pdf_viewer.as_deref()),
+            general.and_then(|g| g.student_name.as_deref()),
+            context,
+            occurrence,
+        );
+
+        match session {
+            Ok(session) => {
+                info!(
+                    event = "session_start",
+                    course = context.course.as_deref().unwrap_or_default(),
+                    lecture = context.lecture_number,
+                    "Opened lecture notes"
+                );
+                self.sessions.push(session);
+                true
+            }
+            Err(e) => {
+                error!("Error opening lecture notes: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Stop the lecture sessions for which `ended` returns true
+    async fn stop_sessions(&mut self, ended: impl Fn(&LectureSession) -> bool) {
+        let (ended, running): (Vec<_>, Vec<_>) = std::mem::take(&mut self.sessions)
+            .into_iter()
+            .partition(|session| ended(session));
+        self.sessions = running;
+
+        for session in ended {
+            info!(
+                event = "session_end",
+                course_id = session.course_id,
+                "Closing lecture notes"
+            );
+            session.stop().await;
+        }
+    }
+
+    /// Run the deadline hooks of deadlines that came within the lead time
+    ///
+    /// Each deadline's hooks run once; a changed due date counts as a new deadline.
+    async fn handle_deadlines(&mut self) -> Result<()> {
+        let Some(hooks) = self.hooks.as_ref().filter(|h| !h.on_deadline.is_empty()) else {
+            return Ok(());
+        };
+
+        let conn = connection_seaorm::get_connection().await?;
+        let now = chrono::Local::now().naive_local();
+        let lead = hooks::deadline_lead(hooks);
+        let deadlines = hooks::open_deadlines(&conn).await?;
+
+        // Forget deadlines that were met or removed
+        self.deadlines_run
+            .retain(|key| deadlines.iter().any(|d| &d.key == key));
+
+        for deadline in deadlines {
+            if deadline.due <= now
+                || deadline.hook_at(lead) > now
+                || self.deadlines_run.contains(&deadline.key)
+            {
+                continue;
+            }
+
+            let context = HookContext::for_deadline(&conn, &deadline).await?;
+            info!(
+                event = "deadline",
+                kind = deadline.kind,
+                title = %deadline.title,
+                due = %deadline.due,
+                "Deadline approaching"
+            );
+            hooks::spawn_hooks(hooks, HookEvent::Deadline, context);
+            self.deadlines_run.insert(deadline.key);
+        }
+
+        Ok(())
+    }
+
+    /// Notify about upcoming slots, cancellations and room changes
+    ///
+    /// Each notification is sent once; upcoming slots are announced once the
+    /// switch window opens.
+    async fn send_notifications(&mut self) -> Result<()> {
+        let Some(notifier) = &self.notifier else {
+            return Ok(());
+        };
+
+        let conn = connection_seaorm::get_connection().await?;
+        let now = chrono::Local::now().naive_local();
+        let pending = notifier::pending_notifications(&conn, now, self.switch_window).await?;
+
+        // Forget notifications from previous days
+        let yesterday = now.date() - chrono::Days::new(1);
+        self.sent_notifications.retain(|_, day| *day >= yesterday);
+
+        for notification in pending {
+            if self.sent_notifications.contains_key(&notification.key) {
+                continue;
+            }
+            match notifier::send(notifier, &notification).await {
+                Ok(()) => {
+                    info!(event = "notify", key = %notification.key, "Notified: {}", notification.summary);
+                    self.sent_notifications.insert(notification.key, now.date());
+                }
+                Err(e) => warn!("Error sending notification: {}", e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Determine when the next check is due
+    ///
+    /// This is the next schedule boundary (slot start minus switch window,
+    /// slot end or midnight), the expiry of a manual course selection or the
+    /// next deadline hook, and at most one heartbeat interval while a course
+    /// is active.
+    /// Falls back to the check interval on errors.
+    async fn plan_wake_up(&self) -> chrono::NaiveDateTime {
+        let now = chrono::Local::now().naive_local();
+        let fallback = now
+            + chrono::Duration::from_std(self.check_interval)
+                .unwrap_or_else(|_| chrono::Duration::minutes(5));
+
+        let boundary = async {
+            let conn = connection_seaorm::get_connection().await?;
+            let boundary = ScheduleEngine::next_boundary(&conn, now, self.switch_window).await?;
+
+            // Hand back to the schedule on time when a manual selection expires
+            let active = queries::active::get(&conn).await?;
+            let boundary = match manual_hold_until(&conn, &active, self.switch_window).await? {
+                Some(until) if until > now => boundary.min(until),
+                _ => boundary,
+            };
+
+            // Run deadline hooks on time
+            let boundary = match self.hooks.as_ref().filter(|h| !h.on_deadline.is_empty()) {
+                Some(hooks) => {
+                    let lead = hooks::deadline_lead(hooks);
+                    hooks::open_deadlines(&conn)
+                        .await?
+                        .iter()
+                        .map(|deadline| deadline.hook_at(lead))
+                        .filter(|at| *at > now)
+                        .fold(boundary, chrono::NaiveDateTime::min)
+                }
+                None => boundary,
+            };
+            Ok::<_, MmsError>(match active.course_id {
+                Some(_) => boundary.min(now + HEARTBEAT_INTERVAL),
+                None => boundary,
+            })
+        }
+        .await;
+
+        let wake_at = match boundary {
+            Ok(boundary) => boundary,
+            Err(e) => {
+                error!("Error planning next check: {}", e);
+                fallback
+            }
+        };
+
+        // Resume on time after a pause
+        match lock(&self.shared).paused_until {
+            Some(until) => wake_at.min(until),
+            None => wake_at,
+        }
+    }
+
+    /// Whether automatic switching is paused, ending expired pauses
+    fn is_paused(&self) -> bool {
+        let mut shared = lock(&self.shared);
+        let Some(until) = shared.paused_until else {
+            return false;
+        };
+        if chrono::Local::now().naive_local() < until {
+            return true;
+        }
+
+        shared.paused_until = None;
+        info!(event = "resume", "Automatic switching resumed");
+        false
+    }
+
+    /// Wait until `wake_at`, a signal, or a change of the database, config or slides
+    ///
+    /// Sleeps against the wall clock, so a boundary that passed during a
+    /// suspend is handled right after resuming. Database writes only wake
+    /// the daemon when they change the plan, which keeps its own writes
+    /// (heartbeats, lectures, slides) from waking it again.
+    async fn wait_until(&mut self, wake_at: chrono::NaiveDateTime, signals: &mut Signals) -> Wake {
+        // Held open while waiting, so reading the plan does not recreate the WAL
+        let conn = connection_seaorm::get_connection().await.ok();
+        let plan = match &conn {
+            Some(conn) => Self::plan_fingerprint(conn).await.ok(),
+            None => None,
+        };
+
+        loop {
+            if chrono::Local::now().naive_local() >= wake_at {
+                return Wake::Due;
+            }
+
+            let changes = tokio::select! {
+                _ = watcher::sleep_until(wake_at) => continue,
+                signal = signals.recv() => return Wake::Signal(signal),
+                Some(wake) = recv_control(&mut self.control) => return wake,
+                changes = Self::watch(&mut self.watcher) => changes,
+            };
+
+            if changes.config {
+                return Wake::Changed;
+            }
+            if changes.database
+                && let Some(conn) = &conn
+                && Self::plan_fingerprint(conn).await.ok() != plan
+            {
+                return Wake::Changed;
+            }
+            if changes.slides {
+                return Wake::SlidesChanged;
+            }
+        }
+    }
+
+    /// Wait for changes of watched files, never resolving without a watcher
+    async fn watch(watcher: &mut Option<FileWatcher>) -> watcher::Changes {
+        match watcher {
+            Some(watcher) => watcher.changed().await,
+            None => std::future::pending().await,
+        }
+    }
 
-        let conn = connection_seaorm::get_connection().await.map_err(|e| {
-            eprintln!("[DEBUG] Failed to get connection: {}", e);
-            MmsError::Database(e)
-        })?;
+    /// Hash of the rows the plan is computed from
+    async fn plan_fingerprint(conn: &DatabaseConnection) -> Result<u64> {
+        use sea_orm::EntityTrait;
+        use std::hash::{Hash, Hasher};
 
-        eprintln!("[DEBUG] Got database connection");
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        format!("{:?}", semesters::Entity::find().all(conn).await?).hash(&mut hasher);
+        format!("{:?}", courses::Entity::find().all(conn).await?).hash(&mut hasher);
+        format!("{:?}", course_schedules::Entity::find().all(conn).await?).hash(&mut hasher);
+        format!("{:?}", course_events::Entity::find().all(conn).await?).hash(&mut hasher);
+        format!("{:?}", holidays::Entity::find().all(conn).await?).hash(&mut hasher);
+        format!("{:?}", holiday_exceptions::Entity::find().all(conn).await?).hash(&mut hasher);
+        format!("{:?}", active_course::Entity::find().all(conn).await?).hash(&mut hasher);
+        format!("{:?}", exercises::Entity::find().all(conn).await?).hash(&mut hasher);
+        format!("{:?}", todos::Entity::find().all(conn).await?).hash(&mut hasher);
+        Ok(hasher.finish())
+    }
+
+    /// Check schedule and update active course if needed
+    async fn check_and_update(&self) -> Result<()> {
+        let conn = connection_seaorm::get_connection().await?;
 
         // Get current active course
-        let active = queries::active::get(&conn).await.map_err(|e| {
-            eprintln!("[DEBUG] Failed to get active state: {}", e);
-            e
-        })?;
+        let active = queries::active::get(&conn).await?;
         let current_course_id = active.course_id;
 
-        eprintln!("[DEBUG] Current course_id: {:?}", current_course_id);
+        // Keep a manually selected course until it expires
+        let now = chrono::Local::now().naive_local();
+        if let Some(until) = manual_hold_until(&conn, &active, self.switch_window).await? {
+            if now < until {
+                debug!(until = %until, "Keeping manually selected course");
+                return Ok(());
+            }
+            queries::active::clear_manual(&conn).await?;
+            info!(event = "manual_end", "Manual course selection ended");
+        }
 
         // Determine what course should be active now
-        let should_be_active = ScheduleEngine::determine_active_course_now(&conn)
-            .await
-            .map_err(|e| {
-                eprintln!("[DEBUG] Failed to determine active course: {}", e);
-                e
-            })?;
-
-        // Debug logging
-        eprintln!(
-            "[DEBUG] Current: {:?}, Should be: {:?}",
-            current_course_id, should_be_active
+        let should_be_active =
+            ScheduleEngine::determine_active_course_with_window(&conn, now, self.switch_window)
+                .await?;
+
+        debug!(
+            current = ?current_course_id,
+            scheduled = ?should_be_active,
+            "Checked schedule"
         );
 
         // Check if we need to switch
         if current_course_id != should_be_active {
-            eprintln!("[DEBUG] Switching course...");
-            self.switch_course(&conn, current_course_id, should_be_active)
+            let reason = match should_be_active {
+                Some(id)
+                    if ScheduleEngine::is_event_driven(&conn, id, now).await?
+                        || ScheduleEngine::is_event_driven(&conn, id, now + self.switch_window)
+                            .await? =>
+                {
+                    SwitchReason::Override
+                }
+                _ => SwitchReason::Schedule,
+            };
+            self.switch_course(&conn, current_course_id, should_be_active, reason)
                 .await?;
         }
 
         Ok(())
     }
 
+    /// Register new, changed, renamed and removed slides of the current semester
+    ///
+    /// Also refreshes the slides folders watched while waiting.
+    async fn scan_slides(&mut self) -> Result<()> {
+        let conn = connection_seaorm::get_connection().await?;
+        let dirs = slides::semester_slides_dirs(&conn).await?;
+        if let Some(watcher) = &mut self.watcher {
+            watcher.set_slide_dirs(dirs);
+        }
+
+        let scans = slides::scan_semester_slides(&conn, false).await?;
+        for (course, scan) in &scans {
+            info!(
+                event = "slides",
+                course = %course.short_name,
+                added = scan.added.len(),
+                updated = scan.updated.len(),
+                renamed = scan.renamed.len(),
+                removed = scan.removed.len(),
+                "Slides changed"
+            );
+        }
+
+        // Point the slides link at the newest deck
+        if !scans.is_empty() {
+            self.refresh_course_context(&conn).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Record elapsed lectures for all courses of the current semester
+    async fn sync_all_lectures(&self) -> Result<()> {
+        let conn = connection_seaorm::get_connection().await?;
+        let created =
+            lecture::sync_semester_lectures(&conn, chrono::Local::now().naive_local()).await?;
+
+        if !created.is_empty() {
+            info!(count = created.len(), "Recorded elapsed lectures");
+        }
+
+        Ok(())
+    }
+
+    /// Record elapsed lectures for a single course
+    async fn sync_course_lectures(&self, conn: &DatabaseConnection, course_id: i64) -> Result<()> {
+        let created =
+            lecture::sync_lectures(conn, course_id, chrono::Local::now().naive_local()).await?;
+
+        for lecture in &created {
+            info!(
+                event = "lecture",
+                course_id,
+                number = lecture.lecture_number,
+                date = %lecture.date,
+                "Recorded lecture"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Point the symlinks at a course
+    async fn link_course(
+        &self,
+        conn: &DatabaseConnection,
+        semester_dir: &str,
+        course: &courses::Model,
+    ) -> Result<()> {
+        if let Some(symlinks) = &self.symlinks {
+            symlinks.set_course(Path::new(semester_dir), Path::new(&course.directory_path))?;
+            symlinks.set_course_context(conn, course).await?;
+        }
+        Ok(())
+    }
+
+    /// Re-point the context links of the active course
+    async fn refresh_course_context(&self, conn: &DatabaseConnection) -> Result<()> {
+        let Some(symlinks) = &self.symlinks else {
+            return Ok(());
+        };
+        let Some(course_id) = queries::active::get(conn).await?.course_id else {
+            return Ok(());
+        };
+
+        let course = queries::course::get_by_id(conn, course_id).await?;
+        symlinks.set_course_context(conn, &course).await
+    }
+
     /// Switch to a different active course
     async fn switch_course(
         &self,
         conn: &DatabaseConnection,
         from: Option<i64>,
         to: Option<i64>,
+        reason: SwitchReason,
     ) -> Result<()> {
         match (from, to) {
             (Some(old_id), Some(new_id)) => {
@@ -142,12 +779,15 @@ impl Daemon {
                 queries::active::set_active_course(conn, new_id, new_course.semester_id).await?;
 
                 // Update symlinks
-                symlink::update_semester_symlink(&semester.directory_path)?;
-                symlink::update_course_symlink(&semester.directory_path, &new_course.short_name)?;
+                self.link_course(conn, &semester.directory_path, &new_course)
+                    .await?;
 
-                println!(
-                    "[{}] Switched course: {} -> {}",
-                    chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+                info!(
+                    event = "switch",
+                    reason = %reason,
+                    from = %old_course.short_name,
+                    to = %new_course.short_name,
+                    "Switched course: {} -> {}",
                     old_course.name,
                     new_course.name
                 );
@@ -158,12 +798,16 @@ impl Daemon {
                 // Clear active course
                 queries::active::clear_active_course(conn).await?;
 
-                // Remove course symlink but keep semester symlink
-                let _ = symlink::remove_course_symlink();
+                // Remove course symlinks but keep semester symlink
+                if let Some(symlinks) = &self.symlinks {
+                    let _ = symlinks.remove_course();
+                }
 
-                println!(
-                    "[{}] No active course (was: {})",
-                    chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+                info!(
+                    event = "switch",
+                    reason = %reason,
+                    from = %old_course.short_name,
+                    "No active course (was: {})",
                     old_course.name
                 );
             }
@@ -175,59 +819,98 @@ impl Daemon {
                 queries::active::set_active_course(conn, new_id, new_course.semester_id).await?;
 
                 // Update symlinks
-                symlink::update_semester_symlink(&semester.directory_path)?;
-                symlink::update_course_symlink(&semester.directory_path, &new_course.short_name)?;
+                self.link_course(conn, &semester.directory_path, &new_course)
+                    .await?;
 
-                println!(
-                    "[{}] Course started: {}",
-                    chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+                info!(
+                    event = "switch",
+                    reason = %reason,
+                    to = %new_course.short_name,
+                    "Course started: {}",
                     new_course.name
                 );
             }
             (None, None) => {
                 // No change, nothing to do
+                return Ok(());
             }
         }
 
+        history::record_switch(conn, from, to, reason, chrono::Utc::now()).await?;
+
+        if let Some(hooks) = &self.hooks {
+            let mut context = match to {
+                Some(new_id) => {
+                    let occurrence = Self::current_occurrence(conn, new_id).await?;
+                    HookContext::for_course(conn, new_id, occurrence.as_ref()).await?
+                }
+                None => HookContext::default(),
+            };
+            if let Some(old_id) = from {
+                context.previous_course =
+                    Some(queries::course::get_by_id(conn, old_id).await?.short_name);
+            }
+            hooks::spawn_hooks(hooks, HookEvent::Switch, context);
+        }
+
         Ok(())
     }
 
+    /// The running or next occurrence of a course today
+    async fn current_occurrence(
+        conn: &DatabaseConnection,
+        course_id: i64,
+    ) -> Result<Option<Occurrence>> {
+        let now = chrono::Local::now().naive_local();
+        let occurrences =
+            ScheduleEngine::occurrences_for_course(conn, course_id, now.date(), now.date()).await?;
+        Ok(occurrences
+            .into_iter()
+            .find(|o| o.date.and_time(o.end_time) > now))
+    }
+
     /// Check if daemon is already running
+    ///
+    /// A daemon is running if the PID file is locked and its PID belongs
+    /// to an mms daemon process. Stale PID files are ignored.
     pub fn is_running(&self) -> Result<bool> {
+        Ok(self.running_pid()?.is_some())
+    }
+
+    /// PID of the running daemon, if any
+    fn running_pid(&self) -> Result<Option<u32>> {
         if !self.pid_file.exists() {
-            return Ok(false);
+            return Ok(None);
         }
 
-        let pid_str = fs::read_to_string(&self.pid_file)?;
-        let pid: u32 = pid_str
-            .trim()
-            .parse()
-            .map_err(|_| MmsError::Other("Invalid PID file".to_string()))?;
+        // If we can lock the file, no daemon holds it (stale PID file)
+        let file = File::open(&self.pid_file)?;
+        match file.try_lock() {
+            Ok(()) => return Ok(None),
+            Err(fs::TryLockError::WouldBlock) => {}
+            Err(fs::TryLockError::Error(e)) => return Err(e.into()),
+        }
 
-        // Check if process with this PID exists
-        Ok(Self::process_exists(pid))
+        let pid = Self::read_pid(&self.pid_file)?;
+        if !Self::is_mms_daemon(pid) {
+            return Ok(None);
+        }
+
+        Ok(Some(pid))
     }
 
     /// Stop the daemon
     pub fn stop(&self) -> Result<()> {
-        if !self.is_running()? {
-            return Err(MmsError::Other("Daemon is not running".to_string()));
-        }
-
-        let pid_str = fs::read_to_string(&self.pid_file)?;
-        let pid: u32 = pid_str
-            .trim()
-            .parse()
-            .map_err(|_| MmsError::Other("Invalid PID file".to_string()))?;
+        let pid = self
+            .running_pid()?
+            .ok_or_else(|| MmsError::Other("Daemon is not running".to_string()))?;
 
         // Send SIGTERM to the process
-        Self::kill_process(pid)?;
+        Self::terminate_process(pid)?;
 
         // Wait for process to exit
         for _ in 0..50 {
             if !Self::process_exists(pid) {
-                // Remove PID file
-                fs::remove_file(&self.pid_file)?;
                 return Ok(());
             }
             std::thread::sleep(Duration::from_millis(100));
@@ -238,30 +921,53 @@ impl Daemon {
 
     /// Get daemon status
     pub fn status(&self) -> Result<DaemonStatus> {
-        if !self.is_running()? {
-            return Ok(DaemonStatus::Stopped);
+        match self.running_pid()? {
+            Some(pid) => Ok(DaemonStatus::Running { pid }),
+            None => Ok(DaemonStatus::Stopped),
         }
-
-        let pid_str = fs::read_to_string(&self.pid_file)?;
-        let pid: u32 = pid_str
-            .trim()
-            .parse()
-            .map_err(|_| MmsError::Other("Invalid PID file".to_string()))?;
-
-        Ok(DaemonStatus::Running { pid })
     }
 
-    /// Write PID file
-    fn write_pid_file(&self) -> Result<()> {
-        let pid = std::process::id();
-
+    /// Create the PID file, lock it exclusively and write our PID
+    ///
+    /// The returned file must be kept open for as long as the daemon runs.
+    fn acquire_pid_file(&self) -> Result<File> {
         // Create parent directory if it doesn't exist
         if let Some(parent) = self.pid_file.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        fs::write(&self.pid_file, pid.to_string())?;
-        Ok(())
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(&self.pid_file)?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(fs::TryLockError::WouldBlock) => {
+                return Err(MmsError::Other(
+                    "Daemon is already running. Use 'mms service stop' to stop it first."
+                        .to_string(),
+                ));
+            }
+            Err(fs::TryLockError::Error(e)) => return Err(e.into()),
+        }
+
+        file.set_len(0)?;
+        write!(file, "{}", std::process::id())?;
+        file.flush()?;
+
+        Ok(file)
+    }
+
+    /// Read the PID stored in a PID file
+    fn read_pid(pid_file: &PathBuf) -> Result<u32> {
+        let pid_str = fs::read_to_string(pid_file)?;
+        pid_str
+            .trim()
+            .parse()
+            .map_err(|_| MmsError::Other("Invalid PID file".to_string()))
     }
 
     /// Cleanup on exit
@@ -269,6 +975,12 @@ impl Daemon {
         if self.pid_file.exists() {
             fs::remove_file(&self.pid_file)?;
         }
+        if self.control.is_some() {
+            let socket = paths::control_socket_path()?;
+            if socket.exists() {
+                fs::remove_file(socket)?;
+            }
+        }
         Ok(())
     }
 
@@ -282,26 +994,54 @@ impl Daemon {
     /// Check if a process with given PID exists
     #[cfg(unix)]
     fn process_exists(pid: u32) -> bool {
-        let output = std::process::Command::new("kill")
-            .arg("-0")
-            .arg(pid.to_string())
-            .output();
+        use nix::errno::Errno;
+        use nix::sys::signal::kill;
+        use nix::unistd::Pid;
 
-        match output {
-            Ok(output) => output.status.success(),
+        // Signal 0 only checks for existence; EPERM means it exists but is not ours
+        match kill(Pid::from_raw(pid as i32), None) {
+            Ok(()) => true,
+            Err(Errno::EPERM) => true,
             Err(_) => false,
         }
     }
 
-    /// Kill a process by PID
+    /// Send SIGTERM to a process
     #[cfg(unix)]
-    fn kill_process(pid: u32) -> Result<()> {
-        std::process::Command::new("kill")
-            .arg("-TERM")
-            .arg(pid.to_string())
-            .output()
-            .map_err(|e| MmsError::Other(format!("Failed to send signal: {}", e)))?;
-        Ok(())
+    fn terminate_process(pid: u32) -> Result<()> {
+        use nix::sys::signal::{Signal, kill};
+        use nix::unistd::Pid;
+
+        kill(Pid::from_raw(pid as i32), Signal::SIGTERM)
+            .map_err(|e| MmsError::Other(format!("Failed to send signal: {}", e)))
+    }
+
+    /// Check that a PID belongs to an `mms service run` process
+    #[cfg(target_os = "linux")]
+    fn is_mms_daemon(pid: u32) -> bool {
+        let Ok(cmdline) = fs::read(format!("/proc/{}/cmdline", pid)) else {
+            return false;
+        };
+
+        let args: Vec<String> = cmdline
+            .split(|b| *b == 0)
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect();
+
+        let is_mms = args
+            .first()
+            .and_then(|arg| std::path::Path::new(arg).file_name())
+            .is_some_and(|name| name.to_string_lossy().starts_with("mms"));
+
+        is_mms && args.windows(2).any(|w| w[0] == "service" && w[1] == "run")
+    }
+
+    /// Check that a PID belongs to a live process
+    ///
+    /// Without procfs the PID file lock is the only ownership proof.
+    #[cfg(all(unix, not(target_os = "linux")))]
+    fn is_mms_daemon(pid: u32) -> bool {
+        Self::process_exists(pid)
     }
 
     #[cfg(not(unix))]
@@ -311,9 +1051,269 @@ impl Daemon {
     }
 
     #[cfg(not(unix))]
-    fn kill_process(_pid: u32) -> Result<()> {
+    fn terminate_process(_pid: u32) -> Result<()> {
         Err(MmsError::Other("Windows is not yet supported".to_string()))
     }
+
+    #[cfg(not(unix))]
+    fn is_mms_daemon(_pid: u32) -> bool {
+        false
+    }
+}
+
+// ============================================================================
+// Control
+// ============================================================================
+
+/// Answers control requests while the daemon loop is busy
+///
+/// Status is read from the database and the shared state; requests that
+/// change the plan wake the daemon loop, which re-checks the schedule.
+#[cfg(unix)]
+struct ControlTask {
+    shared: Arc<Mutex<SharedState>>,
+    wake: mpsc::UnboundedSender<Wake>,
+}
+
+#[cfg(unix)]
+impl ControlTask {
+    /// Answer requests until the control socket closes
+    async fn run(self, mut requests: mpsc::Receiver<ControlMessage>) {
+        while let Some((request, reply)) = requests.recv().await {
+            let response = self.handle(request).await;
+            let _ = reply.send(response);
+        }
+    }
+
+    /// Handle a request from the control socket
+    ///
+    /// The reported state is the one before the daemon loop re-checks the
+    /// schedule.
+    async fn handle(&self, request: ControlRequest) -> ControlResponse {
+        let now = chrono::Local::now().naive_local();
+        let wake = match request {
+            ControlRequest::Status => None,
+            ControlRequest::Recheck => Some(Wake::Due),
+            ControlRequest::Pause { minutes } => {
+                let until = match minutes {
+                    Some(minutes) => {
+                        let until = i64::try_from(minutes)
+                            .ok()
+                            .and_then(chrono::TimeDelta::try_minutes)
+                            .and_then(|pause| now.checked_add_signed(pause));
+                        match until {
+                            Some(until) => until,
+                            None => {
+                                return ControlResponse::error(format!(
+                                    "Cannot pause for {} minutes",
+                                    minutes
+                                ));
+                            }
+                        }
+                    }
+                    None => chrono::NaiveDateTime::MAX,
+                };
+                lock(&self.shared).paused_until = Some(until);
+                info!(event = "pause", until = %until, "Automatic switching paused");
+                Some(Wake::Due)
+            }
+            ControlRequest::Resume => {
+                if lock(&self.shared).paused_until.take().is_some() {
+                    info!(event = "resume", "Automatic switching resumed");
+                }
+                Some(Wake::Due)
+            }
+            ControlRequest::Reload => {
+                // Report an unusable config here, the daemon loop applies it
+                let config = match Config::load() {
+                    Ok(config) => config,
+                    Err(e) => return ControlResponse::error(e.to_string()),
+                };
+                if config.schedule.is_none() {
+                    return ControlResponse::error(MmsError::ScheduleNotSet.to_string());
+                }
+                Some(Wake::Changed)
+            }
+        };
+
+        let response = match self.state().await {
+            Ok(state) => ControlResponse::ok(Some(state)),
+            Err(e) => ControlResponse::error(e.to_string()),
+        };
+        if let Some(wake) = wake
+            && self.wake.send(wake).is_err()
+        {
+            return ControlResponse::error("daemon is shutting down");
+        }
+        response
+    }
+
+    /// Snapshot of the daemon's state for the control socket
+    async fn state(&self) -> Result<DaemonState> {
+        let conn = connection_seaorm::get_connection().await?;
+        let now = chrono::Local::now().naive_local();
+        let (paused_until, next_check, switch_window) = {
+            let shared = lock(&self.shared);
+            (shared.paused_until, shared.next_check, shared.switch_window)
+        };
+
+        let active_model = queries::active::get(&conn).await?;
+        let active = active_model.course_id;
+        let active_course = match active {
+            Some(id) => Some(queries::course::get_by_id(&conn, id).await?.short_name),
+            None => None,
+        };
+
+        // A manual selection postpones switching until it ends
+        let manual_until = manual_hold_until(&conn, &active_model, switch_window)
+            .await?
+            .filter(|until| now < *until);
+        let planned = match manual_until {
+            Some(chrono::NaiveDateTime::MAX) => None,
+            Some(until) => {
+                let course = ScheduleEngine::determine_active_course_with_window(
+                    &conn,
+                    until,
+                    switch_window,
+                )
+                .await?;
+                if course != active {
+                    Some((until, course))
+                } else {
+                    ScheduleEngine::next_switch(
+                        &conn,
+                        until,
+                        switch_window,
+                        active,
+                        chrono::Duration::days(7),
+                    )
+                    .await?
+                }
+            }
+            None => {
+                ScheduleEngine::next_switch(
+                    &conn,
+                    now,
+                    switch_window,
+                    active,
+                    chrono::Duration::days(7),
+                )
+                .await?
+            }
+        };
+
+        let next_switch = match planned {
+            Some((at, course_id)) => Some(PlannedSwitch {
+                at,
+                course: match course_id {
+                    Some(id) => Some(queries::course::get_by_id(&conn, id).await?.short_name),
+                    None => None,
+                },
+            }),
+            None => None,
+        };
+
+        let paused = paused_until.is_some_and(|until| now < until);
+        Ok(DaemonState {
+            active_course,
+            next_check,
+            next_switch,
+            manual: manual_until.is_some(),
+            manual_until: manual_until.filter(|until| *until != chrono::NaiveDateTime::MAX),
+            paused,
+            paused_until: paused_until
+                .filter(|until| paused && *until != chrono::NaiveDateTime::MAX),
+        })
+    }
+}
+
+/// Until when a manual course selection is kept
+///
+/// `None` if the active course follows the schedule. A manual selection
+/// ends at its expiry or when the next scheduled slot starts, whichever
+/// comes first (`NaiveDateTime::MAX` if neither is known).
+async fn manual_hold_until(
+    conn: &DatabaseConnection,
+    active: &active_course::Model,
+    switch_window: chrono::Duration,
+) -> Result<Option<chrono::NaiveDateTime>> {
+    if !active.is_manual {
+        return Ok(None);
+    }
+
+    let local = |at: chrono::DateTime<chrono::Utc>| at.with_timezone(&chrono::Local).naive_local();
+    let selected_at = active
+        .activated_at
+        .map(local)
+        .unwrap_or_else(|| chrono::Local::now().naive_local());
+    let next_slot =
+        ScheduleEngine::next_slot_start(conn, selected_at, switch_window, MANUAL_HORIZON).await?;
+
+    Ok(Some(
+        [next_slot, active.manual_until.map(local)]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap_or(chrono::NaiveDateTime::MAX),
+    ))
+}
+
+// ============================================================================
+// Signals
+// ============================================================================
+
+/// Signals the daemon reacts to
+enum DaemonSignal {
+    /// SIGTERM or SIGINT - stop the daemon
+    Shutdown,
+    /// SIGHUP - reload the configuration
+    Reload,
+}
+
+/// Native signal listeners of the daemon loop
+#[cfg(unix)]
+struct Signals {
+    terminate: tokio::signal::unix::Signal,
+    interrupt: tokio::signal::unix::Signal,
+    hangup: tokio::signal::unix::Signal,
+}
+
+#[cfg(unix)]
+impl Signals {
+    fn new() -> Result<Self> {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        Ok(Self {
+            terminate: signal(SignalKind::terminate())?,
+            interrupt: signal(SignalKind::interrupt())?,
+            hangup: signal(SignalKind::hangup())?,
+        })
+    }
+
+    /// Wait for the next signal
+    async fn recv(&mut self) -> DaemonSignal {
+        tokio::select! {
+            _ = self.terminate.recv() => DaemonSignal::Shutdown,
+            _ = self.interrupt.recv() => DaemonSignal::Shutdown,
+            _ = self.hangup.recv() => DaemonSignal::Reload,
+        }
+    }
+}
+
+#[cfg(not(unix))]
+struct Signals;
+
+#[cfg(not(unix))]
+impl Signals {
+    fn new() -> Result<Self> {
+        Ok(Self)
+    }
+
+    /// Wait for the next signal
+    async fn recv(&mut self) -> DaemonSignal {
+        let _ = tokio::signal::ctrl_c().await;
+        DaemonSignal::Shutdown
+    }
 }
 
 #[derive(Debug)]
@@ -321,3 +1321,185 @@ pub enum DaemonStatus {
     Running { pid: u32 },
     Stopped,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::semester_with_course;
+    use tempfile::TempDir;
+
+    fn test_daemon(temp_dir: &TempDir) -> Daemon {
+        Daemon {
+            check_interval: Duration::from_secs(60),
+            switch_window: chrono::Duration::minutes(10),
+            pid_file: temp_dir.path().join("daemon.pid"),
+            auto_mark_attendance: false,
+            notifier: None,
+            sent_notifications: HashMap::new(),
+            hooks: None,
+            deadlines_run: HashSet::new(),
+            notes: None,
+            general: None,
+            sessions: Vec::new(),
+            watcher: None,
+            last_slot_check: None,
+            shared: Arc::default(),
+            control: None,
+            logging: LoggingConfig::default(),
+            symlinks: None,
+        }
+    }
+
+    #[test]
+    fn test_pid_file_is_locked_exclusively() {
+        let temp_dir = TempDir::new().unwrap();
+        let daemon = test_daemon(&temp_dir);
+
+        let lock = daemon.acquire_pid_file().unwrap();
+        assert_eq!(
+            Daemon::read_pid(&daemon.pid_file).unwrap(),
+            std::process::id()
+        );
+
+        // A second instance cannot take over the PID file
+        assert!(daemon.acquire_pid_file().is_err());
+
+        drop(lock);
+        assert!(daemon.acquire_pid_file().is_ok());
+    }
+
+    #[test]
+    fn test_stale_pid_file_is_not_running() {
+        let temp_dir = TempDir::new().unwrap();
+        let daemon = test_daemon(&temp_dir);
+
+        // PID of a live process, but the file is not locked
+        fs::write(&daemon.pid_file, std::process::id().to_string()).unwrap();
+        assert!(!daemon.is_running().unwrap());
+        assert!(matches!(daemon.status().unwrap(), DaemonStatus::Stopped));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_foreign_process_is_not_a_daemon() {
+        let temp_dir = TempDir::new().unwrap();
+        let daemon = test_daemon(&temp_dir);
+
+        // Locked by us, but the test binary is not `mms service run`
+        let _lock = daemon.acquire_pid_file().unwrap();
+        assert!(!Daemon::is_mms_daemon(std::process::id()));
+        assert!(!daemon.is_running().unwrap());
+    }
+
+    /// Local wall clock time as UTC
+    fn utc(date: &str, time: &str) -> chrono::DateTime<chrono::Utc> {
+        use chrono::TimeZone;
+        chrono::Local
+            .from_local_datetime(&at(date, time))
+            .unwrap()
+            .with_timezone(&chrono::Utc)
+    }
+
+    fn at(date: &str, time: &str) -> chrono::NaiveDateTime {
+        chrono::NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_manual_hold_until() {
+        use sea_orm::{ActiveModelTrait, Set};
+
+        let temp_dir = TempDir::new().unwrap();
+        let (db, course) = semester_with_course(temp_dir.path(), "algo", "Algorithms").await;
+        // Mondays 10:00-12:00
+        course_schedules::ActiveModel {
+            course_id: Set(course.id),
+            schedule_type: Set("Lecture".to_string()),
+            day_of_week: Set(0),
+            start_time: Set("10:00".to_string()),
+            end_time: Set("12:00".to_string()),
+            start_date: Set("2024-10-01".to_string()),
+            end_date: Set("2025-02-28".to_string()),
+            priority: Set(0),
+            created_at: Set(chrono::Utc::now()),
+            updated_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let window = chrono::Duration::minutes(10);
+        // Selected on Tuesday morning
+        let selected = |manual_until| active_course::Model {
+            id: 1,
+            course_id: Some(course.id),
+            semester_id: Some(course.semester_id),
+            lecture_id: None,
+            activated_at: Some(utc("2024-10-08", "09:00")),
+            is_manual: true,
+            manual_until,
+            updated_at: chrono::Utc::now(),
+        };
+
+        let scheduled = active_course::Model {
+            is_manual: false,
+            ..selected(None)
+        };
+        assert_eq!(
+            manual_hold_until(&db, &scheduled, window).await.unwrap(),
+            None
+        );
+
+        // Kept until the next Monday slot, or an earlier expiry
+        assert_eq!(
+            manual_hold_until(&db, &selected(None), window)
+                .await
+                .unwrap(),
+            Some(at("2024-10-14", "09:50"))
+        );
+        let expiring = selected(Some(utc("2024-10-08", "18:00")));
+        assert_eq!(
+            manual_hold_until(&db, &expiring, window).await.unwrap(),
+            Some(at("2024-10-08", "18:00"))
+        );
+        let expiring_late = selected(Some(utc("2024-10-20", "18:00")));
+        assert_eq!(
+            manual_hold_until(&db, &expiring_late, window)
+                .await
+                .unwrap(),
+            Some(at("2024-10-14", "09:50"))
+        );
+
+        // No slot within a week after the lecture period
+        let after_lectures = active_course::Model {
+            activated_at: Some(utc("2025-03-03", "09:00")),
+            ..selected(None)
+        };
+        assert_eq!(
+            manual_hold_until(&db, &after_lectures, window)
+                .await
+                .unwrap(),
+            Some(chrono::NaiveDateTime::MAX)
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_pause_rejects_overflowing_minutes() {
+        let (wake, mut woken) = mpsc::unbounded_channel();
+        let task = ControlTask {
+            shared: Arc::default(),
+            wake,
+        };
+
+        let response = task
+            .handle(ControlRequest::Pause {
+                minutes: Some(u64::MAX),
+            })
+            .await;
+        assert!(!response.ok);
+        assert!(lock(&task.shared).paused_until.is_none());
+        assert!(woken.try_recv().is_err());
+    }
+}
@@ -0,0 +1,509 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use sea_orm::DatabaseConnection;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use tracing::{info, warn};
+
+use crate::config::settings::HooksConfig;
+use crate::db::queries;
+use crate::error::Result;
+use crate::semester::SemesterInfo;
+use crate::service::scheduler::Occurrence;
+
+// ==================================================================================
+// Hook Events
+// ==================================================================================
+
+/// Moments at which user hooks run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    /// The active course changed
+    Switch,
+    /// A lecture, tutorial, ... begins
+    SlotStart,
+    /// A lecture, tutorial, ... ends
+    SlotEnd,
+    /// An open exercise or todo is due soon
+    Deadline,
+}
+
+impl HookEvent {
+    /// Name of the hook as used in `[hooks]` and `MMS_HOOK`
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::Switch => "on_switch",
+            HookEvent::SlotStart => "on_slot_start",
+            HookEvent::SlotEnd => "on_slot_end",
+            HookEvent::Deadline => "on_deadline",
+        }
+    }
+
+    /// Commands configured for this event
+    fn commands<'a>(&self, config: &'a HooksConfig) -> &'a [String] {
+        match self {
+            HookEvent::Switch => &config.on_switch,
+            HookEvent::SlotStart => &config.on_slot_start,
+            HookEvent::SlotEnd => &config.on_slot_end,
+            HookEvent::Deadline => &config.on_deadline,
+        }
+    }
+}
+
+// ==================================================================================
+// Hook Context
+// ==================================================================================
+
+/// Environment passed to hook commands
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HookContext {
+    pub course: Option<String>,
+    pub course_name: Option<String>,
+    pub course_dir: Option<String>,
+    pub semester: Option<String>,
+    pub previous_course: Option<String>,
+    pub slot_type: Option<String>,
+    pub slot_start: Option<String>,
+    pub slot_end: Option<String>,
+    pub room: Option<String>,
+    pub building: Option<String>,
+    pub lecture_number: Option<i64>,
+    pub deadline_kind: Option<String>,
+    pub deadline_title: Option<String>,
+    pub deadline_due: Option<String>,
+}
+
+impl HookContext {
+    /// Build the context for a course, optionally for one of its occurrences
+    ///
+    /// The lecture number is taken from the recorded lecture of the occurrence,
+    /// or the next free number if the lecture has not been recorded yet.
+    pub async fn for_course(
+        conn: &DatabaseConnection,
+        course_id: i64,
+        occurrence: Option<&Occurrence>,
+    ) -> Result<Self> {
+        let course = queries::course::get_by_id(conn, course_id).await?;
+        let semester = queries::semester::get_by_id(conn, course.semester_id).await?;
+        let semester_dir = semester.directory_path.clone();
+        let semester_code = SemesterInfo::try_from(semester)
+            .map(|s| s.code)
+            .unwrap_or(semester_dir);
+
+        let mut context = Self {
+            course: Some(course.short_name),
+            course_name: Some(course.name),
+            course_dir: Some(course.directory_path),
+            semester: Some(semester_code),
+            ..Default::default()
+        };
+
+        if let Some(occurrence) = occurrence {
+            context.slot_type = Some(occurrence.schedule_type.to_lowercase());
+            context.slot_start = Some(occurrence.start_time.format("%H:%M").to_string());
+            context.slot_end = Some(occurrence.end_time.format("%H:%M").to_string());
+            context.room = occurrence.room.clone();
+            context.building = occurrence.building.clone();
+
+            if occurrence.is_type("lecture") {
+                let lectures = queries::lectures::list_by_course(conn, course_id).await?;
+                let date = occurrence.date.format("%Y-%m-%d").to_string();
+                let start = occurrence.start_time.format("%H:%M").to_string();
+                context.lecture_number = lectures
+                    .iter()
+                    .find(|l| l.date == date && l.start_time == start)
+                    .map(|l| l.lecture_number)
+                    .or_else(|| {
+                        Some(lectures.iter().map(|l| l.lecture_number).max().unwrap_or(0) + 1)
+                    });
+            }
+        }
+
+        Ok(context)
+    }
+
+    /// Build the context for a deadline, including its course if it has one
+    pub async fn for_deadline(conn: &DatabaseConnection, deadline: &Deadline) -> Result<Self> {
+        let mut context = match deadline.course_id {
+            Some(course_id) => Self::for_course(conn, course_id, None).await?,
+            None => Self::default(),
+        };
+        context.deadline_kind = Some(deadline.kind.to_string());
+        context.deadline_title = Some(deadline.title.clone());
+        context.deadline_due = Some(deadline.due.format("%Y-%m-%d %H:%M").to_string());
+        Ok(context)
+    }
+
+    /// Environment variables for the hook process
+    fn env(&self, event: HookEvent) -> Vec<(&'static str, String)> {
+        let mut env = vec![("MMS_HOOK", event.name().to_string())];
+        let vars = [
+            ("MMS_COURSE", &self.course),
+            ("MMS_COURSE_NAME", &self.course_name),
+            ("MMS_COURSE_DIR", &self.course_dir),
+            ("MMS_SEMESTER", &self.semester),
+            ("MMS_PREVIOUS_COURSE", &self.previous_course),
+            ("MMS_SLOT_TYPE", &self.slot_type),
+            ("MMS_SLOT_START", &self.slot_start),
+            ("MMS_SLOT_END", &self.slot_end),
+            ("MMS_ROOM", &self.room),
+            ("MMS_BUILDING", &self.building),
+            ("MMS_DEADLINE_KIND", &self.deadline_kind),
+            ("MMS_DEADLINE_TITLE", &self.deadline_title),
+            ("MMS_DEADLINE_DUE", &self.deadline_due),
+        ];
+        for (name, value) in vars {
+            if let Some(value) = value {
+                env.push((name, value.clone()));
+            }
+        }
+        if let Some(number) = self.lecture_number {
+            env.push(("MMS_LECTURE_NUMBER", number.to_string()));
+        }
+        env
+    }
+}
+
+// ==================================================================================
+// Deadlines
+// ==================================================================================
+
+/// An open exercise or todo with a due date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deadline {
+    /// Identifies the deadline and its due date, so its hooks run once
+    pub key: String,
+    /// `exercise` or `todo`
+    pub kind: &'static str,
+    pub title: String,
+    pub course_id: Option<i64>,
+    pub due: NaiveDateTime,
+}
+
+impl Deadline {
+    /// When the deadline hooks run, `lead` before the deadline
+    pub fn hook_at(&self, lead: chrono::Duration) -> NaiveDateTime {
+        self.due
+            .checked_sub_signed(lead)
+            .unwrap_or(NaiveDateTime::MIN)
+    }
+}
+
+/// How long before a deadline the deadline hooks run
+pub fn deadline_lead(config: &HooksConfig) -> chrono::Duration {
+    i64::try_from(config.deadline_lead_minutes)
+        .ok()
+        .and_then(chrono::Duration::try_minutes)
+        .unwrap_or(chrono::Duration::MAX)
+}
+
+/// Open deadlines of the current semester's courses and of all todos
+///
+/// Exercises are open until submitted, todos until completed. Due dates
+/// without a time are due at the end of the day; unparsable ones are skipped.
+pub async fn open_deadlines(conn: &DatabaseConnection) -> Result<Vec<Deadline>> {
+    let mut deadlines = Vec::new();
+
+    if let Some(semester) = queries::semester::get_current(conn).await? {
+        for course in queries::course::list_unarchived_by_semester(conn, semester.id).await? {
+            for exercise in queries::exercises::list_by_course(conn, course.id).await? {
+                if exercise.is_submitted {
+                    continue;
+                }
+                let Some(due) = exercise.due_date.as_deref().and_then(parse_due) else {
+                    continue;
+                };
+                deadlines.push(Deadline {
+                    key: format!("exercise:{}:{}", exercise.id, due),
+                    kind: "exercise",
+                    title: exercise
+                        .title
+                        .unwrap_or_else(|| format!("Exercise {}", exercise.exercise_number)),
+                    course_id: Some(course.id),
+                    due,
+                });
+            }
+        }
+    }
+
+    for todo in queries::todos::list_open_with_due_date(conn).await? {
+        let Some(due) = todo.due_date.as_deref().and_then(parse_due) else {
+            continue;
+        };
+        deadlines.push(Deadline {
+            key: format!("todo:{}:{}", todo.id, due),
+            kind: "todo",
+            title: todo.title,
+            course_id: todo.course_id,
+            due,
+        });
+    }
+
+    Ok(deadlines)
+}
+
+/// Parse a due date (`YYYY-MM-DD` or `DD.MM.YYYY`, optionally followed by `HH:MM`)
+fn parse_due(due: &str) -> Option<NaiveDateTime> {
+    let due = due.trim();
+    ["%Y-%m-%d %H:%M", "%d.%m.%Y %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(due, format).ok())
+        .or_else(|| {
+            ["%Y-%m-%d", "%d.%m.%Y"]
+                .iter()
+                .find_map(|format| NaiveDate::parse_from_str(due, format).ok())
+                .and_then(|date| date.and_hms_opt(23, 59, 0))
+        })
+}
+
+// ==================================================================================
+// Running Hooks
+// ==================================================================================
+
+/// Run all hooks configured for `event` in the background
+///
+/// Hooks run one after another with `sh -c`, so they can rely on each other.
+/// Long-running programs (e.g. a recording) can be started in the background with `&`.
+pub fn spawn_hooks(config: &HooksConfig, event: HookEvent, context: HookContext) {
+    let commands = event.commands(config).to_vec();
+    if commands.is_empty() {
+        return;
+    }
+
+    let timeout = Duration::from_secs(config.timeout_seconds);
+    tokio::spawn(async move {
+        for command in commands {
+            run_hook(&command, event, &context, timeout).await;
+        }
+    });
+}
+
+/// Run a single hook command, logging its output
+///
+/// Returns whether the hook exited successfully within the timeout. Only the
+/// hook process itself is waited for: programs it starts in the background
+/// may keep running, and their output is logged as it arrives.
+pub async fn run_hook(
+    command: &str,
+    event: HookEvent,
+    context: &HookContext,
+    timeout: Duration,
+) -> bool {
+    let child = Command::new("sh")
+        .args(["-c", command])
+        .envs(context.env(event))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            warn!(hook = event.name(), command, "Failed to start hook: {}", e);
+            return false;
+        }
+    };
+
+    // Background programs inherit the pipes, so the output is read separately
+    // and does not keep the hook from finishing
+    if let Some(stdout) = child.stdout.take() {
+        tokio::spawn(log_output(stdout, event, false));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        tokio::spawn(log_output(stderr, event, true));
+    }
+
+    let status = match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(Ok(status)) => status,
+        Ok(Err(e)) => {
+            warn!(hook = event.name(), command, "Hook failed: {}", e);
+            return false;
+        }
+        Err(_) => {
+            let _ = child.kill().await;
+            warn!(
+                hook = event.name(),
+                command,
+                timeout_seconds = timeout.as_secs(),
+                "Hook timed out and was killed"
+            );
+            return false;
+        }
+    };
+
+    if !status.success() {
+        warn!(hook = event.name(), command, status = %status, "Hook failed");
+        return false;
+    }
+    info!(
+        event = "hook",
+        hook = event.name(),
+        command,
+        "Hook finished"
+    );
+    true
+}
+
+/// Log the lines of a hook's stdout or stderr until it is closed
+async fn log_output(stream: impl AsyncRead + Unpin, event: HookEvent, is_stderr: bool) {
+    let mut lines = BufReader::new(stream).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if is_stderr {
+            warn!(hook = event.name(), stream = "stderr", "{}", line);
+        } else {
+            info!(hook = event.name(), stream = "stdout", "{}", line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::entities::{exercises, todos};
+    use crate::test_support::semester_with_course;
+    use sea_orm::{ActiveModelTrait, Set};
+    use tempfile::TempDir;
+
+    fn context() -> HookContext {
+        HookContext {
+            course: Some("cs101".to_string()),
+            course_dir: Some("/studies/b3/cs101".to_string()),
+            semester: Some("b3".to_string()),
+            room: Some("HS 1".to_string()),
+            lecture_number: Some(4),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hook_environment() {
+        let temp_dir = TempDir::new().unwrap();
+        let out = temp_dir.path().join("env");
+        let command = format!(
+            "echo \"$MMS_HOOK $MMS_COURSE $MMS_COURSE_DIR $MMS_SEMESTER $MMS_ROOM $MMS_LECTURE_NUMBER ${{MMS_SLOT_TYPE:-none}}\" > {}",
+            out.display()
+        );
+
+        assert!(
+            run_hook(
+                &command,
+                HookEvent::SlotStart,
+                &context(),
+                Duration::from_secs(5)
+            )
+            .await
+        );
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            "on_slot_start cs101 /studies/b3/cs101 b3 HS 1 4 none\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hook_timeout_and_failure() {
+        let started = std::time::Instant::now();
+        assert!(
+            !run_hook(
+                "sleep 10",
+                HookEvent::Switch,
+                &context(),
+                Duration::from_millis(200)
+            )
+            .await
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        assert!(
+            !run_hook(
+                "exit 3",
+                HookEvent::SlotEnd,
+                &context(),
+                Duration::from_secs(5)
+            )
+            .await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hook_with_background_program() {
+        let started = std::time::Instant::now();
+        assert!(
+            run_hook(
+                "sleep 10 & echo started",
+                HookEvent::SlotStart,
+                &context(),
+                Duration::from_secs(2)
+            )
+            .await
+        );
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_open_deadlines() {
+        let temp_dir = TempDir::new().unwrap();
+        let (db, course) = semester_with_course(temp_dir.path(), "algo", "Algorithms").await;
+
+        for (number, due, is_submitted) in [
+            (1, Some("2024-11-05"), false),
+            (2, Some("2024-11-12 10:00"), true),
+            (3, None, false),
+        ] {
+            exercises::ActiveModel {
+                course_id: Set(course.id),
+                exercise_number: Set(number),
+                due_date: Set(due.map(str::to_string)),
+                is_submitted: Set(is_submitted),
+                is_graded: Set(false),
+                created_at: Set(chrono::Utc::now()),
+                updated_at: Set(chrono::Utc::now()),
+                ..Default::default()
+            }
+            .insert(&db)
+            .await
+            .unwrap();
+        }
+        for (title, completed) in [("Register for exam", false), ("Buy book", true)] {
+            todos::ActiveModel {
+                title: Set(title.to_string()),
+                due_date: Set(Some("20.11.2024 12:00".to_string())),
+                completed: Set(completed),
+                auto_clear: Set(false),
+                created_at: Set(chrono::Utc::now()),
+                updated_at: Set(chrono::Utc::now()),
+                ..Default::default()
+            }
+            .insert(&db)
+            .await
+            .unwrap();
+        }
+
+        let deadlines = open_deadlines(&db).await.unwrap();
+        assert_eq!(deadlines.len(), 2);
+
+        // Dates without a time are due at the end of the day
+        let exercise = &deadlines[0];
+        assert_eq!(
+            (exercise.kind, exercise.title.as_str()),
+            ("exercise", "Exercise 1")
+        );
+        assert_eq!(exercise.due.to_string(), "2024-11-05 23:59:00");
+        let lead = chrono::Duration::hours(24);
+        assert_eq!(exercise.hook_at(lead).to_string(), "2024-11-04 23:59:00");
+
+        let todo = &deadlines[1];
+        assert_eq!((todo.kind, todo.course_id), ("todo", None));
+        assert_eq!(todo.due.to_string(), "2024-11-20 12:00:00");
+
+        let env = HookContext::for_deadline(&db, exercise)
+            .await
+            .unwrap()
+            .env(HookEvent::Deadline);
+        assert!(env.contains(&("MMS_HOOK", "on_deadline".to_string())));
+        assert!(env.contains(&("MMS_COURSE", "algo".to_string())));
+        assert!(env.contains(&("MMS_DEADLINE_DUE", "2024-11-05 23:59".to_string())));
+    }
+}
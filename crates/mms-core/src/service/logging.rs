@@ -0,0 +1,322 @@
+//! Daemon logging
+//!
+//! The daemon logs human-readable lines to stdout and JSON lines to a
+//! daily-rotated file in `<data dir>/logs`:
+//!
+//! ```text
+//! {"timestamp":"2025-10-20T08:50:00.000123Z","level":"INFO","fields":{"message":"Switched course","event":"switch","from":"ana","to":"algo","reason":"schedule"},"target":"mms_core::service::daemon"}
+//! ```
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+use crate::config::settings::LoggingConfig;
+use crate::error::{MmsError, Result};
+use crate::paths;
+
+/// Log files are named `daemon.<date>.log`
+const LOG_FILE_PREFIX: &str = "daemon";
+const LOG_FILE_SUFFIX: &str = "log";
+
+/// Environment variable overriding the configured level (e.g. `MMS_LOG=debug`)
+const LOG_ENV: &str = "MMS_LOG";
+
+// ==================================================================================
+// Setup
+// ==================================================================================
+
+/// Install the global logger for the daemon
+///
+/// The returned guard flushes the log file when dropped and must be kept
+/// alive while the daemon runs.
+pub fn init(config: &LoggingConfig) -> Result<WorkerGuard> {
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(config.max_files)
+        .build(paths::log_dir_path()?)
+        .map_err(|e| MmsError::Other(format!("Failed to open log file: {}", e)))?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    let filter = EnvFilter::try_from_env(LOG_ENV)
+        .or_else(|_| EnvFilter::try_new(&config.level))
+        .map_err(|e| MmsError::Other(format!("Invalid log level '{}': {}", config.level, e)))?;
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_target(false))
+        .with(
+            tracing_subscriber::fmt::layer()
+                .json()
+                .with_writer(writer)
+                .with_current_span(false),
+        )
+        .try_init()
+        .map_err(|e| MmsError::Other(format!("Failed to initialize logging: {}", e)))?;
+
+    Ok(guard)
+}
+
+// ==================================================================================
+// Reading Logs
+// ==================================================================================
+
+/// A parsed line of the JSON log
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    pub message: String,
+    /// Structured fields besides the message (e.g. `event`, `from`, `to`)
+    pub fields: Map<String, Value>,
+}
+
+impl LogEntry {
+    /// Parse a JSON log line, `None` for anything else
+    pub fn parse(line: &str) -> Option<Self> {
+        let value: Value = serde_json::from_str(line).ok()?;
+        let timestamp = DateTime::parse_from_rfc3339(value.get("timestamp")?.as_str()?)
+            .ok()?
+            .with_timezone(&Utc);
+        let level = value.get("level")?.as_str()?.to_string();
+
+        let mut fields = value.get("fields")?.as_object()?.clone();
+        let message = match fields.remove("message") {
+            Some(Value::String(message)) => message,
+            Some(other) => other.to_string(),
+            None => String::new(),
+        };
+
+        Some(Self {
+            timestamp,
+            level,
+            message,
+            fields,
+        })
+    }
+
+    /// Render the entry as a single human-readable line
+    pub fn display_line(&self) -> String {
+        let mut line = format!(
+            "{} {:>5} {}",
+            self.timestamp
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S"),
+            self.level,
+            self.message
+        );
+        for (key, value) in &self.fields {
+            match value {
+                Value::String(s) => line.push_str(&format!(" {}={}", key, s)),
+                other => line.push_str(&format!(" {}={}", key, other)),
+            }
+        }
+        line
+    }
+}
+
+/// Log files in `dir`, oldest first
+pub fn log_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with(&format!("{}.", LOG_FILE_PREFIX))
+                        && name.ends_with(&format!(".{}", LOG_FILE_SUFFIX))
+                })
+        })
+        .collect();
+    // Dates in the names sort chronologically
+    files.sort();
+    Ok(files)
+}
+
+/// Read all log entries in `dir`, optionally only those at or after `since`
+pub fn read_logs(dir: &Path, since: Option<DateTime<Utc>>) -> Result<Vec<LogEntry>> {
+    let mut entries = Vec::new();
+    for file in log_files(dir)? {
+        for line in fs::read_to_string(&file)?.lines() {
+            if let Some(entry) = LogEntry::parse(line)
+                && since.is_none_or(|since| entry.timestamp >= since)
+            {
+                entries.push(entry);
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Parse a `--since` value relative to `now`
+///
+/// Accepts durations (`30m`, `2h`, `7d`), dates (`2025-10-20`) and date-times
+/// (`2025-10-20 14:00`) in local time.
+pub fn parse_since(value: &str, now: DateTime<Local>) -> Result<DateTime<Utc>> {
+    let invalid = || MmsError::InvalidTimeSpec(value.to_string());
+    let value = value.trim();
+
+    if let Some(unit) = value.chars().last().filter(|c| c.is_ascii_alphabetic()) {
+        let amount: i64 = value[..value.len() - 1].parse().map_err(|_| invalid())?;
+        let duration = match unit {
+            'm' => Duration::try_minutes(amount),
+            'h' => Duration::try_hours(amount),
+            'd' => Duration::try_days(amount),
+            'w' => Duration::try_weeks(amount),
+            _ => return Err(invalid()),
+        };
+        return duration
+            .and_then(|duration| now.checked_sub_signed(duration))
+            .map(|since| since.with_timezone(&Utc))
+            .ok_or_else(invalid);
+    }
+
+    let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d").map(|d| d.and_hms_opt(0, 0, 0).unwrap())
+        })
+        .map_err(|_| invalid())?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SWITCH_LINE: &str = r#"{"timestamp":"2025-10-20T08:50:00.000123Z","level":"INFO","fields":{"message":"Switched course","event":"switch","from":"ana","to":"algo","reason":"schedule"},"target":"mms_core::service::daemon"}"#;
+
+    #[test]
+    fn test_parse_entry() {
+        let entry = LogEntry::parse(SWITCH_LINE).unwrap();
+        assert_eq!(entry.level, "INFO");
+        assert_eq!(entry.message, "Switched course");
+        assert_eq!(entry.fields["event"], "switch");
+        assert_eq!(entry.fields["to"], "algo");
+        assert_eq!(
+            entry.timestamp,
+            Utc.with_ymd_and_hms(2025, 10, 20, 8, 50, 0).unwrap() + Duration::microseconds(123)
+        );
+
+        assert!(LogEntry::parse("Starting MMS daemon...").is_none());
+    }
+
+    /// Collects everything written by the JSON layer
+    #[derive(Clone, Default)]
+    struct Buffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_parse_subscriber_output() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_writer(move || writer.clone())
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(
+                event = "switch",
+                from = "ana",
+                to = "algo",
+                "Switched course"
+            );
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let entry = LogEntry::parse(output.lines().next().unwrap()).unwrap();
+        assert_eq!(entry.level, "INFO");
+        assert_eq!(entry.message, "Switched course");
+        assert_eq!(entry.fields["event"], "switch");
+        assert_eq!(entry.fields["from"], "ana");
+        assert!(Utc::now() - entry.timestamp < Duration::minutes(1));
+    }
+
+    #[test]
+    fn test_read_logs_since() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("daemon.2025-10-19.log"),
+            SWITCH_LINE.replace("2025-10-20", "2025-10-19") + "\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("daemon.2025-10-20.log"),
+            format!("{}\nnot json\n", SWITCH_LINE),
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("other.log"), SWITCH_LINE).unwrap();
+
+        assert_eq!(read_logs(temp_dir.path(), None).unwrap().len(), 2);
+
+        let since = Utc.with_ymd_and_hms(2025, 10, 20, 0, 0, 0).unwrap();
+        let entries = read_logs(temp_dir.path(), Some(since)).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].timestamp.date_naive().to_string(), "2025-10-20");
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = Local.with_ymd_and_hms(2025, 10, 20, 12, 0, 0).unwrap();
+
+        assert_eq!(
+            parse_since("2h", now).unwrap(),
+            (now - Duration::hours(2)).with_timezone(&Utc)
+        );
+        assert_eq!(
+            parse_since("1d", now).unwrap(),
+            (now - Duration::days(1)).with_timezone(&Utc)
+        );
+        assert_eq!(
+            parse_since("2025-10-19 14:30", now).unwrap(),
+            Local
+                .with_ymd_and_hms(2025, 10, 19, 14, 30, 0)
+                .unwrap()
+                .with_timezone(&Utc)
+        );
+        assert_eq!(
+            parse_since("2025-10-19", now).unwrap(),
+            Local
+                .with_ymd_and_hms(2025, 10, 19, 0, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc)
+        );
+
+        assert!(parse_since("yesterday", now).is_err());
+        assert!(parse_since("5x", now).is_err());
+
+        // Too far back to represent
+        assert!(matches!(
+            parse_since("999999999999w", now),
+            Err(MmsError::InvalidTimeSpec(_))
+        ));
+        assert!(parse_since("999999999d", now).is_err());
+    }
+}
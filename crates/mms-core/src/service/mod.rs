@@ -1,5 +1,14 @@
+pub mod control;
 pub mod daemon;
+pub mod hooks;
+pub mod logging;
+pub mod notifier;
 pub mod scheduler;
+pub mod session;
+pub mod watcher;
 
 pub use daemon::{Daemon, DaemonStatus};
-pub use scheduler::ScheduleEngine;
+pub use hooks::{HookContext, HookEvent};
+pub use notifier::{Notification, Notifier};
+pub use scheduler::{Occurrence, ScheduleEngine};
+pub use session::LectureSession;
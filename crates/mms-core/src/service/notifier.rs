@@ -0,0 +1,571 @@
+use chrono::{Days, Duration, NaiveDateTime};
+use sea_orm::DatabaseConnection;
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::config::Config;
+use crate::config::settings::NotifierBackend;
+use crate::db::queries;
+use crate::error::{MmsError, Result};
+use crate::service::scheduler::{Occurrence, ScheduleEngine};
+
+/// Application name reported to the notification server
+const APP_NAME: &str = "mms";
+
+/// How long a notification stays visible (milliseconds, D-Bus only)
+#[cfg(target_os = "linux")]
+const EXPIRE_TIMEOUT_MS: i32 = 10_000;
+
+/// How long a backend may take to deliver a notification
+const NOTIFY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+// ==================================================================================
+// Notifications
+// ==================================================================================
+
+/// A desktop notification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// Identifies the notified occurrence or event, so it is only sent once
+    pub key: String,
+    pub summary: String,
+    pub body: String,
+}
+
+/// Something that can deliver notifications to the user
+pub trait Notifier: Send + Sync {
+    fn notify(&self, notification: &Notification) -> Result<()>;
+}
+
+/// Create the notifier configured in `[notifications]`
+///
+/// Returns `None` if notifications are disabled (`schedule.notify = false`)
+/// or no backend is available on this platform.
+pub fn notifier_from_config(config: &Config) -> Option<Arc<dyn Notifier>> {
+    if !config.schedule.as_ref().is_some_and(|s| s.notify) {
+        return None;
+    }
+
+    let notifications = config.notifications.clone().unwrap_or_default();
+    match notifications.backend {
+        NotifierBackend::Dbus => dbus_notifier(),
+        NotifierBackend::Osascript => Some(Arc::new(OsascriptNotifier)),
+        NotifierBackend::Command => notifications
+            .command
+            .map(|command| Arc::new(CommandNotifier::new(command)) as Arc<dyn Notifier>),
+        NotifierBackend::Auto => {
+            if cfg!(target_os = "macos") {
+                Some(Arc::new(OsascriptNotifier))
+            } else {
+                dbus_notifier()
+            }
+        }
+    }
+}
+
+/// The session bus notifier, `None` where D-Bus is not supported
+fn dbus_notifier() -> Option<Arc<dyn Notifier>> {
+    #[cfg(target_os = "linux")]
+    return Some(Arc::new(DbusNotifier::session()));
+    #[cfg(not(target_os = "linux"))]
+    return None;
+}
+
+/// Deliver a notification without blocking the async runtime
+///
+/// Backends block on D-Bus calls and child processes, so they run on a
+/// blocking thread and are given up on after a timeout.
+pub async fn send(notifier: &Arc<dyn Notifier>, notification: &Notification) -> Result<()> {
+    let notifier = Arc::clone(notifier);
+    let notification = notification.clone();
+    let task = tokio::task::spawn_blocking(move || notifier.notify(&notification));
+
+    match tokio::time::timeout(NOTIFY_TIMEOUT, task).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => Err(MmsError::Other(format!("Notification task failed: {}", e))),
+        Err(_) => Err(MmsError::NotificationFailed {
+            backend: "notifier".to_string(),
+            reason: format!("timed out after {}s", NOTIFY_TIMEOUT.as_secs()),
+        }),
+    }
+}
+
+// ==================================================================================
+// Backends
+// ==================================================================================
+
+/// Freedesktop `org.freedesktop.Notifications` over D-Bus
+#[cfg(target_os = "linux")]
+pub struct DbusNotifier {
+    /// Bus address; `None` uses the session bus (`DBUS_SESSION_BUS_ADDRESS`)
+    address: Option<String>,
+}
+
+#[cfg(target_os = "linux")]
+impl DbusNotifier {
+    /// Notifier for the user's session bus
+    pub fn session() -> Self {
+        Self { address: None }
+    }
+
+    /// Notifier for the bus at the given address
+    pub fn with_address(address: impl Into<String>) -> Self {
+        Self {
+            address: Some(address.into()),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Notifier for DbusNotifier {
+    fn notify(&self, notification: &Notification) -> Result<()> {
+        let failed = |e: zbus::Error| MmsError::NotificationFailed {
+            backend: "dbus".to_string(),
+            reason: e.to_string(),
+        };
+
+        let builder = match &self.address {
+            Some(address) => zbus::blocking::connection::Builder::address(address.as_str()),
+            None => zbus::blocking::connection::Builder::session(),
+        }
+        .map_err(failed)?;
+        let connection = builder
+            .method_timeout(NOTIFY_TIMEOUT)
+            .build()
+            .map_err(failed)?;
+
+        // Notify(app_name, replaces_id, app_icon, summary, body, actions, hints, expire_timeout)
+        let hints: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        connection
+            .call_method(
+                Some("org.freedesktop.Notifications"),
+                "/org/freedesktop/Notifications",
+                Some("org.freedesktop.Notifications"),
+                "Notify",
+                &(
+                    APP_NAME,
+                    0u32,
+                    "",
+                    notification.summary.as_str(),
+                    notification.body.as_str(),
+                    Vec::<&str>::new(),
+                    hints,
+                    EXPIRE_TIMEOUT_MS,
+                ),
+            )
+            .map_err(failed)?;
+        Ok(())
+    }
+}
+
+/// macOS Notification Center via `osascript`
+pub struct OsascriptNotifier;
+
+impl Notifier for OsascriptNotifier {
+    fn notify(&self, notification: &Notification) -> Result<()> {
+        let script = format!(
+            "display notification {} with title {}",
+            applescript_string(&notification.body),
+            applescript_string(&notification.summary)
+        );
+        run("osascript", Command::new("osascript").args(["-e", &script]))
+    }
+}
+
+/// User-provided shell command
+///
+/// The command is run with `sh -c` and receives the notification in the
+/// `MMS_NOTIFY_SUMMARY` and `MMS_NOTIFY_BODY` environment variables.
+pub struct CommandNotifier {
+    command: String,
+}
+
+impl CommandNotifier {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+        }
+    }
+}
+
+impl Notifier for CommandNotifier {
+    fn notify(&self, notification: &Notification) -> Result<()> {
+        run(
+            "command",
+            Command::new("sh")
+                .args(["-c", &self.command])
+                .env("MMS_NOTIFY_SUMMARY", &notification.summary)
+                .env("MMS_NOTIFY_BODY", &notification.body),
+        )
+    }
+}
+
+/// Run a backend command, turning a non-zero exit or a timeout into an error
+///
+/// A command still running after the timeout is killed.
+fn run(backend: &str, command: &mut Command) -> Result<()> {
+    let failed = |reason: String| MmsError::NotificationFailed {
+        backend: backend.to_string(),
+        reason,
+    };
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| failed(e.to_string()))?;
+
+    let deadline = Instant::now() + NOTIFY_TIMEOUT;
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| failed(e.to_string()))? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(failed(format!(
+                "timed out after {}s",
+                NOTIFY_TIMEOUT.as_secs()
+            )));
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    };
+
+    if !status.success() {
+        let mut stderr = String::new();
+        if let Some(mut pipe) = child.stderr.take() {
+            let _ = std::io::Read::read_to_string(&mut pipe, &mut stderr);
+        }
+        return Err(failed(stderr.trim().to_string()));
+    }
+    Ok(())
+}
+
+/// Quote a string as an AppleScript literal
+fn applescript_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// ==================================================================================
+// Pending Notifications
+// ==================================================================================
+
+/// Collect the notifications that are due at `now`
+///
+/// This function performs the following steps:
+/// 1. Announces occurrences of the current semester starting within `lead`
+/// 2. Announces cancellations and room changes for today and tomorrow
+///
+/// Callers are expected to remember the keys of sent notifications.
+pub async fn pending_notifications(
+    conn: &DatabaseConnection,
+    now: NaiveDateTime,
+    lead: Duration,
+) -> Result<Vec<Notification>> {
+    let Some(semester) = queries::semester::get_current(conn).await? else {
+        return Ok(Vec::new());
+    };
+
+    let today = now.date();
+    let tomorrow = today + Days::new(1);
+    let mut notifications = Vec::new();
+
+    for course in queries::course::list_unarchived_by_semester(conn, semester.id).await? {
+        // Upcoming occurrences
+        let occurrences =
+            ScheduleEngine::occurrences_for_course(conn, course.id, today, tomorrow).await?;
+        for occurrence in occurrences {
+            let starts_at = occurrence.date.and_time(occurrence.start_time);
+            if starts_at > now && starts_at - now <= lead {
+                notifications.push(upcoming(&course.short_name, &occurrence, starts_at - now));
+            }
+        }
+
+        // Cancellations and room changes
+        for date in [today, tomorrow] {
+            let events = queries::event::get_by_course_and_date(
+                conn,
+                course.id,
+                date.format("%Y-%m-%d").to_string(),
+            )
+            .await?;
+
+            for event in events {
+                let kind = match event.schedule_id {
+                    Some(id) => queries::schedule::get_by_id(conn, id)
+                        .await
+                        .map(|s| s.schedule_type.to_lowercase())
+                        .ok(),
+                    None => None,
+                };
+                let what = match &kind {
+                    Some(kind) => format!("{} {}", course.short_name, kind),
+                    None => course.short_name.clone(),
+                };
+                let when = match &event.start_time {
+                    Some(start) => format!("{} {}", date.format("%a %d.%m."), start),
+                    None => date.format("%a %d.%m.").to_string(),
+                };
+
+                let (summary, details) = match event.event_type.as_str() {
+                    "Cancellation" => (format!("{} cancelled", what), when),
+                    "RoomChange" => {
+                        let room = location_text(&event.room, &event.building);
+                        (
+                            format!("{} moved", what),
+                            format!("{}: now in {}", when, room),
+                        )
+                    }
+                    _ => continue,
+                };
+
+                let body = match &event.description {
+                    Some(description) => format!("{}\n{}", details, description),
+                    None => details,
+                };
+
+                notifications.push(Notification {
+                    key: format!("event:{}", event.id),
+                    summary,
+                    body,
+                });
+            }
+        }
+    }
+
+    Ok(notifications)
+}
+
+/// Notification for an occurrence that starts in `remaining`
+fn upcoming(short_name: &str, occurrence: &Occurrence, remaining: Duration) -> Notification {
+    // Round up so that 9m30s reads as "10 min"
+    let minutes = (remaining.num_seconds() + 59) / 60;
+    Notification {
+        key: format!(
+            "occurrence:{}:{}:{}",
+            occurrence.schedule_id,
+            occurrence.date,
+            occurrence.start_time.format("%H:%M")
+        ),
+        summary: format!(
+            "{} {} starting in {} min",
+            short_name,
+            occurrence.schedule_type.to_lowercase(),
+            minutes
+        ),
+        body: format!(
+            "{}-{} in {}",
+            occurrence.start_time.format("%H:%M"),
+            occurrence.end_time.format("%H:%M"),
+            location_text(&occurrence.room, &occurrence.building)
+        ),
+    }
+}
+
+/// Human-readable room and building
+fn location_text(room: &Option<String>, building: &Option<String>) -> String {
+    match (room, building) {
+        (Some(room), Some(building)) => format!("{}, {}", room, building),
+        (Some(room), None) => room.clone(),
+        (None, Some(building)) => building.clone(),
+        (None, None) => "unknown room".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::entities::{course_events, course_schedules};
+    use crate::test_support::semester_with_course;
+    use sea_orm::{ActiveModelTrait, Set};
+    use std::io::Read;
+    use std::time::Duration as StdDuration;
+    use tempfile::TempDir;
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn notification() -> Notification {
+        Notification {
+            key: "test".to_string(),
+            summary: "CS101 lecture starting in 10 min".to_string(),
+            body: "10:00-12:00 in 'MI HS 1'".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_command_notifier() {
+        let temp_dir = TempDir::new().unwrap();
+        let out = temp_dir.path().join("out");
+        let notifier = CommandNotifier::new(format!(
+            "printf '%s|%s' \"$MMS_NOTIFY_SUMMARY\" \"$MMS_NOTIFY_BODY\" > {}",
+            out.display()
+        ));
+
+        notifier.notify(&notification()).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            "CS101 lecture starting in 10 min|10:00-12:00 in 'MI HS 1'"
+        );
+
+        assert!(
+            CommandNotifier::new("exit 1")
+                .notify(&notification())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_command_notifier_times_out() {
+        let started = Instant::now();
+        let result = CommandNotifier::new("sleep 30").notify(&notification());
+
+        assert!(matches!(
+            result,
+            Err(MmsError::NotificationFailed { reason, .. }) if reason.contains("timed out")
+        ));
+        assert!(started.elapsed() < StdDuration::from_secs(10));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    #[ignore = "needs dbus-daemon and dbus-monitor"]
+    fn test_dbus_notifier_on_private_bus() {
+        // Start a private session bus
+        let output = Command::new("dbus-daemon")
+            .args(["--session", "--fork", "--nopidfile"])
+            .args(["--print-address=1", "--print-pid=1"])
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let mut lines = stdout.lines();
+        let address = lines.next().unwrap().to_string();
+        let bus_pid: i32 = lines.next().unwrap().trim().parse().unwrap();
+
+        // Watch Notify calls on it
+        let mut monitor = Command::new("dbus-monitor")
+            .args(["--address", &address])
+            .arg("type='method_call',interface='org.freedesktop.Notifications'")
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        std::thread::sleep(StdDuration::from_millis(300));
+
+        // No notification server runs on the private bus, so the call itself fails
+        let result = DbusNotifier::with_address(&address).notify(&notification());
+        std::thread::sleep(StdDuration::from_millis(300));
+
+        monitor.kill().unwrap();
+        let mut seen = String::new();
+        monitor
+            .stdout
+            .take()
+            .unwrap()
+            .read_to_string(&mut seen)
+            .unwrap();
+        monitor.wait().unwrap();
+        Command::new("kill")
+            .arg(bus_pid.to_string())
+            .status()
+            .unwrap();
+
+        assert!(result.is_err());
+        assert!(seen.contains("member=Notify"));
+        assert!(seen.contains("string \"mms\""));
+        assert!(seen.contains("uint32 0"));
+        assert!(seen.contains("string \"CS101 lecture starting in 10 min\""));
+        assert!(seen.contains("string \"10:00-12:00 in 'MI HS 1'\""));
+        assert!(seen.contains("int32 10000"));
+    }
+
+    #[tokio::test]
+    async fn test_pending_notifications() {
+        let temp_dir = TempDir::new().unwrap();
+        let (db, course) =
+            semester_with_course(temp_dir.path(), "cs101", "Introduction to Computer Science")
+                .await;
+
+        // Mondays 10:00-12:00 in HS 1, Tuesdays 14:00-16:00
+        let mut schedule_ids = Vec::new();
+        for (day, start, end, kind) in [
+            (0, "10:00", "12:00", "Lecture"),
+            (1, "14:00", "16:00", "Tutorial"),
+        ] {
+            let schedule = course_schedules::ActiveModel {
+                course_id: Set(course.id),
+                schedule_type: Set(kind.to_string()),
+                day_of_week: Set(day),
+                start_time: Set(start.to_string()),
+                end_time: Set(end.to_string()),
+                start_date: Set("2024-10-01".to_string()),
+                end_date: Set("2025-02-28".to_string()),
+                room: Set(Some("HS 1".to_string())),
+                priority: Set(0),
+                created_at: Set(chrono::Utc::now()),
+                updated_at: Set(chrono::Utc::now()),
+                ..Default::default()
+            }
+            .insert(&db)
+            .await
+            .unwrap();
+            schedule_ids.push(schedule.id);
+        }
+
+        // Monday 2024-10-07: nothing announced yet at 09:00
+        let lead = Duration::minutes(10);
+        let pending = pending_notifications(&db, at("2024-10-07", "09:00"), lead)
+            .await
+            .unwrap();
+        assert!(pending.is_empty());
+
+        let pending = pending_notifications(&db, at("2024-10-07", "09:50"), lead)
+            .await
+            .unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(
+            pending[0].summary,
+            format!("{} lecture starting in 10 min", course.short_name)
+        );
+        assert_eq!(pending[0].body, "10:00-12:00 in HS 1");
+
+        // Tomorrow's tutorial is cancelled, the next lecture moves
+        for (event_type, date, schedule_id, room) in [
+            ("Cancellation", "2024-10-08", schedule_ids[1], None),
+            ("RoomChange", "2024-10-07", schedule_ids[0], Some("HS 2")),
+        ] {
+            course_events::ActiveModel {
+                course_id: Set(course.id),
+                schedule_id: Set(Some(schedule_id)),
+                event_type: Set(event_type.to_string()),
+                date: Set(date.to_string()),
+                room: Set(room.map(String::from)),
+                created_at: Set(chrono::Utc::now()),
+                updated_at: Set(chrono::Utc::now()),
+                ..Default::default()
+            }
+            .insert(&db)
+            .await
+            .unwrap();
+        }
+
+        let pending = pending_notifications(&db, at("2024-10-07", "09:55"), lead)
+            .await
+            .unwrap();
+        let summaries: Vec<_> = pending.iter().map(|n| n.summary.as_str()).collect();
+        assert_eq!(
+            summaries,
+            vec![
+                format!("{} lecture starting in 5 min", course.short_name),
+                format!("{} lecture moved", course.short_name),
+                format!("{} tutorial cancelled", course.short_name),
+            ]
+        );
+        assert_eq!(pending[0].body, "10:00-12:00 in HS 2");
+        assert_eq!(pending[1].body, "Mon 07.10.: now in HS 2");
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{Datelike, Local, NaiveDate, NaiveTime};
+use chrono::{Datelike, Days, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime};
 use sea_orm::DatabaseConnection;
 // Changed to course_events
 // Changed to course_events
@@ -15,6 +15,28 @@ use crate::error::Result;
 
 use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter}; // Added PaginatorTrait
 
+/// A single concrete occurrence of a recurring schedule on a specific date,
+/// with room and time changes already applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occurrence {
+    pub course_id: i64,
+    pub schedule_id: i64,
+    pub schedule_type: String,
+    pub date: NaiveDate,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub room: Option<String>,
+    pub building: Option<String>,
+    pub location: Option<String>,
+}
+
+impl Occurrence {
+    /// Whether this occurrence belongs to a schedule of the given type (case-insensitive)
+    pub fn is_type(&self, schedule_type: &str) -> bool {
+        self.schedule_type.eq_ignore_ascii_case(schedule_type)
+    }
+}
+
 /// ScheduleEngine determines which course should be active at any given time
 /// based on recurring schedules, one-time events, cancellations, and holidays.
 pub struct ScheduleEngine;
@@ -42,7 +64,7 @@ impl ScheduleEngine {
         };
 
         // Get all courses for this semester
-        let courses = queries::course::list_by_semester(conn, semester.id).await?;
+        let courses = queries::course::list_unarchived_by_semester(conn, semester.id).await?;
 
         // Convert NaiveDate and NaiveTime to String for database queries
         let date_str = date.format("%Y-%m-%d").to_string();
@@ -82,6 +104,284 @@ impl ScheduleEngine {
         Ok(None)
     }
 
+    /// Determine the active course at `now`, switching early to slots starting within `switch_window`
+    ///
+    /// A course that is active right now always wins over an upcoming one.
+    pub async fn determine_active_course_with_window(
+        conn: &DatabaseConnection,
+        now: NaiveDateTime,
+        switch_window: Duration,
+    ) -> Result<Option<i64>> {
+        if let Some(course_id) = Self::determine_active_course(conn, now.date(), now.time()).await?
+        {
+            return Ok(Some(course_id));
+        }
+
+        if switch_window <= Duration::zero() {
+            return Ok(None);
+        }
+
+        let upcoming = now + switch_window;
+        Self::determine_active_course(conn, upcoming.date(), upcoming.time()).await
+    }
+
+    /// Compute the next moment after `now` at which the active course may change
+    ///
+    /// Candidates are slot starts (minus the switch window), slot ends and
+    /// timed events of today and tomorrow. Falls back to the next midnight.
+    pub async fn next_boundary(
+        conn: &DatabaseConnection,
+        now: NaiveDateTime,
+        switch_window: Duration,
+    ) -> Result<NaiveDateTime> {
+        let today = now.date();
+        let tomorrow = today + Days::new(1);
+        let mut next = tomorrow.and_time(NaiveTime::MIN);
+
+        let semester = match queries::semester::get_current(conn).await? {
+            Some(s) => s,
+            None => return Ok(next),
+        };
+
+        let mut candidates = Vec::new();
+        for course in queries::course::list_unarchived_by_semester(conn, semester.id).await? {
+            for occurrence in Self::occurrences_for_course(conn, course.id, today, tomorrow).await?
+            {
+                let start = NaiveDateTime::new(occurrence.date, occurrence.start_time);
+                candidates.push(start - switch_window);
+                candidates.push(start);
+                candidates.push(NaiveDateTime::new(occurrence.date, occurrence.end_time));
+            }
+
+            for date in [today, tomorrow] {
+                let events = queries::event::get_by_course_and_date(
+                    conn,
+                    course.id,
+                    date.format("%Y-%m-%d").to_string(),
+                )
+                .await?;
+
+                for event in events {
+                    if let Some(start) = event.start_time.as_deref().and_then(parse_time) {
+                        let start = NaiveDateTime::new(date, start);
+                        candidates.push(start - switch_window);
+                        candidates.push(start);
+                    }
+                    if let Some(end) = event.end_time.as_deref().and_then(parse_time) {
+                        candidates.push(NaiveDateTime::new(date, end));
+                    }
+                }
+            }
+        }
+
+        for candidate in candidates {
+            if candidate > now && candidate < next {
+                next = candidate;
+            }
+        }
+
+        Ok(next)
+    }
+
+    /// Whether a course is active at `at` only because of an event
+    ///
+    /// True for one-time and override events outside of the course's
+    /// recurring schedule.
+    pub async fn is_event_driven(
+        conn: &DatabaseConnection,
+        course_id: i64,
+        at: NaiveDateTime,
+    ) -> Result<bool> {
+        let date_str = at.date().format("%Y-%m-%d").to_string();
+        let time_str = at.time().format("%H:%M").to_string();
+
+        if Self::is_recurring_schedule_active(conn, course_id, &date_str, &time_str).await? {
+            return Ok(false);
+        }
+
+        Ok(
+            Self::get_override_course(conn, course_id, &date_str, &time_str).await?
+                == Some(course_id)
+                || Self::is_one_time_event_active(conn, course_id, &date_str, &time_str).await?,
+        )
+    }
+
+    /// Find the next moment after `now` at which the scheduled course differs from `current`
+    ///
+    /// Walks the schedule boundaries up to `horizon` ahead. Returns the time and
+    /// the course that will be active then, or `None` if nothing changes.
+    pub async fn next_switch(
+        conn: &DatabaseConnection,
+        now: NaiveDateTime,
+        switch_window: Duration,
+        current: Option<i64>,
+        horizon: Duration,
+    ) -> Result<Option<(NaiveDateTime, Option<i64>)>> {
+        let end = now + horizon;
+        let mut at = now;
+
+        loop {
+            at = Self::next_boundary(conn, at, switch_window).await?;
+            if at > end {
+                return Ok(None);
+            }
+
+            let course = Self::determine_active_course_with_window(conn, at, switch_window).await?;
+            if course != current {
+                return Ok(Some((at, course)));
+            }
+        }
+    }
+
+    /// Find the next moment after `now` at which a slot switches to a course
+    ///
+    /// Unlike [`Self::next_switch`] this skips the end of the current slot, so
+    /// back-to-back slots of the same course count as one.
+    pub async fn next_slot_start(
+        conn: &DatabaseConnection,
+        now: NaiveDateTime,
+        switch_window: Duration,
+        horizon: Duration,
+    ) -> Result<Option<NaiveDateTime>> {
+        let end = now + horizon;
+        let mut at = now;
+        let mut current =
+            Self::determine_active_course_with_window(conn, now, switch_window).await?;
+
+        while let Some((switch_at, course)) =
+            Self::next_switch(conn, at, switch_window, current, end - at).await?
+        {
+            if course.is_some() {
+                return Ok(Some(switch_at));
+            }
+            at = switch_at;
+            current = None;
+        }
+
+        Ok(None)
+    }
+
+    /// List all occurrences of a course's recurring schedules within [from, to]
+    ///
+    /// Holidays (without exception) and cancelled occurrences are skipped;
+    /// room and time changes are applied. Results are sorted chronologically.
+    pub async fn occurrences_for_course(
+        conn: &DatabaseConnection,
+        course_id: i64,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<Occurrence>> {
+        let schedules = queries::schedule::list_by_course(conn, course_id).await?;
+        let mut occurrences = Vec::new();
+
+        for schedule in schedules {
+            let schedule_start = NaiveDate::parse_from_str(&schedule.start_date, "%Y-%m-%d")?;
+            let schedule_end = NaiveDate::parse_from_str(&schedule.end_date, "%Y-%m-%d")?;
+            let start_time = NaiveTime::parse_from_str(&schedule.start_time, "%H:%M")?;
+            let end_time = NaiveTime::parse_from_str(&schedule.end_time, "%H:%M")?;
+
+            let first = from.max(schedule_start);
+            let last = to.min(schedule_end);
+
+            // Advance to the first matching weekday
+            let offset =
+                (schedule.day_of_week + 7 - first.weekday().num_days_from_monday() as i64) % 7;
+            let Some(mut date) = first.checked_add_days(chrono::Days::new(offset as u64)) else {
+                continue;
+            };
+
+            while date <= last {
+                let date_str = date.format("%Y-%m-%d").to_string();
+
+                if !Self::is_holiday(conn, course_id, &date_str).await? {
+                    let mut occurrence = Occurrence {
+                        course_id,
+                        schedule_id: schedule.id,
+                        schedule_type: schedule.schedule_type.clone(),
+                        date,
+                        start_time,
+                        end_time,
+                        room: schedule.room.clone(),
+                        building: schedule.building.clone(),
+                        location: schedule.location.clone(),
+                    };
+
+                    if Self::apply_events(conn, &mut occurrence, &schedule.start_time).await? {
+                        occurrences.push(occurrence);
+                    }
+                }
+
+                date = match date.checked_add_days(chrono::Days::new(7)) {
+                    Some(next) => next,
+                    None => break,
+                };
+            }
+        }
+
+        occurrences.sort_by_key(|o| (o.date, o.start_time));
+        Ok(occurrences)
+    }
+
+    /// Apply the events of an occurrence's date to it
+    ///
+    /// Returns `false` if the occurrence is cancelled.
+    async fn apply_events(
+        conn: &DatabaseConnection,
+        occurrence: &mut Occurrence,
+        schedule_start: &str,
+    ) -> Result<bool> {
+        let date_str = occurrence.date.format("%Y-%m-%d").to_string();
+        let events =
+            queries::event::get_by_course_and_date(conn, occurrence.course_id, date_str).await?;
+
+        for event in events {
+            // Events bound to another schedule do not affect this occurrence
+            if event
+                .schedule_id
+                .is_some_and(|id| id != occurrence.schedule_id)
+            {
+                continue;
+            }
+
+            match event.event_type.as_str() {
+                "Cancellation" => {
+                    let cancelled = match (&event.start_time, &event.end_time) {
+                        (Some(start), Some(end)) => {
+                            Self::is_time_in_range(schedule_start, start, end)
+                        }
+                        // No time specified = entire day cancelled
+                        _ => true,
+                    };
+                    if cancelled {
+                        return Ok(false);
+                    }
+                }
+                "RoomChange" => {
+                    if event.room.is_some() {
+                        occurrence.room = event.room;
+                    }
+                    if event.building.is_some() {
+                        occurrence.building = event.building;
+                    }
+                    if event.location.is_some() {
+                        occurrence.location = event.location;
+                    }
+                }
+                "TimeChange" => {
+                    if let Some(start) = event.start_time {
+                        occurrence.start_time = NaiveTime::parse_from_str(&start, "%H:%M")?;
+                    }
+                    if let Some(end) = event.end_time {
+                        occurrence.end_time = NaiveTime::parse_from_str(&end, "%H:%M")?;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(true)
+    }
+
     /// Check if a course is cancelled at a specific date and time
     async fn is_cancelled(
         conn: &DatabaseConnection,
@@ -252,6 +552,11 @@ impl ScheduleEngine {
     }
 }
 
+/// Parse an "HH:MM" time, ignoring malformed values
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time, "%H:%M").ok()
+}
+
 // Tests kept mostly as is but commented out to pass compilation if async test harness missing
 #[cfg(test)]
 mod tests {
@@ -299,4 +604,167 @@ mod tests {
         let after = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
         assert!(!ScheduleEngine::is_date_in_range(&after, &start, &end));
     }
+
+    // ------------------------------------------------------------------------
+    // Wake-up Planning
+    // ------------------------------------------------------------------------
+
+    use crate::db::entities::course_schedules;
+    use crate::test_support::semester_with_course;
+    use sea_orm::{ActiveModelTrait, Set};
+    use tempfile::TempDir;
+
+    /// Set up a current semester with one course meeting Mondays 10:00-12:00
+    async fn setup_weekly_course() -> (DatabaseConnection, TempDir, i64) {
+        let temp_dir = TempDir::new().unwrap();
+        let (db, course) = semester_with_course(temp_dir.path(), "algo", "Algorithms").await;
+
+        course_schedules::ActiveModel {
+            course_id: Set(course.id),
+            schedule_type: Set("Lecture".to_string()),
+            day_of_week: Set(0),
+            start_time: Set("10:00".to_string()),
+            end_time: Set("12:00".to_string()),
+            start_date: Set("2024-10-01".to_string()),
+            end_date: Set("2025-02-28".to_string()),
+            priority: Set(0),
+            created_at: Set(chrono::Utc::now()),
+            updated_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        (db, temp_dir, course.id)
+    }
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_next_switch() {
+        let (db, _temp_dir, course_id) = setup_weekly_course().await;
+        let window = Duration::minutes(10);
+        let week = Duration::days(7);
+
+        // Sunday evening: the Monday lecture is next
+        let next = ScheduleEngine::next_switch(&db, at("2024-10-06", "20:00"), window, None, week)
+            .await
+            .unwrap();
+        assert_eq!(next, Some((at("2024-10-07", "09:50"), Some(course_id))));
+
+        // During the lecture: switching away at its end
+        let next = ScheduleEngine::next_switch(
+            &db,
+            at("2024-10-07", "11:00"),
+            window,
+            Some(course_id),
+            week,
+        )
+        .await
+        .unwrap();
+        assert_eq!(next, Some((at("2024-10-07", "12:00"), None)));
+
+        // Nothing within a day
+        let next = ScheduleEngine::next_switch(
+            &db,
+            at("2024-10-07", "13:00"),
+            window,
+            None,
+            Duration::days(1),
+        )
+        .await
+        .unwrap();
+        assert_eq!(next, None);
+    }
+
+    #[tokio::test]
+    async fn test_next_slot_start() {
+        let (db, _temp_dir, _course_id) = setup_weekly_course().await;
+        let window = Duration::minutes(10);
+        let week = Duration::days(7);
+
+        // Before the lecture: its switch window
+        let next = ScheduleEngine::next_slot_start(&db, at("2024-10-07", "08:00"), window, week)
+            .await
+            .unwrap();
+        assert_eq!(next, Some(at("2024-10-07", "09:50")));
+
+        // During the lecture: the end is skipped, next week's lecture is next
+        let next = ScheduleEngine::next_slot_start(&db, at("2024-10-07", "11:00"), window, week)
+            .await
+            .unwrap();
+        assert_eq!(next, Some(at("2024-10-14", "09:50")));
+
+        let next = ScheduleEngine::next_slot_start(
+            &db,
+            at("2024-10-07", "11:00"),
+            window,
+            Duration::days(1),
+        )
+        .await
+        .unwrap();
+        assert_eq!(next, None);
+    }
+
+    #[tokio::test]
+    async fn test_next_boundary() {
+        let (db, _temp_dir, _course_id) = setup_weekly_course().await;
+        let window = Duration::minutes(10);
+
+        // Monday 2024-10-07: switch window opens at 09:50
+        let next = ScheduleEngine::next_boundary(&db, at("2024-10-07", "08:00"), window)
+            .await
+            .unwrap();
+        assert_eq!(next, at("2024-10-07", "09:50"));
+
+        let next = ScheduleEngine::next_boundary(&db, at("2024-10-07", "09:50"), window)
+            .await
+            .unwrap();
+        assert_eq!(next, at("2024-10-07", "10:00"));
+
+        let next = ScheduleEngine::next_boundary(&db, at("2024-10-07", "10:30"), window)
+            .await
+            .unwrap();
+        assert_eq!(next, at("2024-10-07", "12:00"));
+
+        // Nothing left today - wake up at midnight
+        let next = ScheduleEngine::next_boundary(&db, at("2024-10-07", "12:00"), window)
+            .await
+            .unwrap();
+        assert_eq!(next, at("2024-10-08", "00:00"));
+
+        // Late evening: midnight comes first
+        let next =
+            ScheduleEngine::next_boundary(&db, at("2024-10-06", "23:55"), Duration::minutes(10))
+                .await
+                .unwrap();
+        assert_eq!(next, at("2024-10-07", "00:00"));
+    }
+
+    #[tokio::test]
+    async fn test_switch_window() {
+        let (db, _temp_dir, course_id) = setup_weekly_course().await;
+        let window = Duration::minutes(10);
+
+        let before_window = ScheduleEngine::determine_active_course_with_window(
+            &db,
+            at("2024-10-07", "09:45"),
+            window,
+        )
+        .await
+        .unwrap();
+        assert_eq!(before_window, None);
+
+        let in_window = ScheduleEngine::determine_active_course_with_window(
+            &db,
+            at("2024-10-07", "09:55"),
+            window,
+        )
+        .await
+        .unwrap();
+        assert_eq!(in_window, Some(course_id));
+    }
 }
@@ -0,0 +1,349 @@
+//! Lecture sessions
+//!
+//! While a lecture is running the daemon can keep its note open: the note is
+//! created from the configured template, a watch process compiles it and the
+//! compiled PDF is shown in the configured viewer. Watcher and viewer are
+//! stopped when the slot ends; the editor is left open so no work is lost.
+
+use chrono::NaiveDateTime;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::{Child, Command};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::config::settings::NotesConfig;
+use crate::error::Result;
+use crate::notes::{self, LectureNote};
+use crate::service::hooks::HookContext;
+use crate::service::scheduler::Occurrence;
+
+/// How often a process waiting for its file checks whether it exists
+const FILE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long a process may take to exit after SIGTERM before it is killed
+const TERMINATE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// ==================================================================================
+// Session
+// ==================================================================================
+
+/// Note, watcher and viewer opened for a running lecture
+pub struct LectureSession {
+    pub course_id: i64,
+    /// End of the lecture slot
+    pub ends_at: NaiveDateTime,
+    processes: Vec<ManagedProcess>,
+}
+
+impl LectureSession {
+    /// Whether `config` asks for anything to be opened at lecture start
+    pub fn is_enabled(config: &NotesConfig) -> bool {
+        config.auto_open || config.auto_watch || config.auto_open_pdf
+    }
+
+    /// Open the note of a lecture occurrence
+    ///
+    /// This function performs the following steps:
+    /// 1. Creates the note from the configured template unless it exists
+    /// 2. Opens it in `editor` (if `auto_open`)
+    /// 3. Starts `watch_command` (if `auto_watch`)
+    /// 4. Opens the PDF in `pdf_viewer` once it exists (if `auto_open_pdf`
+    ///    and a viewer is set)
+    pub fn start(
+        config: &NotesConfig,
+        editor: Option<&str>,
+        pdf_viewer: Option<&str>,
+        student_name: Option<&str>,
+        context: &HookContext,
+        occurrence: &Occurrence,
+    ) -> Result<Self> {
+        let note = LectureNote {
+            course_dir: PathBuf::from(context.course_dir.clone().unwrap_or_default()),
+            course_code: context.course.clone().unwrap_or_default(),
+            course_name: context.course_name.clone().unwrap_or_default(),
+            semester: context.semester.clone().unwrap_or_default(),
+            number: context.lecture_number.unwrap_or(1),
+            date: occurrence.date,
+        };
+        let source = note.source_path();
+        let pdf = note.pdf_path();
+
+        let template = notes::load_template(&config.template)?;
+        if notes::create_lecture_note(&note, &template, student_name)? {
+            info!(event = "note", file = %source.display(), "Created lecture note");
+        }
+
+        let mut session = Self {
+            course_id: occurrence.course_id,
+            ends_at: occurrence.date.and_time(occurrence.end_time),
+            processes: Vec::new(),
+        };
+
+        if config.auto_open {
+            match editor {
+                Some(editor) => open_detached("editor", &with_file(editor, &source)),
+                None => warn!("notes.auto_open is set but general.default_editor is not"),
+            }
+        }
+
+        if config.auto_watch {
+            let command = config
+                .watch_command
+                .replace("{input}", &shell_quote(&source))
+                .replace("{output}", &shell_quote(&pdf));
+            session
+                .processes
+                .push(ManagedProcess::spawn("watcher", command, None));
+        }
+
+        // Openers like xdg-open hand the file over and exit, so the viewer
+        // must be a program that can be closed when the lecture ends
+        if config.auto_open_pdf {
+            match pdf_viewer {
+                Some(viewer) => session.processes.push(ManagedProcess::spawn(
+                    "viewer",
+                    with_file(viewer, &pdf),
+                    Some(pdf),
+                )),
+                None => warn!("notes.auto_open_pdf is set but general.default_pdf_viewer is not"),
+            }
+        }
+
+        Ok(session)
+    }
+
+    /// Stop the watcher and viewer of the session
+    pub async fn stop(self) {
+        for process in self.processes {
+            process.stop().await;
+        }
+    }
+}
+
+// ==================================================================================
+// Processes
+// ==================================================================================
+
+/// A process owned by a session, stopped together with it
+struct ManagedProcess {
+    name: &'static str,
+    stop: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl ManagedProcess {
+    /// Run `command` with `sh -c`, optionally only once `wait_for` exists
+    fn spawn(name: &'static str, command: String, wait_for: Option<PathBuf>) -> Self {
+        let (stop, mut stopped) = oneshot::channel();
+
+        let task = tokio::spawn(async move {
+            if let Some(path) = wait_for {
+                tokio::select! {
+                    _ = wait_for_file(&path) => {}
+                    _ = &mut stopped => return,
+                }
+            }
+
+            let mut child = match shell(&command).kill_on_drop(true).spawn() {
+                Ok(child) => child,
+                Err(e) => {
+                    warn!(process = name, command, "Failed to start: {}", e);
+                    return;
+                }
+            };
+            debug!(process = name, command, "Started");
+
+            tokio::select! {
+                status = child.wait() => match status {
+                    Ok(status) if status.success() => debug!(process = name, "Exited"),
+                    Ok(status) => warn!(process = name, command, %status, "Exited unexpectedly"),
+                    Err(e) => warn!(process = name, command, "Failed: {}", e),
+                },
+                _ = &mut stopped => terminate(&mut child, name).await,
+            }
+        });
+
+        Self { name, stop, task }
+    }
+
+    /// Stop the process and wait until it is gone
+    async fn stop(self) {
+        // The task may already have finished if the process exited on its own
+        let _ = self.stop.send(());
+        if let Err(e) = self.task.await {
+            warn!(process = self.name, "Failed to stop: {}", e);
+        }
+    }
+}
+
+/// Ask a process (and everything it started) to exit, killing it after a timeout
+async fn terminate(child: &mut Child, name: &str) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        use nix::sys::signal::{Signal, killpg};
+        use nix::unistd::Pid;
+
+        // Each managed process leads its own process group (see `shell`)
+        let _ = killpg(Pid::from_raw(pid as i32), Signal::SIGTERM);
+        if tokio::time::timeout(TERMINATE_TIMEOUT, child.wait())
+            .await
+            .is_ok()
+        {
+            debug!(process = name, "Stopped");
+            return;
+        }
+    }
+
+    let _ = child.kill().await;
+    debug!(process = name, "Killed");
+}
+
+/// Wait until a file exists
+async fn wait_for_file(path: &Path) {
+    while !path.exists() {
+        tokio::time::sleep(FILE_POLL_INTERVAL).await;
+    }
+}
+
+/// Start a program that keeps running on its own (e.g. the editor)
+fn open_detached(name: &str, command: &str) {
+    match shell(command).spawn() {
+        Ok(_) => debug!(process = name, command, "Opened"),
+        Err(e) => warn!(process = name, command, "Failed to open: {}", e),
+    }
+}
+
+/// A `sh -c` command in its own process group, detached from the daemon's stdio
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    #[cfg(unix)]
+    cmd.process_group(0);
+    cmd
+}
+
+/// Put the file into a program command, at `{file}` or appended
+fn with_file(program: &str, file: &Path) -> String {
+    let file = shell_quote(file);
+    if program.contains("{file}") {
+        program.replace("{file}", &file)
+    } else {
+        format!("{} {}", program, file)
+    }
+}
+
+/// Quote a path for `sh`
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', r"'\''"))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, NaiveTime};
+    use tempfile::TempDir;
+
+    fn config() -> NotesConfig {
+        NotesConfig {
+            auto_watch: true,
+            auto_open_pdf: true,
+            template: "default".to_string(),
+            auto_open: false,
+            watch_command: String::new(),
+        }
+    }
+
+    fn occurrence() -> Occurrence {
+        Occurrence {
+            course_id: 1,
+            schedule_id: 1,
+            schedule_type: "Lecture".to_string(),
+            date: NaiveDate::from_ymd_opt(2024, 10, 28).unwrap(),
+            start_time: NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            end_time: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+            room: None,
+            building: None,
+            location: None,
+        }
+    }
+
+    /// Wait until a file exists, failing after a few seconds
+    async fn expect_file(path: &Path) {
+        tokio::time::timeout(Duration::from_secs(10), wait_for_file(path))
+            .await
+            .unwrap_or_else(|_| panic!("{} was not created", path.display()));
+    }
+
+    #[tokio::test]
+    async fn test_session_lifecycle() {
+        let temp_dir = TempDir::new().unwrap();
+        let course_dir = temp_dir.path().join("cs101");
+        let context = HookContext {
+            course: Some("cs101".to_string()),
+            course_name: Some("Algorithms".to_string()),
+            course_dir: Some(course_dir.to_string_lossy().to_string()),
+            semester: Some("b3".to_string()),
+            lecture_number: Some(4),
+            ..Default::default()
+        };
+
+        // The "watcher" compiles once and keeps running, the "viewer" records what it opened
+        let viewer_log = temp_dir.path().join("viewer");
+        let config = NotesConfig {
+            watch_command: "cp {input} {output} && sleep 60".to_string(),
+            ..config()
+        };
+        let viewer = format!("echo {{file}} > '{}' && sleep 60", viewer_log.display());
+
+        let session =
+            LectureSession::start(&config, None, Some(&viewer), None, &context, &occurrence())
+                .unwrap();
+        assert_eq!(session.ends_at.to_string(), "2024-10-28 12:00:00");
+
+        let pdf = course_dir.join("notes/build/lecture_04.pdf");
+        assert!(course_dir.join("notes/lectures/lecture_04.typ").exists());
+        expect_file(&pdf).await;
+        expect_file(&viewer_log).await;
+        // Written by echo once the viewer started
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(
+            std::fs::read_to_string(&viewer_log).unwrap().trim(),
+            pdf.to_string_lossy()
+        );
+
+        let started = std::time::Instant::now();
+        session.stop().await;
+        assert!(started.elapsed() < TERMINATE_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn test_viewer_waits_for_pdf() {
+        let temp_dir = TempDir::new().unwrap();
+        let context = HookContext {
+            course_dir: Some(temp_dir.path().to_string_lossy().to_string()),
+            lecture_number: Some(1),
+            ..Default::default()
+        };
+        let viewer_log = temp_dir.path().join("viewer");
+        let config = NotesConfig {
+            auto_watch: false,
+            ..config()
+        };
+        let viewer = format!("touch '{}' #", viewer_log.display());
+
+        let session =
+            LectureSession::start(&config, None, Some(&viewer), None, &context, &occurrence())
+                .unwrap();
+
+        // Nothing compiles the note, so the viewer never starts
+        tokio::time::sleep(Duration::from_millis(800)).await;
+        assert!(!viewer_log.exists());
+        session.stop().await;
+    }
+}
@@ -0,0 +1,243 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::error::Result;
+
+/// Longest sleep between wall clock checks where no wall clock timer exists
+#[cfg(not(target_os = "linux"))]
+const CLOCK_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// Which watched files changed since the last call to [`FileWatcher::changed`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Changes {
+    /// The configuration file was written
+    pub config: bool,
+    /// The database or its WAL was written
+    pub database: bool,
+    /// A file in a slides folder was added, written, renamed or removed
+    pub slides: bool,
+}
+
+/// Watches the config file, the database and the slides folders
+///
+/// Files are watched through their parent folder, so editors and tools that
+/// replace a file by renaming a new one over it are noticed as well.
+pub struct FileWatcher {
+    watcher: RecommendedWatcher,
+    events: mpsc::UnboundedReceiver<Vec<PathBuf>>,
+    config: PathBuf,
+    database: Vec<PathBuf>,
+    slide_dirs: Vec<PathBuf>,
+}
+
+impl FileWatcher {
+    /// Watch the given config file and database
+    pub fn new(config: PathBuf, database: PathBuf) -> Result<Self> {
+        let (sender, events) = mpsc::unbounded_channel();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            match event {
+                // Reads (including our own) must not count as changes
+                Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+                    let _ = sender.send(event.paths);
+                }
+                Ok(_) => {}
+                Err(e) => warn!("File watcher error: {}", e),
+            }
+        })
+        .map_err(std::io::Error::other)?;
+
+        let wal = PathBuf::from(format!("{}-wal", database.display()));
+        let mut file_watcher = Self {
+            watcher,
+            events,
+            config,
+            database: vec![database, wal],
+            slide_dirs: Vec::new(),
+        };
+
+        let parents: HashSet<PathBuf> = std::iter::once(&file_watcher.config)
+            .chain(&file_watcher.database)
+            .filter_map(|file| file.parent().map(Path::to_path_buf))
+            .collect();
+        for parent in parents {
+            file_watcher
+                .watcher
+                .watch(&parent, RecursiveMode::NonRecursive)
+                .map_err(std::io::Error::other)?;
+        }
+
+        Ok(file_watcher)
+    }
+
+    /// Replace the watched slides folders
+    ///
+    /// Folders that do not exist (yet) are skipped.
+    pub fn set_slide_dirs(&mut self, dirs: Vec<PathBuf>) {
+        if dirs == self.slide_dirs {
+            return;
+        }
+
+        for dir in &self.slide_dirs {
+            let _ = self.watcher.unwatch(dir);
+        }
+        self.slide_dirs = dirs
+            .into_iter()
+            .filter(
+                |dir| match self.watcher.watch(dir, RecursiveMode::NonRecursive) {
+                    Ok(()) => true,
+                    Err(e) => {
+                        if dir.exists() {
+                            warn!(dir = %dir.display(), "Cannot watch slides folder: {}", e);
+                        }
+                        false
+                    }
+                },
+            )
+            .collect();
+    }
+
+    /// Wait until a watched file changes
+    ///
+    /// Collects all events already queued, so a burst of writes is reported
+    /// once. Cancel safe.
+    pub async fn changed(&mut self) -> Changes {
+        let mut changes = Changes::default();
+        loop {
+            let Some(paths) = self.events.recv().await else {
+                return std::future::pending().await;
+            };
+            self.classify(&paths, &mut changes);
+            while let Ok(paths) = self.events.try_recv() {
+                self.classify(&paths, &mut changes);
+            }
+            if changes != Changes::default() {
+                return changes;
+            }
+        }
+    }
+
+    /// Record which kind of watched file the given paths belong to
+    fn classify(&self, paths: &[PathBuf], changes: &mut Changes) {
+        for path in paths {
+            if *path == self.config {
+                changes.config = true;
+            } else if self.database.contains(path) {
+                changes.database = true;
+            } else if self
+                .slide_dirs
+                .iter()
+                .any(|dir| path.parent() == Some(dir.as_path()))
+            {
+                changes.slides = true;
+            }
+        }
+    }
+}
+
+/// Sleep until the wall clock reaches `at`
+///
+/// Unlike `tokio::time::sleep`, whose monotonic clock stands still during a
+/// suspend, this wakes right after resuming when `at` passed in the meantime,
+/// and early when the system clock is set (the caller re-checks the time).
+#[cfg(target_os = "linux")]
+pub async fn sleep_until(at: chrono::NaiveDateTime) {
+    if let Err(e) = wall_clock_timer(at).await {
+        warn!("Wall clock timer unavailable: {}", e);
+        let remaining = (at - chrono::Local::now().naive_local())
+            .to_std()
+            .unwrap_or_default();
+        tokio::time::sleep(remaining).await;
+    }
+}
+
+/// Wait for an absolute `CLOCK_REALTIME` timer that is cancelled on clock changes
+#[cfg(target_os = "linux")]
+async fn wall_clock_timer(at: chrono::NaiveDateTime) -> std::io::Result<()> {
+    use chrono::TimeZone;
+    use nix::sys::time::TimeSpec;
+    use nix::sys::timerfd::{ClockId, Expiration, TimerFd, TimerFlags, TimerSetTimeFlags};
+    use std::os::fd::{AsFd, AsRawFd};
+    use tokio::io::unix::AsyncFd;
+
+    let at = chrono::Local
+        .from_local_datetime(&at)
+        .earliest()
+        .map(|at| at.with_timezone(&chrono::Utc))
+        .unwrap_or_else(|| at.and_utc());
+
+    let timer = TimerFd::new(
+        ClockId::CLOCK_REALTIME,
+        TimerFlags::TFD_NONBLOCK | TimerFlags::TFD_CLOEXEC,
+    )?;
+    // A zero expiration would disarm the timer instead of firing at once
+    let expiration = TimeSpec::new(at.timestamp().max(1), at.timestamp_subsec_nanos().into());
+    timer.set(
+        Expiration::OneShot(expiration),
+        TimerSetTimeFlags::TFD_TIMER_ABSTIME | TimerSetTimeFlags::TFD_TIMER_CANCEL_ON_SET,
+    )?;
+
+    // Readable on expiry and on a clock change - either way the caller re-checks
+    let fd = AsyncFd::new(timer.as_fd().as_raw_fd())?;
+    let _ = fd.readable().await?;
+    Ok(())
+}
+
+/// Sleep until the wall clock reaches `at`
+///
+/// Without a wall clock timer the sleep is capped, so a suspend delays the
+/// wake up by at most [`CLOCK_CHECK_INTERVAL`] (the caller re-checks the time).
+#[cfg(not(target_os = "linux"))]
+pub async fn sleep_until(at: chrono::NaiveDateTime) {
+    let remaining = (at - chrono::Local::now().naive_local())
+        .to_std()
+        .unwrap_or_default()
+        .min(CLOCK_CHECK_INTERVAL);
+    tokio::time::sleep(remaining).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_changes_are_classified_by_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = temp_dir.path().join("config.toml");
+        let database = temp_dir.path().join("mms.db");
+        let slides = temp_dir.path().join("slides");
+        std::fs::create_dir(&slides).unwrap();
+
+        let mut watcher = FileWatcher::new(config.clone(), database.clone()).unwrap();
+        watcher.set_slide_dirs(vec![slides.clone()]);
+
+        std::fs::write(temp_dir.path().join("unrelated.txt"), "x").unwrap();
+        std::fs::write(slides.join("01.pdf"), "x").unwrap();
+        let changes = tokio::time::timeout(Duration::from_secs(5), watcher.changed())
+            .await
+            .unwrap();
+        assert!(changes.slides);
+        assert!(!changes.config);
+
+        // Late events of the slide write may still be queued
+        std::fs::write(&config, "x").unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !watcher.changed().await.config {}
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sleep_until_past_returns_immediately() {
+        let past = chrono::Local::now().naive_local() - chrono::Duration::minutes(1);
+        tokio::time::timeout(Duration::from_secs(1), sleep_until(past))
+            .await
+            .unwrap();
+    }
+}
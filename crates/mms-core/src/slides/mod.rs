@@ -0,0 +1,8 @@
+pub mod operations;
+pub mod pdf;
+
+pub use operations::{
+    SlideScan, scan_course_slides, scan_semester_slides, semester_slides_dirs, slide_number,
+    slides_dir,
+};
+pub use pdf::page_count;
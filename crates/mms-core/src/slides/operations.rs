@@ -0,0 +1,381 @@
+use crate::db::entities::{courses, slides};
+use crate::db::queries;
+use crate::error::Result;
+use crate::slides::pdf;
+use chrono::{DateTime, Utc};
+use sea_orm::{DatabaseConnection, IntoActiveModel, Set};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Changes found by a slide scan (file names)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlideScan {
+    pub added: Vec<String>,
+    /// Files whose content changed
+    pub updated: Vec<String>,
+    /// Files that were renamed or moved, as (old, new)
+    pub renamed: Vec<(String, String)>,
+    pub removed: Vec<String>,
+}
+
+impl SlideScan {
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.updated.is_empty()
+            && self.renamed.is_empty()
+            && self.removed.is_empty()
+    }
+}
+
+/// A PDF found in a slides folder
+struct SlideFile {
+    path: PathBuf,
+    name: String,
+    modified_at: Option<DateTime<Utc>>,
+}
+
+/// Contents of a slide file
+struct SlideContent {
+    hash: String,
+    page_count: Option<i64>,
+}
+
+impl SlideContent {
+    fn read(path: &Path) -> Result<Self> {
+        let data = fs::read(path)?;
+        Ok(Self {
+            hash: format!("{:x}", Sha256::digest(&data)),
+            page_count: pdf::page_count(&data),
+        })
+    }
+
+    /// Read a slide file, logging and skipping files that cannot be read
+    fn read_or_warn(path: &Path) -> Option<Self> {
+        match Self::read(path) {
+            Ok(content) => Some(content),
+            Err(e) => {
+                warn!(file = %path.display(), "Skipping unreadable slide file: {}", e);
+                None
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Paths
+// ============================================================================
+
+/// Folder holding a course's slides
+pub fn slides_dir(course_dir: &Path) -> PathBuf {
+    course_dir.join("slides")
+}
+
+/// Slide number from names like `01.pdf` or `03-graphs.pdf`
+pub fn slide_number(file_name: &str) -> Option<i64> {
+    let digits: String = file_name
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+// ============================================================================
+// Scanning
+// ============================================================================
+
+/// Bring the `slides` rows of a course in line with its `slides/` folder
+///
+/// This function performs the following steps:
+/// 1. Lists the PDFs in `<course>/slides`
+/// 2. Skips files whose modification time matches their row
+/// 3. Hashes new and modified files and counts their pages
+/// 4. Treats a new file with the hash of a vanished file as a rename, keeping
+///    the row (and its coverage)
+/// 5. Inserts the remaining new files and deletes rows of vanished files
+///
+/// Nothing is written with `dry_run`. Rows are left alone if the folder does
+/// not exist (e.g. the course was moved).
+pub async fn scan_course_slides(
+    db: &DatabaseConnection,
+    course: &courses::Model,
+    dry_run: bool,
+) -> Result<SlideScan> {
+    let dir = slides_dir(Path::new(&course.directory_path));
+    let mut scan = SlideScan::default();
+    if !dir.is_dir() {
+        return Ok(scan);
+    }
+
+    let files = list_slide_files(&dir)?;
+    let mut rows: HashMap<String, slides::Model> = queries::slides::list_by_course(db, course.id)
+        .await?
+        .into_iter()
+        .map(|row| (row.file_path.clone(), row))
+        .collect();
+
+    // Rows without a file are candidates for renames
+    let file_paths: Vec<String> = files
+        .iter()
+        .map(|f| f.path.to_string_lossy().to_string())
+        .collect();
+    let mut vanished: Vec<slides::Model> = rows
+        .extract_if(|path, _| !file_paths.contains(path))
+        .map(|(_, row)| row)
+        .collect();
+
+    let now = Utc::now();
+    let mut new_files = Vec::new();
+
+    for file in files {
+        let path = file.path.to_string_lossy().to_string();
+        let Some(row) = rows.remove(&path) else {
+            new_files.push(file);
+            continue;
+        };
+        if row.file_modified_at == file.modified_at && row.file_hash.is_some() {
+            continue;
+        }
+
+        let Some(content) = SlideContent::read_or_warn(&file.path) else {
+            continue;
+        };
+        if row.file_hash.as_deref() != Some(content.hash.as_str()) {
+            scan.updated.push(file.name.clone());
+        }
+        if !dry_run {
+            let mut active = row.into_active_model();
+            active.file_hash = Set(Some(content.hash));
+            active.page_count = Set(content.page_count);
+            active.file_modified_at = Set(file.modified_at);
+            active.scanned_at = Set(now);
+            active.updated_at = Set(now);
+            queries::slides::update(db, active).await?;
+        }
+    }
+
+    for file in new_files {
+        let Some(content) = SlideContent::read_or_warn(&file.path) else {
+            continue;
+        };
+        let renamed_from = vanished
+            .iter()
+            .position(|row| row.file_hash.as_deref() == Some(content.hash.as_str()))
+            .map(|index| vanished.swap_remove(index));
+
+        match renamed_from {
+            Some(row) => {
+                scan.renamed
+                    .push((row.file_name.clone(), file.name.clone()));
+                if !dry_run {
+                    let mut active = row.into_active_model();
+                    active.file_name = Set(file.name.clone());
+                    active.file_path = Set(file.path.to_string_lossy().to_string());
+                    active.slide_number = Set(slide_number(&file.name));
+                    active.page_count = Set(content.page_count);
+                    active.file_modified_at = Set(file.modified_at);
+                    active.scanned_at = Set(now);
+                    active.updated_at = Set(now);
+                    queries::slides::update(db, active).await?;
+                }
+            }
+            None => {
+                scan.added.push(file.name.clone());
+                if !dry_run {
+                    let slide = slides::ActiveModel {
+                        course_id: Set(course.id),
+                        file_name: Set(file.name.clone()),
+                        file_path: Set(file.path.to_string_lossy().to_string()),
+                        file_hash: Set(Some(content.hash)),
+                        slide_number: Set(slide_number(&file.name)),
+                        page_count: Set(content.page_count),
+                        is_covered: Set(false),
+                        file_modified_at: Set(file.modified_at),
+                        scanned_at: Set(now),
+                        updated_at: Set(now),
+                        ..Default::default()
+                    };
+                    queries::slides::insert(db, slide).await?;
+                }
+            }
+        }
+    }
+
+    for row in vanished {
+        scan.removed.push(row.file_name.clone());
+        if !dry_run {
+            queries::slides::delete(db, row.id).await?;
+        }
+    }
+
+    Ok(scan)
+}
+
+/// Scan the slides of all courses of the current semester
+///
+/// Returns the courses whose slides changed.
+pub async fn scan_semester_slides(
+    db: &DatabaseConnection,
+    dry_run: bool,
+) -> Result<Vec<(courses::Model, SlideScan)>> {
+    let Some(semester) = queries::semester::get_current(db).await? else {
+        return Ok(Vec::new());
+    };
+
+    let mut changed = Vec::new();
+    for course in queries::course::list_unarchived_by_semester(db, semester.id).await? {
+        let scan = scan_course_slides(db, &course, dry_run).await?;
+        if !scan.is_empty() {
+            changed.push((course, scan));
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Slides folders of the courses of the current semester
+pub async fn semester_slides_dirs(db: &DatabaseConnection) -> Result<Vec<PathBuf>> {
+    let Some(semester) = queries::semester::get_current(db).await? else {
+        return Ok(Vec::new());
+    };
+
+    Ok(
+        queries::course::list_unarchived_by_semester(db, semester.id)
+            .await?
+            .iter()
+            .map(|course| slides_dir(Path::new(&course.directory_path)))
+            .collect(),
+    )
+}
+
+/// PDFs directly inside `dir`, sorted by name
+fn list_slide_files(dir: &Path) -> Result<Vec<SlideFile>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_pdf = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"));
+        if !is_pdf || !path.is_file() {
+            continue;
+        }
+
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        // Truncated to microseconds so the value survives the database round trip
+        let modified_at = fs::metadata(&path)?
+            .modified()
+            .ok()
+            .map(DateTime::<Utc>::from)
+            .and_then(|at| DateTime::from_timestamp_micros(at.timestamp_micros()));
+
+        files.push(SlideFile {
+            path,
+            name,
+            modified_at,
+        });
+    }
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::semester_with_course;
+    use tempfile::TempDir;
+
+    async fn setup() -> (DatabaseConnection, TempDir, courses::Model) {
+        let temp_dir = TempDir::new().unwrap();
+        let (db, course) = semester_with_course(temp_dir.path(), "algo", "Algorithms").await;
+        let course = queries::course::get_by_id(&db, course.id).await.unwrap();
+        fs::create_dir_all(slides_dir(Path::new(&course.directory_path))).unwrap();
+
+        (db, temp_dir, course)
+    }
+
+    fn write_pdf(course: &courses::Model, name: &str, pages: i64) {
+        let pdf = pdf::pdf_with_pages(pages as usize);
+        fs::write(
+            slides_dir(Path::new(&course.directory_path)).join(name),
+            pdf,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_slide_number() {
+        assert_eq!(slide_number("01.pdf"), Some(1));
+        assert_eq!(slide_number("12-graphs.pdf"), Some(12));
+        assert_eq!(slide_number("intro.pdf"), None);
+    }
+
+    #[tokio::test]
+    async fn test_scan_course_slides() {
+        let (db, _temp_dir, course) = setup().await;
+        write_pdf(&course, "01.pdf", 20);
+        write_pdf(&course, "02.pdf", 35);
+        fs::write(
+            slides_dir(Path::new(&course.directory_path)).join("notes.txt"),
+            "",
+        )
+        .unwrap();
+
+        // A dry run reports without writing
+        let scan = scan_course_slides(&db, &course, true).await.unwrap();
+        assert_eq!(scan.added, vec!["01.pdf", "02.pdf"]);
+        assert!(
+            queries::slides::list_by_course(&db, course.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let scan = scan_course_slides(&db, &course, false).await.unwrap();
+        assert_eq!(scan.added, vec!["01.pdf", "02.pdf"]);
+        let rows = queries::slides::list_by_course(&db, course.id)
+            .await
+            .unwrap();
+        assert_eq!(rows[0].slide_number, Some(1));
+        assert_eq!(rows[0].page_count, Some(20));
+        assert_eq!(rows[1].page_count, Some(35));
+        assert_eq!(rows[0].file_hash.as_ref().map(|h| h.len()), Some(64));
+
+        // Unchanged files are skipped
+        let scan = scan_course_slides(&db, &course, false).await.unwrap();
+        assert!(scan.is_empty());
+
+        // Rename keeps the row, removal deletes it
+        let mut covered = rows[0].clone().into_active_model();
+        covered.is_covered = Set(true);
+        queries::slides::update(&db, covered).await.unwrap();
+        let dir = slides_dir(Path::new(&course.directory_path));
+        fs::rename(dir.join("01.pdf"), dir.join("03-intro.pdf")).unwrap();
+        fs::remove_file(dir.join("02.pdf")).unwrap();
+
+        let scan = scan_course_slides(&db, &course, false).await.unwrap();
+        assert_eq!(
+            scan.renamed,
+            vec![("01.pdf".to_string(), "03-intro.pdf".to_string())]
+        );
+        assert_eq!(scan.removed, vec!["02.pdf"]);
+        assert!(scan.added.is_empty());
+
+        let rows = queries::slides::list_by_course(&db, course.id)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].file_name, "03-intro.pdf");
+        assert_eq!(rows[0].slide_number, Some(3));
+        assert!(rows[0].is_covered);
+    }
+}
@@ -0,0 +1,64 @@
+use lopdf::Document;
+#[cfg(test)]
+use lopdf::{Object, dictionary};
+
+/// Count the pages of a PDF
+///
+/// Returns `None` if the data cannot be parsed as a PDF (e.g. truncated or
+/// encrypted with a password).
+pub fn page_count(data: &[u8]) -> Option<i64> {
+    let document = Document::load_mem(data).ok()?;
+    Some(document.get_pages().len() as i64)
+}
+
+/// A PDF with `pages` empty pages
+#[cfg(test)]
+pub(crate) fn pdf_with_pages(pages: usize) -> Vec<u8> {
+    let mut document = Document::with_version("1.5");
+    let pages_id = document.new_object_id();
+    let kids: Vec<Object> = (0..pages)
+        .map(|_| {
+            document
+                .add_object(dictionary! {
+                    "Type" => "Page",
+                    "Parent" => pages_id,
+                    "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+                })
+                .into()
+        })
+        .collect();
+    document.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => pages as i64,
+        }),
+    );
+    let catalog_id = document.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    document.trailer.set("Root", catalog_id);
+
+    let mut data = Vec::new();
+    document.save_to(&mut data).unwrap();
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_count() {
+        assert_eq!(page_count(&pdf_with_pages(12)), Some(12));
+        assert_eq!(page_count(&pdf_with_pages(1)), Some(1));
+    }
+
+    #[test]
+    fn test_not_a_pdf() {
+        assert_eq!(page_count(b"hello world"), None);
+        assert_eq!(page_count(b""), None);
+    }
+}
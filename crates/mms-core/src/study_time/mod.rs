@@ -0,0 +1,3 @@
+pub mod operations;
+
+pub use operations::{DEFAULT_HOURS_PER_ECTS, WeeklyStudyTime, study_time_report};
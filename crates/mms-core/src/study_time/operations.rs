@@ -0,0 +1,423 @@
+use crate::db::entities::activation_intervals;
+use crate::db::queries;
+use crate::error::Result;
+use crate::service::ScheduleEngine;
+use crate::utils::date_validation::parse_german_date;
+use chrono::{Datelike, Days, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use sea_orm::DatabaseConnection;
+use std::collections::HashMap;
+
+/// Default expected workload per ECTS credit in hours
+pub const DEFAULT_HOURS_PER_ECTS: f64 = 30.0;
+
+/// Semester length in weeks if the semester has no start/end date
+const DEFAULT_SEMESTER_WEEKS: i64 = 26;
+
+/// How long before a slot an interval may start and still belong to it
+const MAX_EARLY_SWITCH: chrono::Duration = chrono::Duration::hours(1);
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Time a course was active during one week, compared with its expected workload
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeeklyStudyTime {
+    /// Monday of the week
+    pub week_start: NaiveDate,
+    pub course_id: i64,
+    pub short_name: String,
+    pub name: String,
+    /// Hours the course was active
+    pub hours: f64,
+    /// Expected weekly workload derived from the course's ECTS
+    pub expected_hours: f64,
+}
+
+impl WeeklyStudyTime {
+    /// Share of the expected workload actually spent (1.0 = on target)
+    pub fn ratio(&self) -> Option<f64> {
+        if self.expected_hours <= 0.0 {
+            return None;
+        }
+        Some(self.hours / self.expected_hours)
+    }
+}
+
+// ============================================================================
+// Report
+// ============================================================================
+
+/// Build a per-week, per-course study-time report for a semester
+///
+/// This function performs the following steps:
+/// 1. Loads all activation intervals overlapping the weeks from `from` to `to`
+///    (open intervals are capped, see [`open_interval_end`])
+/// 2. Splits every interval at week boundaries (weeks start on Monday, local time)
+/// 3. Compares the hours with the expected weekly workload:
+///    `ects * hours_per_ects / semester weeks`
+///
+/// Every course of the semester gets a row for every week, so neglected
+/// courses show up with zero hours.
+pub async fn study_time_report(
+    db: &DatabaseConnection,
+    semester_id: i64,
+    from: NaiveDate,
+    to: NaiveDate,
+    hours_per_ects: f64,
+    now: NaiveDateTime,
+) -> Result<Vec<WeeklyStudyTime>> {
+    let semester = queries::semester::get_by_id(db, semester_id).await?;
+    let courses = queries::course::list_unarchived_by_semester(db, semester_id).await?;
+
+    let semester_weeks = match (&semester.start_date, &semester.end_date) {
+        (Some(start), Some(end)) => {
+            let days = (parse_german_date(end)? - parse_german_date(start)?).num_days();
+            ((days + 6) / 7).max(1)
+        }
+        _ => DEFAULT_SEMESTER_WEEKS,
+    };
+
+    let first_week = week_start(from);
+    let last_week = week_start(to);
+    let range_start = first_week.and_hms_opt(0, 0, 0).unwrap_or_default();
+    let range_end = (last_week + Days::new(7))
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default();
+
+    let intervals =
+        queries::activation_intervals::list_overlapping(db, to_utc(range_start), to_utc(range_end))
+            .await?;
+
+    // Seconds per (course, week)
+    let mut seconds: HashMap<(i64, NaiveDate), i64> = HashMap::new();
+    for interval in intervals {
+        let start = interval
+            .started_at
+            .with_timezone(&Local)
+            .naive_local()
+            .max(range_start);
+        let end = match interval.ended_at {
+            Some(ended_at) => ended_at.with_timezone(&Local).naive_local(),
+            None => open_interval_end(db, &interval, now).await?,
+        }
+        .min(range_end);
+
+        let mut cursor = start;
+        while cursor < end {
+            let week = week_start(cursor.date());
+            let week_end = (week + Days::new(7)).and_hms_opt(0, 0, 0).unwrap_or(end);
+            let segment_end = end.min(week_end);
+
+            *seconds.entry((interval.course_id, week)).or_default() +=
+                (segment_end - cursor).num_seconds();
+            cursor = segment_end;
+        }
+    }
+
+    let mut report = Vec::new();
+    let mut week = first_week;
+    while week <= last_week {
+        for course in &courses {
+            let active = seconds.get(&(course.id, week)).copied().unwrap_or(0);
+            report.push(WeeklyStudyTime {
+                week_start: week,
+                course_id: course.id,
+                short_name: course.short_name.clone(),
+                name: course.name.clone(),
+                hours: active as f64 / 3600.0,
+                expected_hours: course.ects as f64 * hours_per_ects / semester_weeks as f64,
+            });
+        }
+        week = week + Days::new(7);
+    }
+
+    Ok(report)
+}
+
+/// Where an interval that is still open stops counting
+///
+/// Without a heartbeat the course may not have been used at all since, so an
+/// open interval counts up to the later of the daemon's last heartbeat and the
+/// end of the slot it was opened for - never up to `now` across a night or a
+/// time the daemon was down.
+async fn open_interval_end(
+    db: &DatabaseConnection,
+    interval: &activation_intervals::Model,
+    now: NaiveDateTime,
+) -> Result<NaiveDateTime> {
+    let local = |at: chrono::DateTime<Utc>| at.with_timezone(&Local).naive_local();
+    let started = local(interval.started_at);
+    let last_seen = interval.last_seen_at.map_or(started, local);
+
+    let slot_end = ScheduleEngine::occurrences_for_course(
+        db,
+        interval.course_id,
+        started.date(),
+        started.date(),
+    )
+    .await?
+    .into_iter()
+    .map(|o| (o.date.and_time(o.start_time), o.date.and_time(o.end_time)))
+    .find(|(start, end)| *start - MAX_EARLY_SWITCH <= started && started < *end)
+    .map(|(_, end)| end);
+
+    Ok(slot_end
+        .map_or(last_seen, |end| end.max(last_seen))
+        .min(now))
+}
+
+/// Monday of the week containing `date`
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Days::new(date.weekday().num_days_from_monday() as u64)
+}
+
+/// Interpret a naive local timestamp as UTC instant
+fn to_utc(local: NaiveDateTime) -> chrono::DateTime<Utc> {
+    Local
+        .from_local_datetime(&local)
+        .earliest()
+        .unwrap_or_else(|| Utc.from_utc_datetime(&local).with_timezone(&Local))
+        .with_timezone(&Utc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::entities::course_schedules;
+    use crate::test_support::{course, semester, test_db};
+    use sea_orm::{ActiveModelTrait, IntoActiveModel, Set};
+    use tempfile::TempDir;
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn day(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    /// Set up a 10-week semester with two courses (6 and 3 ECTS)
+    async fn setup() -> (DatabaseConnection, TempDir, i64, i64, i64) {
+        let temp_dir = TempDir::new().unwrap();
+        let db = test_db().await;
+        let mut semester = semester(&db, &temp_dir.path().join("b3"), 3)
+            .await
+            .into_active_model();
+        semester.start_date = Set(Some("07.10.2024".to_string()));
+        semester.end_date = Set(Some("16.12.2024".to_string()));
+        let semester = semester.update(&db).await.unwrap();
+
+        let algo = course(&db, semester.id, "algo", "ALGO", 6).await;
+        let db_course = course(&db, semester.id, "db", "DB", 3).await;
+
+        (db, temp_dir, semester.id, algo.id, db_course.id)
+    }
+
+    async fn add_interval(
+        db: &DatabaseConnection,
+        course_id: i64,
+        start: NaiveDateTime,
+        end: Option<NaiveDateTime>,
+        last_seen: Option<NaiveDateTime>,
+    ) {
+        activation_intervals::ActiveModel {
+            course_id: Set(course_id),
+            started_at: Set(to_utc(start)),
+            ended_at: Set(end.map(to_utc)),
+            last_seen_at: Set(last_seen.map(to_utc)),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_report_splits_weeks() {
+        let (db, _temp_dir, semester_id, algo, db_course) = setup().await;
+
+        // Sunday 22:00 to Monday 01:00 - two hours in the first week, one in the second
+        add_interval(
+            &db,
+            algo,
+            at("2024-10-13", "22:00"),
+            Some(at("2024-10-14", "01:00")),
+            None,
+        )
+        .await;
+        // Still open - counted up to the last heartbeat
+        add_interval(
+            &db,
+            algo,
+            at("2024-10-15", "10:00"),
+            None,
+            Some(at("2024-10-15", "11:30")),
+        )
+        .await;
+
+        let report = study_time_report(
+            &db,
+            semester_id,
+            day("2024-10-07"),
+            day("2024-10-15"),
+            DEFAULT_HOURS_PER_ECTS,
+            at("2024-10-15", "11:30"),
+        )
+        .await
+        .unwrap();
+
+        // Two weeks x two courses
+        assert_eq!(report.len(), 4);
+
+        let first = &report[0];
+        assert_eq!(first.week_start, day("2024-10-07"));
+        assert_eq!(first.course_id, algo);
+        assert_eq!(first.hours, 2.0);
+        // 6 ECTS * 30h / 10 weeks
+        assert_eq!(first.expected_hours, 18.0);
+
+        let second = &report[2];
+        assert_eq!(second.week_start, day("2024-10-14"));
+        assert_eq!(second.hours, 2.5);
+
+        // Neglected course still shows up
+        let neglected = &report[3];
+        assert_eq!(neglected.course_id, db_course);
+        assert_eq!(neglected.hours, 0.0);
+        assert_eq!(neglected.ratio(), Some(0.0));
+    }
+
+    #[tokio::test]
+    async fn test_open_interval_is_capped() {
+        let (db, _temp_dir, semester_id, algo, db_course) = setup().await;
+
+        // Thursday 08:00-10:00 lecture of algo
+        course_schedules::ActiveModel {
+            course_id: Set(algo),
+            schedule_type: Set("Lecture".to_string()),
+            day_of_week: Set(3),
+            start_time: Set("08:00".to_string()),
+            end_time: Set("10:00".to_string()),
+            start_date: Set("2024-10-07".to_string()),
+            end_date: Set("2024-12-16".to_string()),
+            priority: Set(0),
+            created_at: Set(Utc::now()),
+            updated_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        // Switched to shortly before the lecture, then the daemon went down
+        add_interval(
+            &db,
+            algo,
+            at("2024-10-10", "07:50"),
+            None,
+            Some(at("2024-10-10", "08:30")),
+        )
+        .await;
+        // Set manually in the evening, no heartbeat since
+        add_interval(
+            &db,
+            db_course,
+            at("2024-10-10", "20:00"),
+            None,
+            Some(at("2024-10-10", "21:00")),
+        )
+        .await;
+
+        let report = study_time_report(
+            &db,
+            semester_id,
+            day("2024-10-07"),
+            day("2024-10-07"),
+            DEFAULT_HOURS_PER_ECTS,
+            at("2024-10-11", "09:00"),
+        )
+        .await
+        .unwrap();
+
+        // Up to the slot end, not the next morning
+        assert!((report[0].hours - 2.0 - 10.0 / 60.0).abs() < 1e-9);
+        // Up to the last heartbeat
+        assert_eq!(report[1].hours, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_splits_after_gap() {
+        let (db, _temp_dir, semester_id, algo, _) = setup().await;
+        let gap = chrono::Duration::minutes(30);
+
+        queries::active::set_active_course(&db, algo, semester_id)
+            .await
+            .unwrap();
+        let opened = queries::activation_intervals::get_open(&db)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let seen = opened.started_at + chrono::Duration::minutes(10);
+        queries::activation_intervals::heartbeat(&db, seen, gap)
+            .await
+            .unwrap();
+
+        // Suspended for two hours
+        let resumed = seen + chrono::Duration::hours(2);
+        queries::activation_intervals::heartbeat(&db, resumed, gap)
+            .await
+            .unwrap();
+
+        let intervals = queries::activation_intervals::list_overlapping(
+            &db,
+            opened.started_at,
+            resumed + chrono::Duration::hours(1),
+        )
+        .await
+        .unwrap();
+        assert_eq!(intervals.len(), 2);
+        assert_eq!(intervals[0].ended_at, Some(seen));
+        assert_eq!(intervals[1].started_at, resumed);
+        assert_eq!(intervals[1].course_id, algo);
+        assert_eq!(intervals[1].ended_at, None);
+    }
+
+    #[tokio::test]
+    async fn test_active_course_switches_are_logged() {
+        let (db, _temp_dir, semester_id, algo, db_course) = setup().await;
+
+        queries::active::set_active_course(&db, algo, semester_id)
+            .await
+            .unwrap();
+        // Re-activating the same course keeps the interval open
+        queries::active::set_active_course(&db, algo, semester_id)
+            .await
+            .unwrap();
+        queries::active::set_active_course(&db, db_course, semester_id)
+            .await
+            .unwrap();
+        queries::active::clear_active_course(&db).await.unwrap();
+
+        let intervals = queries::activation_intervals::list_overlapping(
+            &db,
+            Utc::now() - chrono::Duration::hours(1),
+            Utc::now() + chrono::Duration::hours(1),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(intervals.len(), 2);
+        assert_eq!(intervals[0].course_id, algo);
+        assert_eq!(intervals[1].course_id, db_course);
+        assert!(intervals.iter().all(|i| i.ended_at.is_some()));
+        assert!(
+            queries::activation_intervals::get_open(&db)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -1,109 +1,370 @@
 use crate::config::Config;
-use crate::error::Result;
+use crate::config::settings::SymlinksConfig;
+use crate::db::entities::courses;
+use crate::db::queries;
+use crate::error::{MmsError, Result};
+use crate::notes;
 use std::os::unix::fs as unix_fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-/// Create or update the cs (current semester) symlink
-pub fn update_semester_symlink(semester_folder_name: &str) -> Result<()> {
-    let config = Config::load()?;
-    let symlink_path = get_semester_symlink_path(&config)?;
+/// A link in `general.symlink_path`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Link {
+    Semester,
+    Course,
+    LectureNotes,
+    NextExercise,
+    Slides,
+}
 
-    // Target path
-    let target_path = config.university_base_path.join(semester_folder_name);
+impl Link {
+    /// All links
+    pub const ALL: [Link; 5] = [
+        Link::Semester,
+        Link::Course,
+        Link::LectureNotes,
+        Link::NextExercise,
+        Link::Slides,
+    ];
 
-    // Remove existing symlink if it exists
-    if symlink_path.exists() || symlink_path.is_symlink() {
-        std::fs::remove_file(&symlink_path)?;
-    }
+    /// Links following the current course
+    const COURSE_CONTEXT: [Link; 3] = [Link::LectureNotes, Link::NextExercise, Link::Slides];
+}
 
-    // Create new symlink
-    unix_fs::symlink(&target_path, &symlink_path)?;
+/// A configured link and where it points
+#[derive(Debug, Clone)]
+pub struct LinkStatus {
+    pub link: Link,
+    pub path: PathBuf,
+    /// `None` if the link does not exist
+    pub target: Option<PathBuf>,
+    /// The link exists but its target does not
+    pub broken: bool,
+}
 
-    Ok(())
+/// The configured links, resolved once from the config
+#[derive(Debug, Clone)]
+pub struct Symlinks {
+    dir: PathBuf,
+    base_path: PathBuf,
+    names: SymlinksConfig,
 }
 
-/// Create or update the cc (current course) symlink
-pub fn update_course_symlink(semester_folder_name: &str, course_folder_name: &str) -> Result<()> {
-    let config = Config::load()?;
-    let symlink_path = get_course_symlink_path(&config)?;
+impl Symlinks {
+    /// Links of a config
+    ///
+    /// # Errors
+    /// Returns `SymlinkNotSet` without a `[general]` section.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let general = config.general.as_ref().ok_or(MmsError::SymlinkNotSet)?;
+        Ok(Self {
+            dir: general.symlink_path.clone(),
+            base_path: config.university_base_path.clone(),
+            names: config.symlinks.clone().unwrap_or_default(),
+        })
+    }
 
-    // Target path
-    let target_path = config
-        .university_base_path
-        .join(semester_folder_name)
-        .join(course_folder_name);
+    /// Path of a link, `None` if the link is turned off
+    pub fn path(&self, link: Link) -> Option<PathBuf> {
+        let name = match link {
+            Link::Semester => Some(&self.names.semester),
+            Link::Course => Some(&self.names.course),
+            Link::LectureNotes => self.names.lecture_notes.as_ref(),
+            Link::NextExercise => self.names.next_exercise.as_ref(),
+            Link::Slides => self.names.slides.as_ref(),
+        };
+        name.map(|name| self.dir.join(name))
+    }
 
-    // Remove existing symlink if it exists
-    if symlink_path.exists() || symlink_path.is_symlink() {
-        std::fs::remove_file(&symlink_path)?;
+    /// Point a link at `target` (relative to `university_base_path`)
+    ///
+    /// The old link is replaced atomically, so the link never disappears.
+    pub fn set(&self, link: Link, target: &Path) -> Result<()> {
+        match self.path(link) {
+            Some(path) => replace_symlink(&self.base_path.join(target), &path),
+            None => Ok(()),
+        }
     }
 
-    // Create new symlink
-    unix_fs::symlink(&target_path, &symlink_path)?;
+    /// Remove a link if it exists
+    pub fn remove(&self, link: Link) -> Result<()> {
+        if let Some(path) = self.path(link)
+            && path.is_symlink()
+        {
+            std::fs::remove_file(&path)?;
+        }
+        Ok(())
+    }
 
-    Ok(())
-}
+    /// Point the semester and course links at a course
+    pub fn set_course(&self, semester_dir: &Path, course_dir: &Path) -> Result<()> {
+        self.set(Link::Semester, semester_dir)?;
+        self.set(Link::Course, course_dir)
+    }
 
-/// Remove the cs (current semester) symlink
-pub fn remove_semester_symlink() -> Result<()> {
-    let config = Config::load()?;
-    let symlink_path = get_semester_symlink_path(&config)?;
+    /// Remove the course link and the links following it
+    pub fn remove_course(&self) -> Result<()> {
+        self.remove(Link::Course)?;
+        for link in Link::COURSE_CONTEXT {
+            self.remove(link)?;
+        }
+        Ok(())
+    }
+
+    /// Point the lecture notes, exercise and slides links into a course
+    ///
+    /// Links without a matching file or folder are removed.
+    pub async fn set_course_context(
+        &self,
+        db: &sea_orm::DatabaseConnection,
+        course: &courses::Model,
+    ) -> Result<()> {
+        let targets = [
+            (Link::LectureNotes, lecture_notes_target(db, course).await?),
+            (Link::NextExercise, next_exercise_target(db, course).await?),
+            (Link::Slides, slides_target(db, course).await?),
+        ];
+
+        for (link, target) in targets {
+            match target {
+                Some(target) => self.set(link, &target)?,
+                None => self.remove(link)?,
+            }
+        }
 
-    if symlink_path.exists() || symlink_path.is_symlink() {
-        std::fs::remove_file(&symlink_path)?;
+        Ok(())
     }
 
-    Ok(())
+    /// Point links at `to` if they pointed at `from` or into it
+    pub fn relink(&self, from: &Path, to: &Path) -> Result<()> {
+        for path in Link::ALL.iter().filter_map(|link| self.path(*link)) {
+            if !path.is_symlink() {
+                continue;
+            }
+            let target = std::fs::read_link(&path)?;
+            if let Ok(rest) = target.strip_prefix(from) {
+                let target_path = if rest.as_os_str().is_empty() {
+                    to.to_path_buf()
+                } else {
+                    to.join(rest)
+                };
+                replace_symlink(&target_path, &path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove links pointing at `dir` or into it
+    pub fn unlink_inside(&self, dir: &Path) -> Result<()> {
+        for path in Link::ALL.iter().filter_map(|link| self.path(*link)) {
+            if path.is_symlink() && std::fs::read_link(&path)?.starts_with(dir) {
+                std::fs::remove_file(&path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// State of all configured links
+    pub fn check(&self) -> Vec<LinkStatus> {
+        Link::ALL
+            .iter()
+            .filter_map(|link| self.path(*link).map(|path| (*link, path)))
+            .map(|(link, path)| {
+                let target = std::fs::read_link(&path).ok();
+                // exists() follows the link
+                let broken = target.is_some() && !path.exists();
+                LinkStatus {
+                    link,
+                    path,
+                    target,
+                    broken,
+                }
+            })
+            .collect()
+    }
 }
 
-/// Remove the cc (current course) symlink
-pub fn remove_course_symlink() -> Result<()> {
-    let config = Config::load()?;
-    let symlink_path = get_course_symlink_path(&config)?;
+/// Replace `link` with a symlink to `target` without a moment where `link` is missing
+///
+/// The new link is created next to `link` and renamed over it.
+fn replace_symlink(target: &Path, link: &Path) -> Result<()> {
+    let name = link
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp = link.with_file_name(format!(".{}.{}.tmp", name, std::process::id()));
 
-    if symlink_path.exists() || symlink_path.is_symlink() {
-        std::fs::remove_file(&symlink_path)?;
+    if temp.is_symlink() {
+        std::fs::remove_file(&temp)?;
+    }
+    unix_fs::symlink(target, &temp)?;
+    if let Err(e) = std::fs::rename(&temp, link) {
+        let _ = std::fs::remove_file(&temp);
+        return Err(e.into());
     }
 
     Ok(())
 }
 
-/// Get the path where the semester symlink should be created
-fn get_semester_symlink_path(config: &Config) -> Result<PathBuf> {
-    config
-        .general
-        .as_ref()
-        .map(|g| g.symlink_path.join("cs"))
-        .ok_or(crate::error::MmsError::SymlinkNotSet)
+// ============================================================================
+// Course context targets
+// ============================================================================
+
+/// Notes file of the latest lecture up to today
+async fn lecture_notes_target(
+    db: &sea_orm::DatabaseConnection,
+    course: &courses::Model,
+) -> Result<Option<PathBuf>> {
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let course_dir = Path::new(&course.directory_path);
+
+    Ok(queries::lectures::list_by_course(db, course.id)
+        .await?
+        .iter()
+        .rev()
+        .filter(|lecture| lecture.date <= today)
+        .map(|lecture| match &lecture.notes_file_path {
+            Some(path) => PathBuf::from(path),
+            None => notes::lecture_note_path(course_dir, lecture.lecture_number),
+        })
+        .find(|path| path.exists()))
 }
 
-/// Get the path where the course symlink should be created
-fn get_course_symlink_path(config: &Config) -> Result<PathBuf> {
-    config
-        .general
-        .as_ref()
-        .map(|g| g.symlink_path.join("cc"))
-        .ok_or(crate::error::MmsError::SymlinkNotSet)
+/// Folder of the first exercise not submitted yet
+async fn next_exercise_target(
+    db: &sea_orm::DatabaseConnection,
+    course: &courses::Model,
+) -> Result<Option<PathBuf>> {
+    Ok(queries::exercises::list_by_course(db, course.id)
+        .await?
+        .iter()
+        .filter(|exercise| !exercise.is_submitted)
+        .filter_map(|exercise| exercise.solution_directory_path.as_ref())
+        .map(PathBuf::from)
+        .find(|path| path.is_dir()))
 }
 
-/// Check if symlinks are correctly set up
-pub fn check_symlinks() -> Result<(Option<PathBuf>, Option<PathBuf>)> {
-    let config = Config::load()?;
+/// Newest slide deck, by number and then modification time
+async fn slides_target(
+    db: &sea_orm::DatabaseConnection,
+    course: &courses::Model,
+) -> Result<Option<PathBuf>> {
+    Ok(queries::slides::list_by_course(db, course.id)
+        .await?
+        .iter()
+        .max_by_key(|slide| (slide.slide_number, slide.file_modified_at))
+        .map(|slide| PathBuf::from(&slide.file_path))
+        .filter(|path| path.exists()))
+}
 
-    let cs_path = get_semester_symlink_path(&config)?;
-    let cc_path = get_course_symlink_path(&config)?;
+// ============================================================================
+// Config shortcuts
+// ============================================================================
 
-    let cs_target = if cs_path.is_symlink() {
-        Some(std::fs::read_link(&cs_path)?)
-    } else {
-        None
-    };
+/// Point links at `to` if they pointed at `from` or into it (nothing without `[general]`)
+pub fn relink(config: &Config, from: &Path, to: &Path) -> Result<()> {
+    match Symlinks::from_config(config) {
+        Ok(symlinks) => symlinks.relink(from, to),
+        Err(_) => Ok(()),
+    }
+}
 
-    let cc_target = if cc_path.is_symlink() {
-        Some(std::fs::read_link(&cc_path)?)
-    } else {
-        None
-    };
+/// Remove links pointing at `dir` or into it (nothing without `[general]`)
+pub fn unlink_course(config: &Config, dir: &Path) -> Result<()> {
+    match Symlinks::from_config(config) {
+        Ok(symlinks) => symlinks.unlink_inside(dir),
+        Err(_) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::settings::GeneralConfig;
+    use tempfile::TempDir;
+
+    fn symlinks(temp_dir: &TempDir) -> Symlinks {
+        let config = Config {
+            university_base_path: temp_dir.path().join("Studies"),
+            general: Some(GeneralConfig {
+                student_name: None,
+                student_id: None,
+                default_editor: None,
+                default_pdf_viewer: None,
+                default_location: None,
+                symlink_path: temp_dir.path().to_path_buf(),
+                semester_folder: crate::paths::DEFAULT_SEMESTER_FOLDER.to_string(),
+            }),
+            grading: None,
+            notes: None,
+            schedule: None,
+            sync: None,
+            attendance: None,
+            study_time: None,
+            notifications: None,
+            hooks: None,
+            logging: None,
+            symlinks: Some(SymlinksConfig {
+                course: "current".to_string(),
+                slides: Some("deck".to_string()),
+                ..SymlinksConfig::default()
+            }),
+        };
+        Symlinks::from_config(&config).unwrap()
+    }
 
-    Ok((cs_target, cc_target))
+    #[test]
+    fn test_set_replaces_link() {
+        let temp_dir = TempDir::new().unwrap();
+        let links = symlinks(&temp_dir);
+        let base = temp_dir.path().join("Studies");
+        std::fs::create_dir_all(base.join("b3/ana")).unwrap();
+        std::fs::create_dir_all(base.join("b3/algo")).unwrap();
+
+        links.set(Link::Course, Path::new("b3/ana")).unwrap();
+        links.set(Link::Course, &base.join("b3/algo")).unwrap();
+
+        let link = temp_dir.path().join("current");
+        assert_eq!(std::fs::read_link(&link).unwrap(), base.join("b3/algo"));
+        assert!(!temp_dir.path().join("cc").exists());
+        // No temporary link is left behind
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+
+        // Turned-off links are never created
+        links.set(Link::LectureNotes, Path::new("b3/ana")).unwrap();
+        assert_eq!(links.path(Link::LectureNotes), None);
+    }
+
+    #[test]
+    fn test_check_reports_broken_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let links = symlinks(&temp_dir);
+        let base = temp_dir.path().join("Studies");
+        std::fs::create_dir_all(base.join("b3/ana")).unwrap();
+
+        links
+            .set_course(Path::new("b3"), Path::new("b3/ana"))
+            .unwrap();
+        links
+            .set(Link::Slides, Path::new("b3/ana/slides/01.pdf"))
+            .unwrap();
+
+        let status = links.check();
+        assert_eq!(
+            status.iter().map(|s| s.link).collect::<Vec<_>>(),
+            vec![Link::Semester, Link::Course, Link::Slides]
+        );
+        assert!(!status[0].broken && !status[1].broken);
+        assert!(status[2].broken);
+
+        links.relink(&base.join("b3"), &base.join("b03")).unwrap();
+        assert!(links.check().iter().all(|s| s.broken));
+
+        links.unlink_inside(&base.join("b03/ana")).unwrap();
+        let status = links.check();
+        assert!(status[0].target.is_some());
+        assert!(status[1].target.is_none() && status[2].target.is_none());
+    }
 }
@@ -0,0 +1,342 @@
+//! Drift between TOML files and database rows
+//!
+//! `.course.toml` and `.semester.toml` files are edited by hand, so they can
+//! disagree with their database rows. Drift is detected field by field and
+//! resolved by picking a side for every differing field. Keys a user added to
+//! a `.course.toml` are kept when the file is rewritten.
+
+use crate::course::import::course_toml;
+use crate::db::entities::{courses, semesters};
+use crate::db::queries;
+use crate::error::{MmsError, Result};
+use crate::semester::SemesterType;
+use crate::sync::import::{apply_course_toml, apply_semester_toml, clear_other_current};
+use crate::toml::{CourseToml, SemesterToml};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, DatabaseConnection, IntoActiveModel, TransactionTrait,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Keys identifying a course or semester, never reported as drift
+const COURSE_KEYS: &[&str] = &["short_name", "uid"];
+const SEMESTER_KEYS: &[&str] = &["type", "number", "uid"];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Where the value of a differing field is taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Toml,
+    Db,
+}
+
+/// A field with different values in the TOML file and the database
+///
+/// Values are formatted as TOML; `None` means the field is not set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDiff {
+    pub field: String,
+    pub toml: Option<String>,
+    pub db: Option<String>,
+}
+
+/// The row a TOML file belongs to
+#[derive(Debug, Clone)]
+pub enum DriftRow {
+    Semester(Box<semesters::Model>),
+    Course(Box<courses::Model>),
+}
+
+/// Differences between a TOML file and its database row
+#[derive(Debug, Clone)]
+pub struct Drift {
+    /// Semester code or `semester/course`
+    pub label: String,
+    pub toml_path: PathBuf,
+    pub row: DriftRow,
+    pub diffs: Vec<FieldDiff>,
+    /// Contents of the TOML file
+    file: toml::Table,
+    /// The database row as it would be written to the TOML file
+    db: toml::Table,
+}
+
+// ============================================================================
+// Detection
+// ============================================================================
+
+/// Compare all semester and course TOML files with their database rows
+///
+/// Rows whose TOML file is missing or cannot be parsed are skipped, as are
+/// rows without differences.
+pub async fn detect_drift(db: &DatabaseConnection) -> Result<Vec<Drift>> {
+    let mut drifts = Vec::new();
+    let mut codes = HashMap::new();
+
+    for semester in queries::semester::list(db).await? {
+        let Some(semester_type) = SemesterType::from_str(&semester.r#type) else {
+            continue;
+        };
+        let code = semester_type.code(semester.number);
+        codes.insert(semester.id, code.clone());
+
+        let toml_path = Path::new(&semester.directory_path).join(".semester.toml");
+        let Ok(toml) = SemesterToml::read(&toml_path) else {
+            continue;
+        };
+        let drift = semester_drift(code, toml_path, semester, semester_type, &toml)?;
+        if !drift.diffs.is_empty() {
+            drifts.push(drift);
+        }
+    }
+
+    for course in queries::course::list(db).await? {
+        let Some(toml_path) = course.toml_path.clone().map(PathBuf::from) else {
+            continue;
+        };
+        let Ok(toml) = CourseToml::read(&toml_path) else {
+            continue;
+        };
+        let label = match codes.get(&course.semester_id) {
+            Some(code) => format!("{}/{}", code, course.short_name),
+            None => course.short_name.clone(),
+        };
+        let drift = course_drift(label, toml_path, course, &toml)?;
+        if !drift.diffs.is_empty() {
+            drifts.push(drift);
+        }
+    }
+
+    Ok(drifts)
+}
+
+fn semester_drift(
+    label: String,
+    toml_path: PathBuf,
+    model: semesters::Model,
+    semester_type: SemesterType,
+    toml: &SemesterToml,
+) -> Result<Drift> {
+    let mut row = SemesterToml::new(semester_type, model.number);
+    row.uid = model.uid.clone();
+    row.start_date = model.start_date.clone();
+    row.end_date = model.end_date.clone();
+    row.university = model.university.clone();
+    // Without a location the TOML inherits the configured default
+    if toml.location.is_some() {
+        row.location = model.default_location.clone();
+    }
+    row.is_current = model.is_current;
+    row.is_archived = model.is_archived;
+
+    let file = to_table(toml)?;
+    let db = to_table(&row)?;
+    Ok(Drift {
+        label,
+        toml_path,
+        diffs: diff_tables(&file, &db, SEMESTER_KEYS),
+        row: DriftRow::Semester(Box::new(model)),
+        file,
+        db,
+    })
+}
+
+fn course_drift(
+    label: String,
+    toml_path: PathBuf,
+    model: courses::Model,
+    toml: &CourseToml,
+) -> Result<Drift> {
+    // Custom keys only live in the TOML file
+    let row = course_toml(&model).with_metadata(toml.metadata.clone());
+
+    let file = to_table(toml)?;
+    let db = to_table(&row)?;
+    Ok(Drift {
+        label,
+        toml_path,
+        diffs: diff_tables(&file, &db, COURSE_KEYS),
+        row: DriftRow::Course(Box::new(model)),
+        file,
+        db,
+    })
+}
+
+/// Fields of two tables with different values, in the order of `file`
+fn diff_tables(file: &toml::Table, db: &toml::Table, ignore: &[&str]) -> Vec<FieldDiff> {
+    let only_in_db = db.keys().filter(|key| !file.contains_key(*key));
+
+    file.keys()
+        .chain(only_in_db)
+        .filter(|key| !ignore.contains(&key.as_str()))
+        .filter(|key| file.get(*key) != db.get(*key))
+        .map(|key| FieldDiff {
+            field: key.clone(),
+            toml: file.get(key).map(|v| v.to_string()),
+            db: db.get(key).map(|v| v.to_string()),
+        })
+        .collect()
+}
+
+fn to_table<T: serde::Serialize>(value: &T) -> Result<toml::Table> {
+    toml::Table::try_from(value)
+        .map_err(|e| MmsError::Parse(format!("Failed to serialize to TOML: {}", e)))
+}
+
+// ============================================================================
+// Resolution
+// ============================================================================
+
+/// Merge a TOML file and its row, taking every differing field from the chosen side
+///
+/// This function performs the following steps:
+/// 1. Asks `choose` for the side of every differing field
+/// 2. Rewrites the TOML file if any field is taken from the database
+/// 3. Updates the row if any field is taken from the TOML file
+pub async fn resolve_drift(
+    db: &DatabaseConnection,
+    drift: &Drift,
+    mut choose: impl FnMut(&FieldDiff) -> Side,
+) -> Result<()> {
+    let mut merged = drift.file.clone();
+    let mut toml_changed = false;
+    let mut db_changed = false;
+
+    for diff in &drift.diffs {
+        match choose(diff) {
+            Side::Toml => db_changed = true,
+            Side::Db => {
+                toml_changed = true;
+                match drift.db.get(&diff.field) {
+                    Some(value) => merged.insert(diff.field.clone(), value.clone()),
+                    None => merged.remove(&diff.field),
+                };
+            }
+        }
+    }
+
+    let parse = |e: toml::de::Error| {
+        MmsError::Parse(format!(
+            "Failed to merge {}: {}",
+            drift.toml_path.display(),
+            e
+        ))
+    };
+
+    match &drift.row {
+        DriftRow::Semester(model) => {
+            let toml: SemesterToml = merged.try_into().map_err(parse)?;
+            if toml_changed {
+                toml.write(&drift.toml_path)?;
+            }
+            if db_changed {
+                let mut active = model.as_ref().clone().into_active_model();
+                apply_semester_toml(&mut active, &toml);
+                active.updated_at = ActiveValue::Set(Utc::now());
+
+                let txn = db.begin().await?;
+                active.update(&txn).await?;
+                if toml.is_current {
+                    clear_other_current(&txn, model.id).await?;
+                }
+                txn.commit().await?;
+            }
+        }
+        DriftRow::Course(model) => {
+            let toml: CourseToml = merged.try_into().map_err(parse)?;
+            if toml_changed {
+                toml.write(&drift.toml_path)?;
+            }
+            if db_changed {
+                let mut active = model.as_ref().clone().into_active_model();
+                apply_course_toml(&mut active, &toml);
+                active.updated_at = ActiveValue::Set(Utc::now());
+                active.update(db).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::semester_with_course;
+    use tempfile::TempDir;
+
+    async fn setup() -> (DatabaseConnection, TempDir, courses::Model) {
+        let temp_dir = TempDir::new().unwrap();
+        let (db, course) = semester_with_course(temp_dir.path(), "algo", "Algorithms").await;
+        let course = queries::course::get_by_id(&db, course.id).await.unwrap();
+
+        (db, temp_dir, course)
+    }
+
+    /// Edit the course TOML by hand: fix the ECTS, add a tutor email and a custom key
+    fn edit_toml(course: &courses::Model) -> PathBuf {
+        let path = PathBuf::from(course.toml_path.clone().unwrap());
+        let content = std::fs::read_to_string(&path).unwrap();
+        let content = content.replace("ects = 6", "ects = 8")
+            + "tutor_email = \"anna@tum.de\"\nexam_date = 2025-02-10\n";
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_detect_drift() {
+        let (db, _temp_dir, course) = setup().await;
+        assert!(detect_drift(&db).await.unwrap().is_empty());
+
+        edit_toml(&course);
+        let drifts = detect_drift(&db).await.unwrap();
+        assert_eq!(drifts.len(), 1);
+        assert_eq!(drifts[0].label, "b3/algo");
+        assert_eq!(
+            drifts[0].diffs,
+            vec![
+                FieldDiff {
+                    field: "ects".to_string(),
+                    toml: Some("8".to_string()),
+                    db: Some("6".to_string()),
+                },
+                FieldDiff {
+                    field: "tutor_email".to_string(),
+                    toml: Some("\"anna@tum.de\"".to_string()),
+                    db: None,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_drift() {
+        let (db, _temp_dir, course) = setup().await;
+        let path = edit_toml(&course);
+        let drift = detect_drift(&db).await.unwrap().remove(0);
+
+        // Keep the hand-fixed ECTS, drop the tutor email
+        resolve_drift(&db, &drift, |diff| match diff.field.as_str() {
+            "ects" => Side::Toml,
+            _ => Side::Db,
+        })
+        .await
+        .unwrap();
+
+        let course = queries::course::get_by_id(&db, course.id).await.unwrap();
+        assert_eq!(course.ects, 8);
+        assert_eq!(course.tutor_email, None);
+
+        let toml = CourseToml::read(&path).unwrap();
+        assert_eq!(toml.ects, 8);
+        assert_eq!(toml.tutor_email, None);
+        assert_eq!(toml.name, "Algorithms");
+        assert!(toml.metadata["exam_date"].is_datetime());
+
+        assert!(detect_drift(&db).await.unwrap().is_empty());
+    }
+}
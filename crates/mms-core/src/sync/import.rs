@@ -0,0 +1,568 @@
+//! Rebuilding the database from the TOML files on disk
+//!
+//! Every semester folder under `university_base_path` carries a `.semester.toml`
+//! and every course folder a `.course.toml`. Importing them recreates the
+//! `semesters` and `courses` rows, e.g. after losing `mms.db` or on a new
+//! machine with a synced studies folder.
+
+use crate::archive::archive_directory;
+use crate::config::Config;
+use crate::course::{CourseCode, Ects};
+use crate::db::entities::{courses, semesters};
+use crate::error::Result;
+use crate::toml::{CourseToml, SemesterToml};
+use crate::utils::date_validation::{validate_date_format, validate_date_range};
+use chrono::Utc;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter, TransactionTrait,
+};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Changes made by an import (semester codes and `semester/course` names)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    pub semesters_created: Vec<String>,
+    pub semesters_updated: Vec<String>,
+    pub courses_created: Vec<String>,
+    pub courses_updated: Vec<String>,
+    /// TOML files that could not be imported, with the reason
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+impl ImportReport {
+    /// Whether nothing changed and nothing was skipped
+    pub fn is_empty(&self) -> bool {
+        self.semesters_created.is_empty()
+            && self.semesters_updated.is_empty()
+            && self.courses_created.is_empty()
+            && self.courses_updated.is_empty()
+            && self.skipped.is_empty()
+    }
+}
+
+/// Whether an upsert created or updated a row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Upsert {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+/// Create or update semesters and courses from their TOML files
+///
+/// This function performs the following steps:
+/// 1. Reads `.semester.toml` of every folder in `university_base_path`
+/// 2. Creates or updates the semester, matched by type and number
+/// 3. Reads `.course.toml` of every folder in the semester folder
+/// 4. Creates or updates the course, matched by semester and short name
+/// 5. Does the same for archived courses in `.archive/<semester>/<course>`
+///
+/// Existing rows keep their ID, so grades, schedules and other data linked to
+/// them survive. Folders without a TOML file are ignored; invalid TOML files
+/// are reported in `skipped`. With `dry_run` nothing is written.
+pub async fn import_from_filesystem(
+    config: &Config,
+    db: &DatabaseConnection,
+    dry_run: bool,
+) -> Result<ImportReport> {
+    let mut report = ImportReport::default();
+    let mut semester_folders: HashMap<OsString, (i64, String)> = HashMap::new();
+
+    // A dry run performs the import in a transaction that is rolled back
+    let txn = db.begin().await?;
+
+    for semester_dir in subdirectories(&config.university_base_path)? {
+        let toml_path = semester_dir.join(".semester.toml");
+        if !toml_path.exists() {
+            continue;
+        }
+        let toml = match SemesterToml::read(&toml_path).and_then(|toml| {
+            validate_semester_toml(&toml)?;
+            Ok(toml)
+        }) {
+            Ok(toml) => toml,
+            Err(e) => {
+                report.skipped.push((toml_path, e.to_string()));
+                continue;
+            }
+        };
+
+        // Like `create_semester`, fall back to the configured default location
+        let existing = find_semester(&txn, &toml).await?;
+        let location = toml.location.clone().or_else(|| {
+            config
+                .general
+                .as_ref()
+                .and_then(|g| g.default_location.clone())
+        });
+        if existing.is_none() && location.is_none() {
+            report.skipped.push((
+                toml_path,
+                "No location set (add `location` or general.default_location)".to_string(),
+            ));
+            continue;
+        }
+
+        let code = toml.code();
+        let (semester_id, upsert) =
+            upsert_semester(&txn, existing, &semester_dir, &toml, location).await?;
+        match upsert {
+            Upsert::Created => report.semesters_created.push(code.clone()),
+            Upsert::Updated => report.semesters_updated.push(code.clone()),
+            Upsert::Unchanged => {}
+        }
+
+        for course_dir in subdirectories(&semester_dir)? {
+            import_course(&txn, semester_id, &code, &course_dir, false, &mut report).await?;
+        }
+        if let Some(folder) = semester_dir.file_name() {
+            semester_folders.insert(folder.to_os_string(), (semester_id, code));
+        }
+    }
+
+    // Archived courses live in `.archive/<semester>/<course>`, named after the
+    // semester folder, and are skipped by the scan above
+    for archive_dir in subdirectories(&archive_directory(config))? {
+        let semester = archive_dir
+            .file_name()
+            .and_then(|folder| semester_folders.get(folder));
+        let Some((semester_id, code)) = semester else {
+            report
+                .skipped
+                .push((archive_dir, "No semester folder with this name".to_string()));
+            continue;
+        };
+        for course_dir in subdirectories(&archive_dir)? {
+            import_course(&txn, *semester_id, code, &course_dir, true, &mut report).await?;
+        }
+    }
+
+    if dry_run {
+        txn.rollback().await?;
+    } else {
+        txn.commit().await?;
+    }
+
+    Ok(report)
+}
+
+/// Create or update a course from the `.course.toml` in its folder
+///
+/// Courses in the archive are always imported as archived.
+async fn import_course<C: ConnectionTrait>(
+    db: &C,
+    semester_id: i64,
+    semester_code: &str,
+    course_dir: &Path,
+    archived: bool,
+    report: &mut ImportReport,
+) -> Result<()> {
+    let toml_path = course_dir.join(".course.toml");
+    if !toml_path.exists() {
+        return Ok(());
+    }
+    let toml = match CourseToml::read(&toml_path).and_then(|toml| {
+        CourseCode::new(toml.short_name.clone())?;
+        Ects::new(toml.ects)?;
+        Ok(toml)
+    }) {
+        Ok(toml) if archived => toml.with_archived(true),
+        Ok(toml) => toml,
+        Err(e) => {
+            report.skipped.push((toml_path, e.to_string()));
+            return Ok(());
+        }
+    };
+
+    let name = format!("{}/{}", semester_code, toml.short_name);
+    match upsert_course(db, semester_id, course_dir, &toml).await? {
+        Upsert::Created => report.courses_created.push(name),
+        Upsert::Updated => report.courses_updated.push(name),
+        Upsert::Unchanged => {}
+    }
+    Ok(())
+}
+
+/// Check the values `create_semester` would reject
+fn validate_semester_toml(toml: &SemesterToml) -> Result<()> {
+    if let Some(ref date) = toml.start_date {
+        validate_date_format(date)?;
+    }
+    if let Some(ref date) = toml.end_date {
+        validate_date_format(date)?;
+    }
+    validate_date_range(&toml.start_date, &toml.end_date)
+}
+
+/// The semester a `.semester.toml` describes, matched by type and number
+async fn find_semester<C: ConnectionTrait>(
+    db: &C,
+    toml: &SemesterToml,
+) -> Result<Option<semesters::Model>> {
+    let semester = semesters::Entity::find()
+        .filter(semesters::Column::Type.eq(toml.semester_type.to_string()))
+        .filter(semesters::Column::Number.eq(toml.number))
+        .one(db)
+        .await?;
+    Ok(semester)
+}
+
+/// Create or update the semester described by a `.semester.toml`
+///
+/// A missing `location` keeps the location of an existing semester.
+async fn upsert_semester<C: ConnectionTrait>(
+    db: &C,
+    existing: Option<semesters::Model>,
+    semester_dir: &Path,
+    toml: &SemesterToml,
+    location: Option<String>,
+) -> Result<(i64, Upsert)> {
+    let is_new = existing.is_none();
+
+    let mut model = match existing {
+        Some(model) => model.into_active_model(),
+        None => semesters::ActiveModel {
+            created_at: ActiveValue::Set(Utc::now()),
+            ..Default::default()
+        },
+    };
+
+    model
+        .r#type
+        .set_if_not_equals(toml.semester_type.to_string());
+    model.number.set_if_not_equals(toml.number);
+    model
+        .directory_path
+        .set_if_not_equals(semester_dir.to_string_lossy().to_string());
+    model.exists_on_disk.set_if_not_equals(true);
+    apply_semester_toml(&mut model, toml);
+    if let Some(location) = location {
+        model.default_location.set_if_not_equals(Some(location));
+    }
+
+    let upsert = match (is_new, model.is_changed()) {
+        (true, _) => Upsert::Created,
+        (false, true) => Upsert::Updated,
+        (false, false) => Upsert::Unchanged,
+    };
+
+    model.last_scanned_at = ActiveValue::Set(Some(Utc::now()));
+    if upsert != Upsert::Unchanged {
+        model.updated_at = ActiveValue::Set(Utc::now());
+    }
+    let model = model.save(db).await?;
+    let id = model.id.unwrap();
+
+    // Only one semester can be current
+    if toml.is_current {
+        clear_other_current(db, id).await?;
+    }
+
+    Ok((id, upsert))
+}
+
+/// Clear `is_current` of every semester except `id`
+pub(crate) async fn clear_other_current<C: ConnectionTrait>(db: &C, id: i64) -> Result<()> {
+    semesters::Entity::update_many()
+        .col_expr(semesters::Column::IsCurrent, Expr::value(false))
+        .filter(semesters::Column::Id.ne(id))
+        .filter(semesters::Column::IsCurrent.eq(true))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Create or update the course described by a `.course.toml`
+async fn upsert_course<C: ConnectionTrait>(
+    db: &C,
+    semester_id: i64,
+    course_dir: &Path,
+    toml: &CourseToml,
+) -> Result<Upsert> {
+    let existing = courses::Entity::find()
+        .filter(courses::Column::SemesterId.eq(semester_id))
+        .filter(courses::Column::ShortName.eq(&toml.short_name))
+        .one(db)
+        .await?;
+    let is_new = existing.is_none();
+
+    let mut model = match existing {
+        Some(model) => model.into_active_model(),
+        None => courses::ActiveModel {
+            is_archived: ActiveValue::Set(false),
+            created_at: ActiveValue::Set(Utc::now()),
+            ..Default::default()
+        },
+    };
+
+    model.semester_id.set_if_not_equals(semester_id);
+    model.short_name.set_if_not_equals(toml.short_name.clone());
+    model
+        .directory_path
+        .set_if_not_equals(course_dir.to_string_lossy().to_string());
+    model.toml_path.set_if_not_equals(Some(
+        course_dir
+            .join(".course.toml")
+            .to_string_lossy()
+            .to_string(),
+    ));
+    model.exists_on_disk.set_if_not_equals(true);
+    model.toml_exists.set_if_not_equals(true);
+    apply_course_toml(&mut model, toml);
+
+    let upsert = match (is_new, model.is_changed()) {
+        (true, _) => Upsert::Created,
+        (false, true) => Upsert::Updated,
+        (false, false) => Upsert::Unchanged,
+    };
+
+    model.last_scanned_at = ActiveValue::Set(Some(Utc::now()));
+    if upsert != Upsert::Unchanged {
+        model.updated_at = ActiveValue::Set(Utc::now());
+    }
+    model.save(db).await?;
+
+    Ok(upsert)
+}
+
+/// Set the fields stored in a `.semester.toml` (a missing location is kept)
+pub(crate) fn apply_semester_toml(model: &mut semesters::ActiveModel, toml: &SemesterToml) {
+    if let Some(uid) = &toml.uid {
+        model.uid.set_if_not_equals(Some(uid.clone()));
+    }
+    model.start_date.set_if_not_equals(toml.start_date.clone());
+    model.end_date.set_if_not_equals(toml.end_date.clone());
+    if let Some(location) = &toml.location {
+        model
+            .default_location
+            .set_if_not_equals(Some(location.clone()));
+    }
+    model.university.set_if_not_equals(toml.university.clone());
+    model.is_current.set_if_not_equals(toml.is_current);
+    model.is_archived.set_if_not_equals(toml.is_archived);
+}
+
+/// Set the fields stored in a `.course.toml` (except the short name)
+pub(crate) fn apply_course_toml(model: &mut courses::ActiveModel, toml: &CourseToml) {
+    if let Some(uid) = &toml.uid {
+        model.uid.set_if_not_equals(Some(uid.clone()));
+    }
+    model.name.set_if_not_equals(toml.name.clone());
+    model.ects.set_if_not_equals(toml.ects as i64);
+    model.lecturer.set_if_not_equals(toml.lecturer.clone());
+    model
+        .lecturer_email
+        .set_if_not_equals(toml.lecturer_email.clone());
+    model.tutor.set_if_not_equals(toml.tutor.clone());
+    model
+        .tutor_email
+        .set_if_not_equals(toml.tutor_email.clone());
+    model
+        .learning_platform_url
+        .set_if_not_equals(toml.learning_platform_url.clone());
+    model.university.set_if_not_equals(toml.university.clone());
+    model.location.set_if_not_equals(toml.location.clone());
+    model.is_external.set_if_not_equals(toml.is_external);
+    model
+        .original_path
+        .set_if_not_equals(toml.original_path.clone());
+    model.is_dropped.set_if_not_equals(toml.is_dropped);
+    // The TOML does not record when a course was dropped
+    if !toml.is_dropped {
+        model.dropped_at.set_if_not_equals(None);
+    }
+    model.is_archived.set_if_not_equals(toml.is_archived);
+    model.has_git_repo.set_if_not_equals(toml.has_git_repo);
+    model
+        .git_remote_url
+        .set_if_not_equals(toml.git_remote_url.clone());
+    model
+        .exercise_naming
+        .set_if_not_equals(toml.exercise_naming.clone());
+    model
+        .exercise_file_naming
+        .set_if_not_equals(toml.exercise_file_naming.clone());
+}
+
+/// Visible subdirectories of a folder, sorted by name
+pub(crate) fn subdirectories(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| !n.starts_with('.'))
+        })
+        .collect();
+    dirs.sort();
+
+    Ok(dirs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::queries;
+    use crate::semester::SemesterType;
+    use crate::test_support::{self, test_db};
+    use tempfile::TempDir;
+
+    async fn setup() -> (DatabaseConnection, TempDir, Config) {
+        let db = test_db().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            university_base_path: temp_dir.path().to_path_buf(),
+            general: None,
+            grading: None,
+            notes: None,
+            schedule: None,
+            sync: None,
+            attendance: None,
+            study_time: None,
+            notifications: None,
+            hooks: None,
+            logging: None,
+            symlinks: None,
+        };
+        (db, temp_dir, config)
+    }
+
+    #[tokio::test]
+    async fn test_import_from_filesystem() {
+        let (db, temp_dir, config) = setup().await;
+
+        // Current until the imported semester says otherwise
+        let old = test_support::semester(&db, &temp_dir.path().join("b2"), 2).await;
+
+        let semester_dir = temp_dir.path().join("b3");
+        SemesterToml::new(SemesterType::Bachelor, 3)
+            .with_start_date("01.10.2024".to_string())
+            .with_end_date("31.03.2025".to_string())
+            .with_university("TUM".to_string())
+            .with_location("Munich".to_string())
+            .with_current(true)
+            .write_to_directory(&semester_dir)
+            .unwrap();
+        CourseToml::new("algo".to_string(), "Algorithms".to_string(), 6)
+            .with_lecturer("Prof. Schmidt")
+            .write_to_directory(&semester_dir.join("algo"))
+            .unwrap();
+        CourseToml::new("ana".to_string(), "Analysis".to_string(), 99)
+            .write_to_directory(&semester_dir.join("ana"))
+            .unwrap();
+        std::fs::create_dir_all(semester_dir.join("notes")).unwrap();
+
+        // Dry run reports but writes nothing
+        let report = import_from_filesystem(&config, &db, true).await.unwrap();
+        assert_eq!(report.semesters_created, vec!["b3"]);
+        assert_eq!(report.courses_created, vec!["b3/algo"]);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(queries::semester::list(&db).await.unwrap().len(), 1);
+        assert!(
+            queries::semester::get_by_id(&db, old.id)
+                .await
+                .unwrap()
+                .is_current
+        );
+
+        let report = import_from_filesystem(&config, &db, false).await.unwrap();
+        assert_eq!(report.courses_created, vec!["b3/algo"]);
+
+        let semester = queries::semester::get_current(&db).await.unwrap().unwrap();
+        assert_eq!(semester.number, 3);
+        assert!(
+            !queries::semester::get_by_id(&db, old.id)
+                .await
+                .unwrap()
+                .is_current
+        );
+        assert_eq!(semester.university.as_deref(), Some("TUM"));
+        let courses = queries::course::list_by_semester(&db, semester.id)
+            .await
+            .unwrap();
+        assert_eq!(courses.len(), 1);
+        assert_eq!(courses[0].lecturer.as_deref(), Some("Prof. Schmidt"));
+        assert!(courses[0].toml_exists);
+
+        // Importing again changes nothing, edits update the existing rows
+        let report = import_from_filesystem(&config, &db, false).await.unwrap();
+        assert!(report.semesters_created.is_empty());
+        assert!(report.semesters_updated.is_empty());
+        assert!(report.courses_updated.is_empty());
+
+        CourseToml::new(
+            "algo".to_string(),
+            "Algorithms and Data Structures".to_string(),
+            8,
+        )
+        .write_to_directory(&semester_dir.join("algo"))
+        .unwrap();
+        let report = import_from_filesystem(&config, &db, false).await.unwrap();
+        assert_eq!(report.courses_updated, vec!["b3/algo"]);
+
+        let course = queries::course::get_by_id(&db, courses[0].id)
+            .await
+            .unwrap();
+        assert_eq!(course.name, "Algorithms and Data Structures");
+        assert_eq!(course.ects, 8);
+        assert_eq!(course.lecturer, None);
+    }
+
+    #[tokio::test]
+    async fn test_import_archived_courses() {
+        let (db, temp_dir, config) = setup().await;
+        let base = temp_dir.path();
+
+        SemesterToml::new(SemesterType::Bachelor, 3)
+            .with_location("Munich".to_string())
+            .write_to_directory(&base.join("b3"))
+            .unwrap();
+        CourseToml::new("ana".to_string(), "Analysis".to_string(), 9)
+            .with_dropped(true)
+            .with_archived(true)
+            .write_to_directory(&base.join(".archive/b3/ana"))
+            .unwrap();
+        CourseToml::new("algo".to_string(), "Algorithms".to_string(), 6)
+            .write_to_directory(&base.join(".archive/b3/algo"))
+            .unwrap();
+        CourseToml::new("la".to_string(), "Linear Algebra".to_string(), 9)
+            .write_to_directory(&base.join(".archive/b1/la"))
+            .unwrap();
+
+        let report = import_from_filesystem(&config, &db, false).await.unwrap();
+        assert_eq!(report.courses_created, vec!["b3/algo", "b3/ana"]);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].0, base.join(".archive/b1"));
+
+        let semester = queries::semester::list(&db).await.unwrap().remove(0);
+        let courses = queries::course::list_by_semester(&db, semester.id)
+            .await
+            .unwrap();
+        assert_eq!(courses.len(), 2);
+        let ana = courses.iter().find(|c| c.short_name == "ana").unwrap();
+        assert!(ana.is_archived && ana.is_dropped);
+        assert_eq!(
+            ana.directory_path,
+            base.join(".archive/b3/ana").to_string_lossy()
+        );
+        let algo = courses.iter().find(|c| c.short_name == "algo").unwrap();
+        assert!(algo.is_archived && !algo.is_dropped);
+    }
+}
@@ -1,17 +1,22 @@
+pub mod drift;
+pub mod import;
+pub mod moves;
+pub mod normalize;
+
+pub use drift::{Drift, DriftRow, FieldDiff, Side, detect_drift, resolve_drift};
+pub use import::{ImportReport, import_from_filesystem};
+pub use moves::{FolderMove, MovedRow, apply_move, detect_moves};
+pub use normalize::normalize_semester_folders;
+
 use crate::config::Config;
 use crate::db::connection_seaorm; // Changed
 use crate::db::entities::semesters::Model as Semester; // Use SeaORM model
 use crate::db::queries;
 use crate::error::{MmsError, Result}; // Import MmsError
-use std::collections::{HashMap, HashSet};
+use crate::paths;
+use crate::semester::SemesterType;
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)] // Redefined locally or move to a shared types module
-pub enum SemesterType {
-    Bachelor,
-    Master,
-}
-
 #[derive(Debug, Clone)]
 pub struct SyncStatus {
     pub semesters_in_db_only: Vec<Semester>,
@@ -24,7 +29,7 @@ pub struct DiskSemester {
     pub folder_name: String,
     pub path: PathBuf,
     pub parsed_type: Option<SemesterType>,
-    pub parsed_number: Option<i32>,
+    pub parsed_number: Option<i64>,
 }
 
 impl SyncStatus {
@@ -36,8 +41,8 @@ impl SyncStatus {
 /// Scan the filesystem for semester folders
 ///
 /// This function iterates over the directory entries in the configured university base path.
-/// It filters for directories, attempts to parse their names as semester folders,
-/// and returns a list of `DiskSemester` structs.
+/// It filters for directories, attempts to parse their names with the configured
+/// `general.semester_folder` pattern, and returns a list of `DiskSemester` structs.
 ///
 /// # Preconditions
 /// - `config.university_base_path()` must return a valid path (validated on config load).
@@ -67,7 +72,8 @@ pub fn scan_disk_semesters(config: &Config) -> Result<Vec<DiskSemester>> {
                 .and_then(|n| n.to_str())
                 .map(|s| s.to_string())?;
 
-            let (parsed_type, parsed_number) = parse_semester_folder(&folder_name);
+            let parsed = paths::parse_semester_folder(config.semester_folder(), &folder_name);
+            let (parsed_type, parsed_number) = parsed.unzip();
 
             Some(DiskSemester {
                 folder_name,
@@ -81,26 +87,11 @@ pub fn scan_disk_semesters(config: &Config) -> Result<Vec<DiskSemester>> {
     Ok(disk_semesters)
 }
 
-/// Parse semester folder name like "m01" -> (Master, 1) or "b03" -> (Bachelor, 3)
-fn parse_semester_folder(folder_name: &str) -> (Option<SemesterType>, Option<i32>) {
-    // Contract: folder_name must have at least 2 chars to possibly be valid (e.g. "b1")
-    if folder_name.len() < 2 {
-        return (None, None);
-    }
-
-    let mut chars = folder_name.chars();
-    let type_char = chars.next().unwrap_or_default().to_ascii_lowercase();
-    let number_str = chars.as_str();
-
-    let semester_type = match type_char {
-        'm' => Some(SemesterType::Master),
-        'b' => Some(SemesterType::Bachelor),
-        _ => None,
-    };
-
-    let number = number_str.parse::<i32>().ok();
-
-    (semester_type, number)
+/// Absolute folder of a semester
+///
+/// Older rows may store `directory_path` relative to `university_base_path`.
+pub(crate) fn semester_path(config: &Config, semester: &Semester) -> PathBuf {
+    config.university_base_path.join(&semester.directory_path)
 }
 
 /// Check sync status between database and filesystem
@@ -117,43 +108,20 @@ pub async fn check_status() -> Result<SyncStatus> {
     // Scan filesystem
     let disk_semesters = scan_disk_semesters(&config)?;
 
-    // Build lookup maps
-    let mut db_map: HashMap<String, Semester> = db_semesters
-        .iter()
-        .map(|s| (s.directory_path.clone(), s.clone())) // Use directory_path as folder name
-        .collect();
-
-    let mut disk_set: HashSet<String> = disk_semesters
-        .iter()
-        .filter(|ds| ds.parsed_type.is_some() && ds.parsed_number.is_some())
-        .map(|ds| ds.folder_name.clone())
-        .collect();
-
-    // Find semesters in both
-    let mut synced_semesters = Vec::new();
-    // Iterating over keys of db_map is tricky while modifying it.
-    // Let's iterate over a clone of keys or just check disk_set against db_map keys.
-
-    let db_folders: Vec<String> = db_map.keys().cloned().collect();
-    for folder_name in db_folders {
-        if disk_set.contains(&folder_name) {
-            if let Some(semester) = db_map.get(&folder_name) {
-                synced_semesters.push(semester.clone());
-            }
-            // We want to remove it from disk_set (to find disk-only ones)
-            // and remove from db_map (to find db-only ones).
-            disk_set.remove(&folder_name);
-            db_map.remove(&folder_name);
-        }
-    }
-
-    // What's left in db_map is in DB only
-    let semesters_in_db_only: Vec<Semester> = db_map.into_values().collect();
+    // Rows are synced if their folder exists, wherever it is
+    let (synced_semesters, semesters_in_db_only): (Vec<Semester>, Vec<Semester>) = db_semesters
+        .into_iter()
+        .partition(|s| semester_path(&config, s).is_dir());
 
-    // What's left in disk_set is on disk only
+    // Folders following the naming pattern that no row points to
     let semesters_on_disk_only: Vec<DiskSemester> = disk_semesters
         .into_iter()
-        .filter(|ds| disk_set.contains(&ds.folder_name))
+        .filter(|ds| ds.parsed_type.is_some() && ds.parsed_number.is_some())
+        .filter(|ds| {
+            !synced_semesters
+                .iter()
+                .any(|s| semester_path(&config, s) == ds.path)
+        })
         .collect();
 
     Ok(SyncStatus {
@@ -171,7 +139,7 @@ pub async fn sync_to_filesystem(dry_run: bool) -> Result<Vec<String>> {
     let mut actions = Vec::new();
 
     for semester in &status.semesters_in_db_only {
-        let semester_path = config.university_base_path.join(&semester.directory_path); // Use directory_path
+        let semester_path = semester_path(&config, semester);
         let action = format!("Create folder: {}", semester_path.display());
         actions.push(action);
 
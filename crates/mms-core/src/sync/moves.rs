@@ -0,0 +1,405 @@
+//! Renamed and moved semester and course folders
+//!
+//! A folder renamed or moved by hand leaves its row with a stale
+//! `directory_path`. The TOML file moved along with it, so the folder is found
+//! again by the stable `uid` in the TOML or, for older files without one, by
+//! semester type and number or course short name.
+
+use crate::config::Config;
+use crate::db::entities::{courses, semesters};
+use crate::db::queries;
+use crate::error::Result;
+use crate::symlink;
+use crate::sync::import::subdirectories;
+use crate::toml::{CourseToml, SemesterToml};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, DatabaseConnection, IntoActiveModel, TransactionTrait,
+};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// The row whose folder moved
+#[derive(Debug, Clone)]
+pub enum MovedRow {
+    Semester(Box<semesters::Model>),
+    Course(Box<courses::Model>),
+}
+
+/// A folder found at a new location
+#[derive(Debug, Clone)]
+pub struct FolderMove {
+    /// Semester code or `semester/course`
+    pub label: String,
+    pub row: MovedRow,
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+// ============================================================================
+// Detection
+// ============================================================================
+
+/// Find semester and course folders that were renamed or moved
+///
+/// This function performs the following steps:
+/// 1. Reads all `.semester.toml` and `.course.toml` files under `university_base_path`
+/// 2. Matches semesters whose folder is gone to an unclaimed semester folder
+/// 3. Matches courses whose folder is gone (after their semester moved) to an
+///    unclaimed course folder, preferring folders in the course's semester
+///
+/// Courses inside a moved semester folder are covered by the semester's move.
+pub async fn detect_moves(config: &Config, db: &DatabaseConnection) -> Result<Vec<FolderMove>> {
+    let base_path = &config.university_base_path;
+    let mut moves = Vec::new();
+
+    // Semester folders on disk
+    let mut semester_dirs = Vec::new();
+    for dir in subdirectories(base_path)? {
+        if let Ok(toml) = SemesterToml::read_from_directory(&dir) {
+            semester_dirs.push((dir, toml));
+        }
+    }
+
+    let semesters = queries::semester::list(db).await?;
+    let mut claimed: HashSet<PathBuf> = semesters
+        .iter()
+        .map(|s| PathBuf::from(&s.directory_path))
+        .filter(|dir| dir.exists())
+        .collect();
+
+    // Current folder of every semester, after moves
+    let mut semester_paths = HashMap::new();
+    for semester in semesters {
+        let from = PathBuf::from(&semester.directory_path);
+        let found = if from.exists() {
+            None
+        } else {
+            semester_dirs.iter().find(|(dir, toml)| {
+                !claimed.contains(dir)
+                    && same_identity(
+                        &semester.uid,
+                        &toml.uid,
+                        toml.semester_type.to_string() == semester.r#type
+                            && toml.number == semester.number,
+                    )
+            })
+        };
+
+        match found {
+            Some((to, toml)) => {
+                claimed.insert(to.clone());
+                semester_paths.insert(semester.id, (from.clone(), to.clone()));
+                moves.push(FolderMove {
+                    label: toml.code(),
+                    row: MovedRow::Semester(Box::new(semester)),
+                    from,
+                    to: to.clone(),
+                });
+            }
+            None => {
+                semester_paths.insert(semester.id, (from.clone(), from));
+            }
+        }
+    }
+
+    // Course folders on disk, in any folder of the base path
+    let mut course_dirs = Vec::new();
+    for semester_dir in subdirectories(base_path)? {
+        for dir in subdirectories(&semester_dir)? {
+            if let Ok(toml) = CourseToml::read_from_directory(&dir) {
+                course_dirs.push((dir, toml));
+            }
+        }
+    }
+
+    let courses: Vec<(courses::Model, PathBuf)> = queries::course::list(db)
+        .await?
+        .into_iter()
+        .filter(|course| course.toml_path.is_some())
+        .map(|course| {
+            let dir = moved_path(
+                &course.directory_path,
+                semester_paths.get(&course.semester_id),
+            );
+            (course, dir)
+        })
+        .collect();
+    let mut claimed: HashSet<PathBuf> = courses
+        .iter()
+        .map(|(_, dir)| dir.clone())
+        .filter(|dir| dir.exists())
+        .collect();
+
+    for (course, dir) in courses {
+        if dir.exists() {
+            continue;
+        }
+        let semester_dir = semester_paths.get(&course.semester_id).map(|(_, to)| to);
+        let mut candidates: Vec<&(PathBuf, CourseToml)> = course_dirs
+            .iter()
+            .filter(|(dir, toml)| {
+                !claimed.contains(dir)
+                    && same_identity(&course.uid, &toml.uid, toml.short_name == course.short_name)
+            })
+            .collect();
+        // Prefer a rename within the semester over a move into another one
+        candidates.sort_by_key(|(dir, _)| dir.parent() != semester_dir.map(|d| d.as_path()));
+
+        if let Some((to, _)) = candidates.first() {
+            claimed.insert(to.clone());
+            let label = match semester_dir.and_then(|d| d.file_name()) {
+                Some(semester) => format!("{}/{}", semester.to_string_lossy(), course.short_name),
+                None => course.short_name.clone(),
+            };
+            // Relative to the semester's new folder, where `cc` points after its move
+            moves.push(FolderMove {
+                label,
+                from: dir,
+                to: to.clone(),
+                row: MovedRow::Course(Box::new(course)),
+            });
+        }
+    }
+
+    Ok(moves)
+}
+
+/// Whether a TOML file belongs to a row: by stable ID if both have one, else by `keys_match`
+fn same_identity(row_uid: &Option<String>, toml_uid: &Option<String>, keys_match: bool) -> bool {
+    match (row_uid, toml_uid) {
+        (Some(row_uid), Some(toml_uid)) => row_uid == toml_uid,
+        _ => keys_match,
+    }
+}
+
+/// `path` with a moved parent folder (`from`, `to`) replaced
+fn moved_path(path: &str, parent_move: Option<&(PathBuf, PathBuf)>) -> PathBuf {
+    let path = PathBuf::from(path);
+    match parent_move {
+        Some((from, to)) => match path.strip_prefix(from) {
+            Ok(rest) => to.join(rest),
+            Err(_) => path,
+        },
+        None => path,
+    }
+}
+
+// ============================================================================
+// Applying
+// ============================================================================
+
+/// Point a row (and the `cs`/`cc` symlinks) at the new folder
+///
+/// This function performs the following steps:
+/// 1. Updates `directory_path` (and `toml_path` of courses)
+/// 2. For a semester, updates the paths of the courses inside its folder
+/// 3. For a course moved into another semester's folder, moves it to that semester
+/// 4. Relinks `cs`/`cc` if they pointed into the old folder
+pub async fn apply_move(
+    config: &Config,
+    db: &DatabaseConnection,
+    folder_move: &FolderMove,
+) -> Result<()> {
+    update_moved_rows(db, folder_move, None).await?;
+    symlink::relink(config, &folder_move.from, &folder_move.to)?;
+
+    Ok(())
+}
+
+/// Steps 1-3 of [`apply_move`] in one transaction, without touching symlinks
+///
+/// `archive_move` is the semester's renamed `.archive` folder (`from`, `to`),
+/// whose archived courses are updated along with the semester.
+pub(crate) async fn update_moved_rows(
+    db: &DatabaseConnection,
+    folder_move: &FolderMove,
+    archive_move: Option<&(PathBuf, PathBuf)>,
+) -> Result<()> {
+    let semesters = queries::semester::list(db).await?;
+    let courses = queries::course::list(db).await?;
+    let parent_move = (folder_move.from.clone(), folder_move.to.clone());
+
+    let txn = db.begin().await?;
+
+    match &folder_move.row {
+        MovedRow::Semester(semester) => {
+            let mut active = semester.as_ref().clone().into_active_model();
+            active.directory_path = ActiveValue::Set(folder_move.to.to_string_lossy().to_string());
+            active.exists_on_disk = ActiveValue::Set(true);
+            active.updated_at = ActiveValue::Set(Utc::now());
+            active.update(&txn).await?;
+
+            for course in courses {
+                let dir = Path::new(&course.directory_path);
+                let course_move = if dir.starts_with(&folder_move.from) {
+                    &parent_move
+                } else if let Some(archive_move) = archive_move
+                    && dir.starts_with(&archive_move.0)
+                {
+                    archive_move
+                } else {
+                    continue;
+                };
+                let dir = moved_path(&course.directory_path, Some(course_move));
+                let toml_path = course
+                    .toml_path
+                    .as_deref()
+                    .map(|path| moved_path(path, Some(course_move)));
+                let mut active = course.into_active_model();
+                active.directory_path = ActiveValue::Set(dir.to_string_lossy().to_string());
+                active.toml_path =
+                    ActiveValue::Set(toml_path.map(|p| p.to_string_lossy().to_string()));
+                active.updated_at = ActiveValue::Set(Utc::now());
+                active.update(&txn).await?;
+            }
+        }
+        MovedRow::Course(course) => {
+            let mut active = course.as_ref().clone().into_active_model();
+            active.directory_path = ActiveValue::Set(folder_move.to.to_string_lossy().to_string());
+            active.toml_path = ActiveValue::Set(Some(
+                folder_move
+                    .to
+                    .join(".course.toml")
+                    .to_string_lossy()
+                    .to_string(),
+            ));
+            active.exists_on_disk = ActiveValue::Set(true);
+            active.toml_exists = ActiveValue::Set(true);
+
+            // Moved into the folder of another semester
+            if let Some(parent) = folder_move.to.parent() {
+                let semester = semesters
+                    .iter()
+                    .find(|s| Path::new(&s.directory_path) == parent);
+                if let Some(semester) = semester {
+                    active.semester_id.set_if_not_equals(semester.id);
+                }
+            }
+
+            active.updated_at = ActiveValue::Set(Utc::now());
+            active.update(&txn).await?;
+        }
+    }
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semester::{SemesterType, create_semester};
+    use crate::test_support;
+    use tempfile::TempDir;
+
+    async fn setup() -> (DatabaseConnection, TempDir, Config) {
+        let db = test_support::test_db().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            university_base_path: temp_dir.path().to_path_buf(),
+            general: None,
+            grading: None,
+            notes: None,
+            schedule: None,
+            sync: None,
+            attendance: None,
+            study_time: None,
+            notifications: None,
+            hooks: None,
+            logging: None,
+            symlinks: None,
+        };
+        (db, temp_dir, config)
+    }
+
+    async fn course(db: &DatabaseConnection, semester_id: i64, short_name: &str) -> courses::Model {
+        let course = test_support::course(db, semester_id, short_name, short_name, 6).await;
+        queries::course::get_by_id(db, course.id).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_detect_and_apply_moves() {
+        let (db, temp_dir, config) = setup().await;
+        let base = temp_dir.path();
+        let semester = create_semester(
+            &config,
+            &db,
+            SemesterType::Bachelor,
+            3,
+            None,
+            None,
+            Some("TUM".to_string()),
+            Some("Munich".to_string()),
+            true,
+            false,
+        )
+        .await
+        .unwrap();
+        let algo = course(&db, semester.id, "algo").await;
+        let ana = course(&db, semester.id, "ana").await;
+        assert!(detect_moves(&config, &db).await.unwrap().is_empty());
+
+        // Rename a course folder, then the semester folder
+        std::fs::rename(base.join("b3/algo"), base.join("b3/algorithms")).unwrap();
+        std::fs::rename(base.join("b3"), base.join("bachelor-3")).unwrap();
+
+        let moves = detect_moves(&config, &db).await.unwrap();
+        assert_eq!(moves.len(), 2);
+        assert_eq!(moves[0].label, "b3");
+        assert_eq!(moves[0].to, base.join("bachelor-3"));
+        assert_eq!(moves[1].label, "bachelor-3/algo");
+        assert_eq!(moves[1].to, base.join("bachelor-3/algorithms"));
+
+        for folder_move in &moves {
+            apply_move(&config, &db, folder_move).await.unwrap();
+        }
+        assert!(detect_moves(&config, &db).await.unwrap().is_empty());
+
+        let algo = queries::course::get_by_id(&db, algo.id).await.unwrap();
+        assert_eq!(
+            PathBuf::from(algo.toml_path.unwrap()),
+            base.join("bachelor-3/algorithms/.course.toml")
+        );
+        let ana = queries::course::get_by_id(&db, ana.id).await.unwrap();
+        assert_eq!(
+            PathBuf::from(ana.directory_path),
+            base.join("bachelor-3/ana")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stable_id_wins_over_short_name() {
+        let (db, temp_dir, config) = setup().await;
+        let base = temp_dir.path();
+        let semester = create_semester(
+            &config,
+            &db,
+            SemesterType::Bachelor,
+            3,
+            None,
+            None,
+            Some("TUM".to_string()),
+            Some("Munich".to_string()),
+            true,
+            false,
+        )
+        .await
+        .unwrap();
+        course(&db, semester.id, "algo").await;
+
+        // A different course with the same short name does not count as the moved folder
+        std::fs::remove_dir_all(base.join("b3/algo")).unwrap();
+        CourseToml::new("algo".to_string(), "Other".to_string(), 6)
+            .with_uid("another-course")
+            .write_to_directory(&base.join("b3/algo2"))
+            .unwrap();
+
+        assert!(detect_moves(&config, &db).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,374 @@
+//! Renaming semester folders to the configured naming pattern
+//!
+//! Folders created with another `general.semester_folder` pattern (or before
+//! it existed) keep their old names. Normalising renames them and stores the
+//! absolute `directory_path`, also for rows holding a path relative to
+//! `university_base_path`. The semester's folder in `.archive` is renamed
+//! along with it.
+
+use crate::archive::archive_directory;
+use crate::config::Config;
+use crate::db::queries;
+use crate::error::{MmsError, Result};
+use crate::paths;
+use crate::semester::SemesterType;
+use crate::symlink;
+use crate::sync::moves::{FolderMove, MovedRow, update_moved_rows};
+use crate::sync::semester_path;
+use sea_orm::DatabaseConnection;
+use std::path::{Path, PathBuf};
+
+/// Rename semester folders and update their rows to follow the naming pattern
+///
+/// This function performs the following steps:
+/// 1. Computes the pattern folder of every semester row
+/// 2. Fails before changing anything if a target folder (or its `.archive`
+///    folder) is taken by another folder
+/// 3. Renames the folders and their `.archive` folders (unless `dry_run`)
+/// 4. Updates the rows, the courses inside and the symlinks like a detected move,
+///    renaming the folders back if the rows cannot be updated
+///
+/// Returns the renamed (or, on dry-run, to be renamed) folders.
+pub async fn normalize_semester_folders(
+    config: &Config,
+    db: &DatabaseConnection,
+    dry_run: bool,
+) -> Result<Vec<FolderMove>> {
+    let mut moves = Vec::new();
+
+    for semester in queries::semester::list(db).await? {
+        let semester_type = SemesterType::from_str(&semester.r#type)
+            .ok_or_else(|| MmsError::InvalidSemesterType(semester.r#type.clone()))?;
+        let folder_name =
+            paths::semester_folder_name(config.semester_folder(), semester_type, semester.number);
+        let to = config.university_base_path.join(folder_name);
+        if Path::new(&semester.directory_path) == to {
+            continue;
+        }
+
+        let from = semester_path(config, &semester);
+        if from != to && from.exists() && to.exists() {
+            return Err(MmsError::FolderExists { from, to });
+        }
+        let (archive_from, archive_to) = archive_move(config, &from, &to);
+        if archive_from != archive_to && archive_from.exists() && archive_to.exists() {
+            return Err(MmsError::FolderExists {
+                from: archive_from,
+                to: archive_to,
+            });
+        }
+
+        moves.push(FolderMove {
+            label: semester_type.code(semester.number),
+            row: MovedRow::Semester(Box::new(semester)),
+            from,
+            to,
+        });
+    }
+
+    if dry_run {
+        return Ok(moves);
+    }
+
+    for folder_move in &moves {
+        let MovedRow::Semester(semester) = &folder_move.row else {
+            continue;
+        };
+
+        let renamed = folder_move.from != folder_move.to && folder_move.from.exists();
+        if renamed {
+            std::fs::rename(&folder_move.from, &folder_move.to)?;
+        }
+        let archive = archive_move(config, &folder_move.from, &folder_move.to);
+        let archive_renamed = archive.0 != archive.1 && archive.0.exists();
+        if archive_renamed && let Err(e) = std::fs::rename(&archive.0, &archive.1) {
+            if renamed {
+                let _ = std::fs::rename(&folder_move.to, &folder_move.from);
+            }
+            return Err(e.into());
+        }
+
+        // Courses of rows with a relative path are relative as well
+        let stored = FolderMove {
+            from: PathBuf::from(&semester.directory_path),
+            ..folder_move.clone()
+        };
+        if let Err(e) = update_moved_rows(db, &stored, Some(&archive)).await {
+            // Keep the folders where the rows still point
+            if renamed {
+                let _ = std::fs::rename(&folder_move.to, &folder_move.from);
+            }
+            if archive_renamed {
+                let _ = std::fs::rename(&archive.1, &archive.0);
+            }
+            return Err(e);
+        }
+        symlink::relink(config, &stored.from, &stored.to)?;
+        if stored.from != folder_move.from {
+            symlink::relink(config, &folder_move.from, &folder_move.to)?;
+        }
+    }
+
+    Ok(moves)
+}
+
+/// The `.archive` folders of a semester folder renamed from `from` to `to`
+fn archive_move(config: &Config, from: &Path, to: &Path) -> (PathBuf, PathBuf) {
+    let archive = archive_directory(config);
+    let folder = |dir: &Path| archive.join(dir.file_name().unwrap_or_default());
+    (folder(from), folder(to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::archive_course;
+    use crate::config::settings::GeneralConfig;
+    use crate::semester::create_semester;
+    use crate::sync::import::import_from_filesystem;
+    use crate::test_support::{course, semester, test_db};
+    use sea_orm::{ActiveModelTrait, ActiveValue, ConnectionTrait, IntoActiveModel};
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_normalize_semester_folders() {
+        let db = test_db().await;
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = Config {
+            university_base_path: temp_dir.path().to_path_buf(),
+            general: Some(GeneralConfig {
+                student_name: None,
+                student_id: None,
+                default_editor: None,
+                default_pdf_viewer: None,
+                default_location: None,
+                symlink_path: temp_dir.path().to_path_buf(),
+                semester_folder: paths::DEFAULT_SEMESTER_FOLDER.to_string(),
+            }),
+            grading: None,
+            notes: None,
+            schedule: None,
+            sync: None,
+            attendance: None,
+            study_time: None,
+            notifications: None,
+            hooks: None,
+            logging: None,
+            symlinks: None,
+        };
+
+        let semester = create_semester(
+            &config,
+            &db,
+            SemesterType::Bachelor,
+            3,
+            None,
+            None,
+            None,
+            Some("Tübingen".to_string()),
+            true,
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(semester.directory_path, temp_dir.path().join("b3"));
+        let course = course(&db, semester.id, "ana", "Analysis", 6).await;
+
+        // Nothing to do while the folders follow the pattern
+        assert!(
+            normalize_semester_folders(&config, &db, false)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        if let Some(general) = config.general.as_mut() {
+            general.semester_folder = "{type}{number:02}".to_string();
+        }
+        let planned = normalize_semester_folders(&config, &db, true)
+            .await
+            .unwrap();
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].to, temp_dir.path().join("b03"));
+        assert!(temp_dir.path().join("b3").exists());
+
+        normalize_semester_folders(&config, &db, false)
+            .await
+            .unwrap();
+        assert!(!temp_dir.path().join("b3").exists());
+        assert!(temp_dir.path().join("b03/ana/.course.toml").exists());
+
+        let semester = queries::semester::get_by_id(&db, semester.id)
+            .await
+            .unwrap();
+        assert_eq!(
+            PathBuf::from(semester.directory_path),
+            temp_dir.path().join("b03")
+        );
+        let course = queries::course::get_by_id(&db, course.id).await.unwrap();
+        assert_eq!(
+            PathBuf::from(course.directory_path),
+            temp_dir.path().join("b03/ana")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_normalize_renames_archive_folder() {
+        let db = test_db().await;
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        let mut config = Config {
+            university_base_path: base.to_path_buf(),
+            general: Some(GeneralConfig {
+                student_name: None,
+                student_id: None,
+                default_editor: None,
+                default_pdf_viewer: None,
+                default_location: None,
+                symlink_path: base.to_path_buf(),
+                semester_folder: paths::DEFAULT_SEMESTER_FOLDER.to_string(),
+            }),
+            grading: None,
+            notes: None,
+            schedule: None,
+            sync: None,
+            attendance: None,
+            study_time: None,
+            notifications: None,
+            hooks: None,
+            logging: None,
+            symlinks: None,
+        };
+
+        let semester = create_semester(
+            &config,
+            &db,
+            SemesterType::Bachelor,
+            3,
+            None,
+            None,
+            None,
+            Some("Tübingen".to_string()),
+            true,
+            false,
+        )
+        .await
+        .unwrap();
+        let ana = course(&db, semester.id, "ana", "Analysis", 6).await;
+        archive_course(&config, &db, ana.id, true).await.unwrap();
+        assert!(base.join(".archive/b3/ana").exists());
+
+        if let Some(general) = config.general.as_mut() {
+            general.semester_folder = "{type}{number:02}".to_string();
+        }
+        normalize_semester_folders(&config, &db, false)
+            .await
+            .unwrap();
+        assert!(!base.join(".archive/b3").exists());
+        assert!(base.join(".archive/b03/ana/.course.toml").exists());
+
+        let ana = queries::course::get_by_id(&db, ana.id).await.unwrap();
+        assert_eq!(
+            PathBuf::from(ana.directory_path),
+            base.join(".archive/b03/ana")
+        );
+        assert_eq!(
+            ana.toml_path.map(PathBuf::from),
+            Some(base.join(".archive/b03/ana/.course.toml"))
+        );
+
+        // The archived course is still found next to its semester folder
+        let report = import_from_filesystem(&config, &db, true).await.unwrap();
+        assert!(report.skipped.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_normalize_refuses_taken_folder() {
+        let db = test_db().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            university_base_path: temp_dir.path().to_path_buf(),
+            general: None,
+            grading: None,
+            notes: None,
+            schedule: None,
+            sync: None,
+            attendance: None,
+            study_time: None,
+            notifications: None,
+            hooks: None,
+            logging: None,
+            symlinks: None,
+        };
+
+        let semester = create_semester(
+            &config,
+            &db,
+            SemesterType::Master,
+            1,
+            None,
+            None,
+            None,
+            Some("Tübingen".to_string()),
+            false,
+            false,
+        )
+        .await
+        .unwrap();
+
+        // Row pointing at an old folder while the pattern folder exists too
+        std::fs::create_dir_all(temp_dir.path().join("m01")).unwrap();
+        let mut active = queries::semester::get_by_id(&db, semester.id)
+            .await
+            .unwrap()
+            .into_active_model();
+        active.directory_path = ActiveValue::Set("m01".to_string());
+        active.update(&db).await.unwrap();
+
+        let result = normalize_semester_folders(&config, &db, false).await;
+        assert!(matches!(result, Err(MmsError::FolderExists { .. })));
+        assert!(temp_dir.path().join("m01").exists());
+    }
+
+    #[tokio::test]
+    async fn test_normalize_renames_back_on_database_error() {
+        let db = test_db().await;
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            university_base_path: temp_dir.path().to_path_buf(),
+            general: None,
+            grading: None,
+            notes: None,
+            schedule: None,
+            sync: None,
+            attendance: None,
+            study_time: None,
+            notifications: None,
+            hooks: None,
+            logging: None,
+            symlinks: None,
+        };
+        let semester = semester(&db, &temp_dir.path().join("bachelor3"), 3).await;
+        std::fs::create_dir_all(temp_dir.path().join(".archive/bachelor3")).unwrap();
+
+        // Updating the rows fails after the folders were renamed
+        db.execute_unprepared("DROP TABLE courses").await.unwrap();
+        assert!(
+            normalize_semester_folders(&config, &db, false)
+                .await
+                .is_err()
+        );
+        assert!(temp_dir.path().join("bachelor3").exists());
+        assert!(!temp_dir.path().join("b3").exists());
+        assert!(temp_dir.path().join(".archive/bachelor3").exists());
+        assert!(!temp_dir.path().join(".archive/b3").exists());
+
+        let semester = queries::semester::get_by_id(&db, semester.id)
+            .await
+            .unwrap();
+        assert_eq!(
+            PathBuf::from(semester.directory_path),
+            temp_dir.path().join("bachelor3")
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! Shared setup for unit tests
+
+use std::path::Path;
+
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, Database, DatabaseConnection, Set};
+
+use crate::course::{CourseCode, CourseInfo, Ects, create_course};
+use crate::db::entities::semesters;
+
+/// An in-memory database with all migrations applied
+pub async fn test_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    crate::db::migrations::run_migrations(&db).await.unwrap();
+    db
+}
+
+/// Insert the current bachelor semester `number` (winter 2024/25) stored in `dir`
+///
+/// Creates `dir` if it does not exist.
+pub async fn semester(db: &DatabaseConnection, dir: &Path, number: i64) -> semesters::Model {
+    std::fs::create_dir_all(dir).unwrap();
+    semesters::ActiveModel {
+        r#type: Set("bachelor".to_string()),
+        number: Set(number),
+        directory_path: Set(dir.to_string_lossy().to_string()),
+        exists_on_disk: Set(true),
+        start_date: Set(Some("01.10.2024".to_string())),
+        end_date: Set(Some("31.03.2025".to_string())),
+        default_location: Set(Some("Munich".to_string())),
+        university: Set(Some("TUM".to_string())),
+        is_current: Set(true),
+        is_archived: Set(false),
+        created_at: Set(Utc::now()),
+        updated_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .unwrap()
+}
+
+/// Create a course without contacts, links or git repository
+pub async fn course(
+    db: &DatabaseConnection,
+    semester_id: i64,
+    code: &str,
+    name: &str,
+    ects: i32,
+) -> CourseInfo {
+    create_course(
+        db,
+        semester_id,
+        CourseCode::new(code.to_string()).unwrap(),
+        name.to_string(),
+        Ects::new(ects).unwrap(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        false,
+        None,
+    )
+    .await
+    .unwrap()
+}
+
+/// A database with semester 3 in `base/b3` and a 6 ECTS course in it
+pub async fn semester_with_course(
+    base: &Path,
+    code: &str,
+    name: &str,
+) -> (DatabaseConnection, CourseInfo) {
+    let db = test_db().await;
+    let semester = semester(&db, &base.join("b3"), 3).await;
+    let course = course(&db, semester.id, code, name, 6).await;
+    (db, course)
+}
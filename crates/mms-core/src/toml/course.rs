@@ -11,6 +11,10 @@ pub struct CourseToml {
     /// Full course name
     pub name: String,
 
+    /// Stable ID, recognises the course after its folder was renamed or moved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+
     /// ECTS credits
     pub ects: i32,
 
@@ -54,6 +58,10 @@ pub struct CourseToml {
     #[serde(default)]
     pub is_dropped: bool,
 
+    /// Whether the course folder was moved to the archive
+    #[serde(default)]
+    pub is_archived: bool,
+
     /// Whether this course has a git repository
     #[serde(default)]
     pub has_git_repo: bool,
@@ -62,10 +70,20 @@ pub struct CourseToml {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub git_remote_url: Option<String>,
 
-    /// Course-specific metadata (for custom extensions)
-    #[serde(flatten)]
+    /// Exercise folder names, e.g. "ex{n:02d}" (detected from the folders if unset)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub metadata: Option<serde_json::Value>,
+    pub exercise_naming: Option<String>,
+
+    /// Assignment file names inside an exercise folder, e.g. "sheet{n}.pdf"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exercise_file_naming: Option<String>,
+
+    /// Course-specific metadata (for custom extensions)
+    ///
+    /// Collects all keys not listed above, so hand-added keys survive when
+    /// the file is rewritten. Kept as TOML values to preserve dates and times.
+    #[serde(flatten, default)]
+    pub metadata: toml::Table,
 }
 
 impl CourseToml {
@@ -74,6 +92,7 @@ impl CourseToml {
         Self {
             short_name,
             name,
+            uid: None,
             ects,
             lecturer: None,
             lecturer_email: None,
@@ -85,9 +104,12 @@ impl CourseToml {
             is_external: false,
             original_path: None,
             is_dropped: false,
+            is_archived: false,
             has_git_repo: false,
             git_remote_url: None,
-            metadata: None,
+            exercise_naming: None,
+            exercise_file_naming: None,
+            metadata: toml::Table::new(),
         }
     }
 
@@ -170,14 +192,24 @@ impl CourseToml {
         self
     }
 
+    pub fn with_archived(mut self, is_archived: bool) -> Self {
+        self.is_archived = is_archived;
+        self
+    }
+
     pub fn with_git_repo(mut self, remote_url: impl Into<String>) -> Self {
         self.has_git_repo = true;
         self.git_remote_url = Some(remote_url.into());
         self
     }
 
-    pub fn with_metadata(mut self, metadata: serde_json::Value) -> Self {
-        self.metadata = Some(metadata);
+    pub fn with_uid(mut self, uid: impl Into<String>) -> Self {
+        self.uid = Some(uid.into());
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: toml::Table) -> Self {
+        self.metadata = metadata;
         self
     }
 }
@@ -250,4 +282,26 @@ mod tests {
 
         assert_eq!(course.toml_path(&semester_dir), expected_path);
     }
+
+    #[test]
+    fn test_metadata_round_trip() {
+        let toml_str = r#"
+short_name = "cs101"
+name = "Intro to Algorithms"
+ects = 8
+exam_room = "MI HS1"
+exam_date = 2025-02-10
+weights = [0.4, 0.6]
+
+[custom]
+discord = "https://discord.gg/cs101"
+"#;
+        let course: CourseToml = toml::from_str(toml_str).unwrap();
+        assert_eq!(course.metadata.len(), 4);
+        assert!(course.metadata["exam_date"].is_datetime());
+
+        let reread: CourseToml = toml::from_str(&toml::to_string_pretty(&course).unwrap()).unwrap();
+        assert_eq!(reread.metadata, course.metadata);
+        assert_eq!(reread.ects, 8);
+    }
 }
@@ -2,7 +2,7 @@ pub mod course;
 pub mod semester;
 
 pub use course::CourseToml;
-pub use semester::{SemesterToml, SemesterType};
+pub use semester::SemesterToml;
 
 use crate::error::{MmsError, Result};
 use std::path::Path;
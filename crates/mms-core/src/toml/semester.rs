@@ -1,54 +1,23 @@
 use crate::error::Result;
+use crate::paths;
+use crate::semester::SemesterType;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
-/// Semester type (Bachelor or Master)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum SemesterType {
-    Bachelor,
-    Master,
-}
-
-impl SemesterType {
-    /// Get the short code prefix (b or m)
-    pub fn prefix(&self) -> char {
-        match self {
-            SemesterType::Bachelor => 'b',
-            SemesterType::Master => 'm',
-        }
-    }
-
-    /// Parse from a string (case-insensitive)
-    #[allow(clippy::should_implement_trait)]
-    pub fn from_str(s: &str) -> Option<Self> {
-        match s.to_lowercase().as_str() {
-            "bachelor" | "b" => Some(SemesterType::Bachelor),
-            "master" | "m" => Some(SemesterType::Master),
-            _ => None,
-        }
-    }
-}
-
-impl std::fmt::Display for SemesterType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SemesterType::Bachelor => write!(f, "bachelor"),
-            SemesterType::Master => write!(f, "master"),
-        }
-    }
-}
-
 /// Contents of a `.semester.toml` file
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SemesterToml {
-    /// Semester type (bachelor or master)
+    /// Semester type (bachelor, master, phd, exchange, preparatory or orientation)
     #[serde(rename = "type")]
     pub semester_type: SemesterType,
 
     /// Semester number (e.g., 1, 2, 3...)
     pub number: i64,
 
+    /// Stable ID, recognises the semester after its folder was renamed or moved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+
     /// Start date in German format (DD.MM.YYYY, e.g., "15.01.2024" or "7.8.2003")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub start_date: Option<String>,
@@ -80,6 +49,7 @@ impl SemesterToml {
         Self {
             semester_type,
             number,
+            uid: None,
             start_date: None,
             end_date: None,
             university: None,
@@ -91,7 +61,7 @@ impl SemesterToml {
 
     /// Get the semester code (e.g., "b1", "m2")
     pub fn code(&self) -> String {
-        format!("{}{}", self.semester_type.prefix(), self.number)
+        self.semester_type.code(self.number)
     }
 
     /// Read a semester TOML file from disk
@@ -117,8 +87,22 @@ impl SemesterToml {
     }
 
     /// Get the expected path for this semester's TOML file
-    pub fn toml_path(&self, base_path: &Path) -> PathBuf {
-        base_path.join(self.code()).join(".semester.toml")
+    ///
+    /// The folder is named after `folder_pattern` (`general.semester_folder`).
+    pub fn toml_path(&self, base_path: &Path, folder_pattern: &str) -> PathBuf {
+        base_path
+            .join(paths::semester_folder_name(
+                folder_pattern,
+                self.semester_type,
+                self.number,
+            ))
+            .join(".semester.toml")
+    }
+
+    /// Builder method to set the stable ID
+    pub fn with_uid(mut self, uid: String) -> Self {
+        self.uid = Some(uid);
+        self
     }
 
     /// Builder method to set start date
@@ -163,34 +147,26 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_semester_type_prefix() {
-        assert_eq!(SemesterType::Bachelor.prefix(), 'b');
-        assert_eq!(SemesterType::Master.prefix(), 'm');
+    fn test_semester_code() {
+        let sem = SemesterToml::new(SemesterType::Bachelor, 1);
+        assert_eq!(sem.code(), "b1");
+
+        let sem = SemesterToml::new(SemesterType::Master, 5);
+        assert_eq!(sem.code(), "m5");
     }
 
     #[test]
-    fn test_semester_type_from_str() {
+    fn test_toml_path_follows_folder_pattern() {
+        let sem = SemesterToml::new(SemesterType::Bachelor, 3);
+        let base = Path::new("/uni");
         assert_eq!(
-            SemesterType::from_str("bachelor"),
-            Some(SemesterType::Bachelor)
+            sem.toml_path(base, paths::DEFAULT_SEMESTER_FOLDER),
+            base.join("b3/.semester.toml")
         );
         assert_eq!(
-            SemesterType::from_str("Bachelor"),
-            Some(SemesterType::Bachelor)
+            sem.toml_path(base, "{type}{number:02}"),
+            base.join("b03/.semester.toml")
         );
-        assert_eq!(SemesterType::from_str("b"), Some(SemesterType::Bachelor));
-        assert_eq!(SemesterType::from_str("master"), Some(SemesterType::Master));
-        assert_eq!(SemesterType::from_str("m"), Some(SemesterType::Master));
-        assert_eq!(SemesterType::from_str("invalid"), None);
-    }
-
-    #[test]
-    fn test_semester_code() {
-        let sem = SemesterToml::new(SemesterType::Bachelor, 1);
-        assert_eq!(sem.code(), "b1");
-
-        let sem = SemesterToml::new(SemesterType::Master, 5);
-        assert_eq!(sem.code(), "m5");
     }
 
     #[test]
@@ -1,5 +1,5 @@
 use crate::error::{MmsError, Result};
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 
 /// Validates German date format (DD.MM.YYYY)
 ///
@@ -34,6 +34,34 @@ pub fn validate_date_range(start: &Option<String>, end: &Option<String>) -> Resu
     Ok(())
 }
 
+/// Parse the end of a manual course selection (HH:MM or DD.MM.YYYY HH:MM, local time)
+///
+/// A bare time that has already passed today means tomorrow.
+pub fn parse_until(value: &str, now: NaiveDateTime) -> Result<DateTime<Utc>> {
+    let until = match NaiveTime::parse_from_str(value, "%H:%M") {
+        Ok(time) => {
+            let today = now.date().and_time(time);
+            if today > now {
+                today
+            } else {
+                today + chrono::Duration::days(1)
+            }
+        }
+        Err(_) => NaiveDateTime::parse_from_str(value, "%d.%m.%Y %H:%M").map_err(|_| {
+            MmsError::Other(format!(
+                "Invalid time '{}', expected HH:MM or dd.mm.yyyy HH:MM",
+                value
+            ))
+        })?,
+    };
+
+    chrono::Local
+        .from_local_datetime(&until)
+        .earliest()
+        .map(|until| until.with_timezone(&Utc))
+        .ok_or_else(|| MmsError::Other(format!("Invalid local time '{}'", value)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -55,4 +83,34 @@ mod tests {
         let bad_start = Some("01.05.2025".to_string());
         assert!(validate_date_range(&bad_start, &end).is_err());
     }
+
+    #[test]
+    fn test_parse_until() {
+        let now = NaiveDateTime::parse_from_str("2024-10-07 12:00", "%Y-%m-%d %H:%M").unwrap();
+        let local = |until: DateTime<Utc>| until.with_timezone(&chrono::Local).naive_local();
+        let at = |value: &str| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap();
+
+        // Later today, or tomorrow once the time has passed
+        assert_eq!(
+            local(parse_until("14:00", now).unwrap()),
+            at("2024-10-07 14:00")
+        );
+        assert_eq!(
+            local(parse_until("09:30", now).unwrap()),
+            at("2024-10-08 09:30")
+        );
+        assert_eq!(
+            local(parse_until("12:00", now).unwrap()),
+            at("2024-10-08 12:00")
+        );
+
+        assert_eq!(
+            local(parse_until("10.10.2024 18:00", now).unwrap()),
+            at("2024-10-10 18:00")
+        );
+
+        assert!(parse_until("25:00", now).is_err());
+        assert!(parse_until("tomorrow", now).is_err());
+        assert!(parse_until("10.10.2024", now).is_err());
+    }
 }
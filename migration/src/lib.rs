@@ -1,6 +1,14 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20250101_000001_initial_schema;
+mod m20250131_000001_lecture_schedule;
+mod m20250201_000001_attendance;
+mod m20250202_000001_activation_intervals;
+mod m20250202_000002_interval_heartbeat;
+mod m20250203_000001_switch_history;
+mod m20250204_000001_manual_selection;
+mod m20250205_000001_stable_ids;
+mod m20250206_000001_exercise_naming;
 
 pub struct Migrator;
 
@@ -9,6 +17,14 @@ impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
         vec![
             Box::new(m20250101_000001_initial_schema::Migration),
+            Box::new(m20250131_000001_lecture_schedule::Migration),
+            Box::new(m20250201_000001_attendance::Migration),
+            Box::new(m20250202_000001_activation_intervals::Migration),
+            Box::new(m20250202_000002_interval_heartbeat::Migration),
+            Box::new(m20250203_000001_switch_history::Migration),
+            Box::new(m20250204_000001_manual_selection::Migration),
+            Box::new(m20250205_000001_stable_ids::Migration),
+            Box::new(m20250206_000001_exercise_naming::Migration),
         ]
     }
 }
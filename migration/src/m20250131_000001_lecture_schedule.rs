@@ -0,0 +1,44 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Synced lectures are matched to schedule occurrences by (schedule_id, date)
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Lectures::Table)
+                    .add_column(integer_null(Lectures::ScheduleId)) // NULL for lectures added by hand or synced before
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Lectures::Table)
+                    .drop_column(Lectures::ScheduleId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+// ==========================================
+// Lectures
+// ==========================================
+
+#[derive(DeriveIden)]
+enum Lectures {
+    Table,
+    ScheduleId,
+}
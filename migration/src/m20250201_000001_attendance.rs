@@ -0,0 +1,172 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        create_attendance_table(manager).await?;
+        create_attendance_requirements_table(manager).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(AttendanceRequirements::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(Attendance::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Courses {
+    Table,
+    Id,
+}
+
+// ==========================================
+// Attendance Tables
+// ==========================================
+
+#[derive(DeriveIden)]
+enum Attendance {
+    Table,
+    Id,
+    CourseId,
+    ScheduleId,
+    ScheduleType,
+    Date,
+    StartTime,
+    EndTime,
+    Status,
+    IsAuto,
+    Notes,
+    CreatedAt,
+    UpdatedAt,
+}
+
+async fn create_attendance_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(Attendance::Table)
+                .if_not_exists()
+                .col(pk_auto(Attendance::Id))
+                .col(integer(Attendance::CourseId))
+                .col(integer_null(Attendance::ScheduleId))
+                .col(string(Attendance::ScheduleType))
+                .col(string(Attendance::Date))
+                .col(string(Attendance::StartTime))
+                .col(string(Attendance::EndTime))
+                .col(string(Attendance::Status)) // 'attended', 'missed', 'excused'
+                .col(boolean(Attendance::IsAuto).default(false))
+                .col(string_null(Attendance::Notes))
+                .col(timestamp(Attendance::CreatedAt).default(Expr::current_timestamp()))
+                .col(timestamp(Attendance::UpdatedAt).default(Expr::current_timestamp()))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(Attendance::Table, Attendance::CourseId)
+                        .to(Courses::Table, Courses::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx_attendance_course")
+                .table(Attendance::Table)
+                .col(Attendance::CourseId)
+                .to_owned(),
+        )
+        .await?;
+
+    // One record per occurrence
+    manager
+        .create_index(
+            Index::create()
+                .name("idx_attendance_course_occurrence")
+                .table(Attendance::Table)
+                .col(Attendance::CourseId)
+                .col(Attendance::Date)
+                .col(Attendance::StartTime)
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[derive(DeriveIden)]
+enum AttendanceRequirements {
+    Table,
+    Id,
+    CourseId,
+    ScheduleType,
+    RequiredPercentage,
+    CreatedAt,
+    UpdatedAt,
+}
+
+async fn create_attendance_requirements_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(AttendanceRequirements::Table)
+                .if_not_exists()
+                .col(pk_auto(AttendanceRequirements::Id))
+                .col(integer(AttendanceRequirements::CourseId))
+                .col(string(AttendanceRequirements::ScheduleType))
+                .col(double(AttendanceRequirements::RequiredPercentage))
+                .col(
+                    timestamp(AttendanceRequirements::CreatedAt).default(Expr::current_timestamp()),
+                )
+                .col(
+                    timestamp(AttendanceRequirements::UpdatedAt).default(Expr::current_timestamp()),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(
+                            AttendanceRequirements::Table,
+                            AttendanceRequirements::CourseId,
+                        )
+                        .to(Courses::Table, Courses::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx_attendance_requirements_course_type")
+                .table(AttendanceRequirements::Table)
+                .col(AttendanceRequirements::CourseId)
+                .col(AttendanceRequirements::ScheduleType)
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+    Ok(())
+}
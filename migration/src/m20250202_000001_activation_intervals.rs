@@ -0,0 +1,90 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        create_activation_intervals_table(manager).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ActivationIntervals::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Courses {
+    Table,
+    Id,
+}
+
+// ==========================================
+// Study Time Tables
+// ==========================================
+
+#[derive(DeriveIden)]
+enum ActivationIntervals {
+    Table,
+    Id,
+    CourseId,
+    StartedAt,
+    EndedAt,
+    CreatedAt,
+}
+
+async fn create_activation_intervals_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(ActivationIntervals::Table)
+                .if_not_exists()
+                .col(pk_auto(ActivationIntervals::Id))
+                .col(integer(ActivationIntervals::CourseId))
+                .col(timestamp(ActivationIntervals::StartedAt))
+                .col(timestamp_null(ActivationIntervals::EndedAt)) // NULL while the course is active
+                .col(timestamp(ActivationIntervals::CreatedAt).default(Expr::current_timestamp()))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(ActivationIntervals::Table, ActivationIntervals::CourseId)
+                        .to(Courses::Table, Courses::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx_activation_intervals_course")
+                .table(ActivationIntervals::Table)
+                .col(ActivationIntervals::CourseId)
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx_activation_intervals_started_at")
+                .table(ActivationIntervals::Table)
+                .col(ActivationIntervals::StartedAt)
+                .to_owned(),
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Open intervals only count up to the daemon's last heartbeat
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ActivationIntervals::Table)
+                    .add_column(timestamp_null(ActivationIntervals::LastSeenAt)) // NULL = never seen by the daemon
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ActivationIntervals::Table)
+                    .drop_column(ActivationIntervals::LastSeenAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+// ==========================================
+// Activation Intervals
+// ==========================================
+
+#[derive(DeriveIden)]
+enum ActivationIntervals {
+    Table,
+    LastSeenAt,
+}
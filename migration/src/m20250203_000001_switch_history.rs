@@ -0,0 +1,86 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        create_switch_history_table(manager).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(SwitchHistory::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Courses {
+    Table,
+    Id,
+}
+
+// ==========================================
+// History Tables
+// ==========================================
+
+#[derive(DeriveIden)]
+enum SwitchHistory {
+    Table,
+    Id,
+    FromCourseId,
+    ToCourseId,
+    Reason,
+    SwitchedAt,
+}
+
+async fn create_switch_history_table(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(SwitchHistory::Table)
+                .if_not_exists()
+                .col(pk_auto(SwitchHistory::Id))
+                .col(integer_null(SwitchHistory::FromCourseId)) // NULL if no course was active
+                .col(integer_null(SwitchHistory::ToCourseId)) // NULL if no course is active afterwards
+                .col(string(SwitchHistory::Reason)) // 'schedule', 'manual', 'override'
+                .col(timestamp(SwitchHistory::SwitchedAt).default(Expr::current_timestamp()))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(SwitchHistory::Table, SwitchHistory::FromCourseId)
+                        .to(Courses::Table, Courses::Id)
+                        .on_delete(ForeignKeyAction::SetNull),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(SwitchHistory::Table, SwitchHistory::ToCourseId)
+                        .to(Courses::Table, Courses::Id)
+                        .on_delete(ForeignKeyAction::SetNull),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .name("idx_switch_history_switched_at")
+                .table(SwitchHistory::Table)
+                .col(SwitchHistory::SwitchedAt)
+                .to_owned(),
+        )
+        .await?;
+
+    Ok(())
+}
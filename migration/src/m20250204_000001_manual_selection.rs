@@ -0,0 +1,62 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only supports one column per ALTER TABLE
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ActiveCourse::Table)
+                    .add_column(boolean(ActiveCourse::IsManual).default(false)) // Set with `mms course set-active`
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ActiveCourse::Table)
+                    .add_column(timestamp_null(ActiveCourse::ManualUntil)) // NULL = until the next scheduled slot
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ActiveCourse::Table)
+                    .drop_column(ActiveCourse::ManualUntil)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ActiveCourse::Table)
+                    .drop_column(ActiveCourse::IsManual)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+// ==========================================
+// Active Course
+// ==========================================
+
+#[derive(DeriveIden)]
+enum ActiveCourse {
+    Table,
+    IsManual,
+    ManualUntil,
+}
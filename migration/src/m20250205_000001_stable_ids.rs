@@ -0,0 +1,109 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Stable IDs are also written to .semester.toml / .course.toml to recognise moved folders
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Semesters::Table)
+                    .add_column(string_null(Semesters::Uid)) // NULL for semesters created before stable IDs
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Courses::Table)
+                    .add_column(string_null(Courses::Uid)) // NULL for courses created before stable IDs
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_semesters_uid")
+                    .table(Semesters::Table)
+                    .col(Semesters::Uid)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_courses_uid")
+                    .table(Courses::Table)
+                    .col(Courses::Uid)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_courses_uid")
+                    .table(Courses::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_semesters_uid")
+                    .table(Semesters::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Courses::Table)
+                    .drop_column(Courses::Uid)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Semesters::Table)
+                    .drop_column(Semesters::Uid)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+// ==========================================
+// Semesters
+// ==========================================
+
+#[derive(DeriveIden)]
+enum Semesters {
+    Table,
+    Uid,
+}
+
+// ==========================================
+// Courses
+// ==========================================
+
+#[derive(DeriveIden)]
+enum Courses {
+    Table,
+    Uid,
+}
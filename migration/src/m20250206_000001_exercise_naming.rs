@@ -0,0 +1,62 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only supports one column per ALTER TABLE
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Courses::Table)
+                    .add_column(string_null(Courses::ExerciseNaming)) // e.g. "ex{n:02d}", NULL = detect from folders
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Courses::Table)
+                    .add_column(string_null(Courses::ExerciseFileNaming)) // e.g. "sheet{n}.pdf", inside the exercise folder
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Courses::Table)
+                    .drop_column(Courses::ExerciseFileNaming)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Courses::Table)
+                    .drop_column(Courses::ExerciseNaming)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+// ==========================================
+// Courses
+// ==========================================
+
+#[derive(DeriveIden)]
+enum Courses {
+    Table,
+    ExerciseNaming,
+    ExerciseFileNaming,
+}